@@ -0,0 +1,484 @@
+//! Programmatic AST construction.
+
+use super::*;
+use num_bigint::BigInt;
+use std::sync::Arc;
+
+/// Builder methods for constructing AST nodes without going through source code, for example in
+/// code generators.
+///
+/// Nodes are created with [`Span::DUMMY`] spans and no doc-comments. Names are interned into
+/// [`Symbol`]s, so the session globals must be set when calling these methods, for example by
+/// calling them inside of `Session::enter`.
+///
+/// Nodes that have many optional parts, like [`FunctionHeader`] and [`VariableDefinition`], are
+/// created with their defaults and can then be modified through their public fields:
+///
+/// ```ignore
+/// let mut header = arena.function_header("inc", vec![arena.var(uint, Some("x"))], vec![arena.var(uint, None)]);
+/// header.visibility = Some(Visibility::Public);
+/// header.state_mutability = StateMutability::Pure;
+/// let ret = arena.binary_expr(arena.ident_expr("x"), BinOpKind::Add, arena.number_expr(1));
+/// let f = arena.function(FunctionKind::Function, header, Some(vec![arena.return_stmt(Some(ret))]));
+/// let c = arena.contract(ContractKind::Contract, "C", &[], vec![f]);
+/// let source_unit = arena.source_unit(vec![c]);
+/// ```
+impl Arena {
+    /// Allocates a list of nodes.
+    pub fn alloc_list<T>(&self, values: Vec<T>) -> Box<'_, [T]> {
+        self.bump.alloc_vec(values)
+    }
+
+    /// Creates an identifier with a dummy span.
+    pub fn ident(&self, name: &str) -> Ident {
+        Ident::from_str(name)
+    }
+
+    /// Creates a path from a dot-separated string, such as `Lib.Struct`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is empty.
+    pub fn path(&self, path: &str) -> AstPath<'_> {
+        assert!(!path.is_empty(), "empty path");
+        PathSlice::from_mut_slice(self.bump.alloc_from_iter(path.split('.').map(Ident::from_str)))
+    }
+
+    /// Creates a list of unnamed call arguments.
+    pub fn call_args<'ast>(&'ast self, args: Vec<Box<'ast, Expr<'ast>>>) -> CallArgs<'ast> {
+        CallArgs::Unnamed(self.alloc_list(args))
+    }
+
+    /// Creates a source unit.
+    pub fn source_unit<'ast>(&'ast self, items: Vec<Item<'ast>>) -> SourceUnit<'ast> {
+        SourceUnit::new(self.alloc_list(items))
+    }
+
+    // Types.
+
+    /// Creates a type.
+    pub fn ty<'ast>(&'ast self, kind: TypeKind<'ast>) -> Type<'ast> {
+        Type { span: Span::DUMMY, kind }
+    }
+
+    /// Creates an elementary type: `uint256`, `address`.
+    pub fn elementary_ty(&self, ty: ElementaryType) -> Type<'_> {
+        self.ty(TypeKind::Elementary(ty))
+    }
+
+    /// Creates a user-defined type from a dot-separated path: `IERC20`, `Lib.Struct`.
+    pub fn custom_ty(&self, path: &str) -> Type<'_> {
+        self.ty(TypeKind::Custom(self.path(path)))
+    }
+
+    /// Creates an array type: `T[]`, `T[size]`.
+    pub fn array_ty<'ast>(
+        &'ast self,
+        element: Type<'ast>,
+        size: Option<Box<'ast, Expr<'ast>>>,
+    ) -> Type<'ast> {
+        self.ty(TypeKind::Array(self.alloc(TypeArray { element, size })))
+    }
+
+    /// Creates a mapping type: `mapping(K => V)`.
+    pub fn mapping_ty<'ast>(&'ast self, key: Type<'ast>, value: Type<'ast>) -> Type<'ast> {
+        let mapping = TypeMapping { key, key_name: None, value, value_name: None };
+        self.ty(TypeKind::Mapping(self.alloc(mapping)))
+    }
+
+    // Expressions.
+
+    /// Creates an expression.
+    pub fn expr<'ast>(&'ast self, kind: ExprKind<'ast>) -> Box<'ast, Expr<'ast>> {
+        self.alloc(Expr { span: Span::DUMMY, kind })
+    }
+
+    /// Creates a literal.
+    ///
+    /// The literal's [`symbol`](Lit::symbol), used when printing it, is derived from `kind`.
+    /// Rational literals are written as a parenthesized division, like `(1 / 2)`.
+    pub fn lit(&self, kind: LitKind) -> &mut Lit {
+        let symbol = match &kind {
+            LitKind::Str(_, value) => Symbol::intern(&String::from_utf8_lossy(value)),
+            LitKind::Number(n) => Symbol::intern(&n.to_string()),
+            LitKind::Rational(r) => Symbol::intern(&format!("({} / {})", r.numer(), r.denom())),
+            LitKind::Address(address) => Symbol::intern(&address.to_string()),
+            LitKind::Bool(b) => Symbol::intern(&b.to_string()),
+            LitKind::Err(_) => Symbol::DUMMY,
+        };
+        self.literals.alloc(Lit { span: Span::DUMMY, symbol, kind })
+    }
+
+    /// Creates a literal expression.
+    pub fn lit_expr(&self, kind: LitKind) -> Box<'_, Expr<'_>> {
+        self.expr(ExprKind::Lit(self.lit(kind), None))
+    }
+
+    /// Creates a number literal expression: `42`.
+    pub fn number_expr(&self, value: impl Into<BigInt>) -> Box<'_, Expr<'_>> {
+        self.lit_expr(LitKind::Number(value.into()))
+    }
+
+    /// Creates a boolean literal expression: `true`.
+    pub fn bool_expr(&self, value: bool) -> Box<'_, Expr<'_>> {
+        self.lit_expr(LitKind::Bool(value))
+    }
+
+    /// Creates a string literal expression: `"foo"`.
+    pub fn str_expr(&self, value: &str) -> Box<'_, Expr<'_>> {
+        self.lit_expr(LitKind::Str(StrKind::Str, Arc::from(value.as_bytes())))
+    }
+
+    /// Creates an identifier expression: `foo`.
+    pub fn ident_expr(&self, name: &str) -> Box<'_, Expr<'_>> {
+        self.alloc(Expr::from_ident(self.ident(name)))
+    }
+
+    /// Creates a type expression: `uint256`, in `uint256(x)`.
+    pub fn type_expr<'ast>(&'ast self, ty: Type<'ast>) -> Box<'ast, Expr<'ast>> {
+        self.alloc(Expr::from_ty(ty))
+    }
+
+    /// Creates a member access expression: `base.member`.
+    pub fn member_expr<'ast>(
+        &'ast self,
+        base: Box<'ast, Expr<'ast>>,
+        member: &str,
+    ) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::Member(base, self.ident(member)))
+    }
+
+    /// Creates an index expression: `base[index]`.
+    pub fn index_expr<'ast>(
+        &'ast self,
+        base: Box<'ast, Expr<'ast>>,
+        index: Box<'ast, Expr<'ast>>,
+    ) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::Index(base, IndexKind::Index(Some(index))))
+    }
+
+    /// Creates a function call expression with unnamed arguments: `callee(args...)`.
+    pub fn call_expr<'ast>(
+        &'ast self,
+        callee: Box<'ast, Expr<'ast>>,
+        args: Vec<Box<'ast, Expr<'ast>>>,
+    ) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::Call(callee, self.call_args(args)))
+    }
+
+    /// Creates a `new` expression: `new T`.
+    pub fn new_expr<'ast>(&'ast self, ty: Type<'ast>) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::New(ty))
+    }
+
+    /// Creates a binary operation expression: `lhs + rhs`.
+    pub fn binary_expr<'ast>(
+        &'ast self,
+        lhs: Box<'ast, Expr<'ast>>,
+        op: BinOpKind,
+        rhs: Box<'ast, Expr<'ast>>,
+    ) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::Binary(lhs, BinOp { span: Span::DUMMY, kind: op }, rhs))
+    }
+
+    /// Creates a unary operation expression: `!expr`, `expr++`.
+    pub fn unary_expr<'ast>(
+        &'ast self,
+        op: UnOpKind,
+        expr: Box<'ast, Expr<'ast>>,
+    ) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::Unary(UnOp { span: Span::DUMMY, kind: op }, expr))
+    }
+
+    /// Creates an assignment expression: `lhs = rhs`, or `lhs += rhs` if `op` is `Some`.
+    pub fn assign_expr<'ast>(
+        &'ast self,
+        lhs: Box<'ast, Expr<'ast>>,
+        op: Option<BinOpKind>,
+        rhs: Box<'ast, Expr<'ast>>,
+    ) -> Box<'ast, Expr<'ast>> {
+        let op = op.map(|kind| BinOp { span: Span::DUMMY, kind });
+        self.expr(ExprKind::Assign(lhs, op, rhs))
+    }
+
+    /// Creates a ternary expression: `cond ? true_ : false_`.
+    pub fn ternary_expr<'ast>(
+        &'ast self,
+        cond: Box<'ast, Expr<'ast>>,
+        true_: Box<'ast, Expr<'ast>>,
+        false_: Box<'ast, Expr<'ast>>,
+    ) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::Ternary(cond, true_, false_))
+    }
+
+    /// Creates a tuple expression: `(a, b)`.
+    pub fn tuple_expr<'ast>(
+        &'ast self,
+        exprs: Vec<Box<'ast, Expr<'ast>>>,
+    ) -> Box<'ast, Expr<'ast>> {
+        self.expr(ExprKind::Tuple(self.bump.alloc_from_iter(exprs.into_iter().map(Some))))
+    }
+
+    // Statements.
+
+    /// Creates a statement.
+    pub fn stmt<'ast>(&'ast self, kind: StmtKind<'ast>) -> Stmt<'ast> {
+        Stmt { docs: Default::default(), span: Span::DUMMY, kind }
+    }
+
+    /// Creates a block.
+    pub fn block<'ast>(&'ast self, stmts: Vec<Stmt<'ast>>) -> Block<'ast> {
+        self.alloc_list(stmts)
+    }
+
+    /// Creates a block statement: `{ stmts... }`.
+    pub fn block_stmt<'ast>(&'ast self, stmts: Vec<Stmt<'ast>>) -> Stmt<'ast> {
+        self.stmt(StmtKind::Block(self.block(stmts)))
+    }
+
+    /// Creates an expression statement: `expr;`.
+    pub fn expr_stmt<'ast>(&'ast self, expr: Box<'ast, Expr<'ast>>) -> Stmt<'ast> {
+        self.stmt(StmtKind::Expr(expr))
+    }
+
+    /// Creates a variable declaration statement: `uint256 x = 1;`.
+    pub fn decl_stmt<'ast>(&'ast self, var: VariableDefinition<'ast>) -> Stmt<'ast> {
+        self.stmt(StmtKind::DeclSingle(self.alloc(var)))
+    }
+
+    /// Creates a return statement: `return expr;`.
+    pub fn return_stmt<'ast>(&'ast self, expr: Option<Box<'ast, Expr<'ast>>>) -> Stmt<'ast> {
+        self.stmt(StmtKind::Return(expr))
+    }
+
+    /// Creates an if statement: `if (cond) then_ else else_`.
+    pub fn if_stmt<'ast>(
+        &'ast self,
+        cond: Box<'ast, Expr<'ast>>,
+        then_: Stmt<'ast>,
+        else_: Option<Stmt<'ast>>,
+    ) -> Stmt<'ast> {
+        let else_ = else_.map(|stmt| self.alloc(stmt));
+        self.stmt(StmtKind::If(cond, self.alloc(then_), else_))
+    }
+
+    /// Creates a while statement: `while (cond) body`.
+    pub fn while_stmt<'ast>(
+        &'ast self,
+        cond: Box<'ast, Expr<'ast>>,
+        body: Stmt<'ast>,
+    ) -> Stmt<'ast> {
+        self.stmt(StmtKind::While(cond, self.alloc(body)))
+    }
+
+    /// Creates an emit statement: `emit Event(args...);`.
+    pub fn emit_stmt<'ast>(
+        &'ast self,
+        event: &str,
+        args: Vec<Box<'ast, Expr<'ast>>>,
+    ) -> Stmt<'ast> {
+        self.stmt(StmtKind::Emit(self.path(event), self.call_args(args)))
+    }
+
+    /// Creates a revert statement: `revert Error(args...);`.
+    pub fn revert_stmt<'ast>(
+        &'ast self,
+        error: &str,
+        args: Vec<Box<'ast, Expr<'ast>>>,
+    ) -> Stmt<'ast> {
+        self.stmt(StmtKind::Revert(self.path(error), self.call_args(args)))
+    }
+
+    // Items.
+
+    /// Creates an item.
+    pub fn item<'ast>(&'ast self, kind: ItemKind<'ast>) -> Item<'ast> {
+        Item { docs: Default::default(), span: Span::DUMMY, kind }
+    }
+
+    /// Creates a variable definition without any specifiers or initializer: `uint256 x`.
+    ///
+    /// This is used for state variables, local variables, parameters and struct fields.
+    pub fn var<'ast>(&'ast self, ty: Type<'ast>, name: Option<&str>) -> VariableDefinition<'ast> {
+        VariableDefinition {
+            span: Span::DUMMY,
+            ty,
+            visibility: None,
+            mutability: None,
+            data_location: None,
+            override_: None,
+            indexed: false,
+            name: name.map(Ident::from_str),
+            initializer: None,
+        }
+    }
+
+    /// Creates a contract, abstract contract, interface or library: `contract C is Bases { body }`.
+    pub fn contract<'ast>(
+        &'ast self,
+        kind: ContractKind,
+        name: &str,
+        bases: &[&str],
+        body: Vec<Item<'ast>>,
+    ) -> Item<'ast> {
+        let bases = bases
+            .iter()
+            .map(|&base| Modifier { name: self.path(base), arguments: CallArgs::empty() });
+        let bases = self.bump.alloc_from_iter(bases);
+        let contract = ItemContract {
+            kind,
+            name: self.ident(name),
+            bases,
+            layout: None,
+            body: self.alloc_list(body),
+        };
+        self.item(ItemKind::Contract(contract))
+    }
+
+    /// Creates a function header with the default specifiers: no visibility, non-payable, and no
+    /// modifiers.
+    pub fn function_header<'ast>(
+        &'ast self,
+        name: &str,
+        parameters: Vec<VariableDefinition<'ast>>,
+        returns: Vec<VariableDefinition<'ast>>,
+    ) -> FunctionHeader<'ast> {
+        FunctionHeader {
+            name: Some(self.ident(name)),
+            parameters: self.alloc_list(parameters),
+            returns: self.alloc_list(returns),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a function, constructor, modifier, fallback or receive function.
+    ///
+    /// `body` is `None` for functions without an implementation.
+    pub fn function<'ast>(
+        &'ast self,
+        kind: FunctionKind,
+        header: FunctionHeader<'ast>,
+        body: Option<Vec<Stmt<'ast>>>,
+    ) -> Item<'ast> {
+        let body = body.map(|stmts| self.block(stmts));
+        self.item(ItemKind::Function(ItemFunction { kind, header, body }))
+    }
+
+    /// Creates a state variable or a file-level constant.
+    pub fn variable<'ast>(&'ast self, var: VariableDefinition<'ast>) -> Item<'ast> {
+        self.item(ItemKind::Variable(var))
+    }
+
+    /// Creates a struct: `struct S { fields... }`.
+    pub fn struct_<'ast>(
+        &'ast self,
+        name: &str,
+        fields: Vec<VariableDefinition<'ast>>,
+    ) -> Item<'ast> {
+        let fields = self.alloc_list(fields);
+        self.item(ItemKind::Struct(ItemStruct { name: self.ident(name), fields }))
+    }
+
+    /// Creates an enum: `enum E { variants... }`.
+    pub fn enum_(&self, name: &str, variants: &[&str]) -> Item<'_> {
+        let variants = self.bump.alloc_from_iter(variants.iter().map(|&v| self.ident(v)));
+        self.item(ItemKind::Enum(ItemEnum { name: self.ident(name), variants }))
+    }
+
+    /// Creates an event: `event E(parameters...);`.
+    pub fn event<'ast>(
+        &'ast self,
+        name: &str,
+        parameters: Vec<VariableDefinition<'ast>>,
+    ) -> Item<'ast> {
+        let parameters = self.alloc_list(parameters);
+        self.item(ItemKind::Event(ItemEvent {
+            name: self.ident(name),
+            parameters,
+            anonymous: false,
+        }))
+    }
+
+    /// Creates a custom error: `error E(parameters...);`.
+    pub fn error<'ast>(
+        &'ast self,
+        name: &str,
+        parameters: Vec<VariableDefinition<'ast>>,
+    ) -> Item<'ast> {
+        let parameters = self.alloc_list(parameters);
+        self.item(ItemKind::Error(ItemError { name: self.ident(name), parameters }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::print::{PrintConfig, Printer};
+
+    #[test]
+    fn build_and_print() {
+        solar_interface::enter(|| {
+            let arena = Arena::new();
+            let uint = || arena.elementary_ty(ElementaryType::UInt(TypeSize::new(32).unwrap()));
+
+            let mut counter = arena.var(uint(), Some("counter"));
+            counter.visibility = Some(Visibility::Public);
+            let event = arena.event("Incremented", vec![arena.var(uint(), Some("by"))]);
+
+            let mut header =
+                arena.function_header("inc", vec![arena.var(uint(), Some("by"))], vec![]);
+            header.visibility = Some(Visibility::External);
+            let check =
+                arena.binary_expr(arena.ident_expr("by"), BinOpKind::Gt, arena.number_expr(0));
+            let require = arena.call_expr(arena.ident_expr("require"), vec![check]);
+            let add = arena.assign_expr(
+                arena.ident_expr("counter"),
+                Some(BinOpKind::Add),
+                arena.ident_expr("by"),
+            );
+            let body = vec![
+                arena.expr_stmt(require),
+                arena.expr_stmt(add),
+                arena.emit_stmt("Incremented", vec![arena.ident_expr("by")]),
+            ];
+            let inc = arena.function(FunctionKind::Function, header, Some(body));
+
+            let contract = arena.contract(
+                ContractKind::Contract,
+                "Counter",
+                &["Base"],
+                vec![arena.variable(counter), event, inc],
+            );
+            let source_unit = arena.source_unit(vec![contract]);
+
+            let printed = Printer::new(PrintConfig::default()).print_source_unit(&source_unit);
+            let expected = "\
+contract Counter is Base {
+    uint256 public counter;
+    event Incremented(uint256 by);
+
+    function inc(uint256 by) external {
+        require(by > 0);
+        counter += by;
+        emit Incremented(by);
+    }
+}
+";
+            assert_eq!(printed, expected);
+        });
+    }
+
+    #[test]
+    fn parenthesize() {
+        solar_interface::enter(|| {
+            let arena = Arena::new();
+            let sum =
+                arena.binary_expr(arena.ident_expr("a"), BinOpKind::Add, arena.number_expr(1));
+            let product = arena.binary_expr(sum, BinOpKind::Mul, arena.ident_expr("b"));
+            let member = arena.member_expr(product, "c");
+            let stmt = arena.return_stmt(Some(member));
+            let printed = Printer::new(PrintConfig::default()).print_stmt(&stmt);
+            assert_eq!(printed, "return ((a + 1) * b).c;");
+        });
+    }
+}
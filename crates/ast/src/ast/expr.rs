@@ -174,6 +174,25 @@ impl BinOpKind {
         }
     }
 
+    /// Returns the precedence of the operator. Operators with higher precedence bind more tightly.
+    ///
+    /// All operators are left-associative, except for `**`, which is right-associative.
+    pub const fn precedence(self) -> u8 {
+        match self {
+            Self::Or => 4,
+            Self::And => 5,
+            Self::Eq | Self::Ne => 6,
+            Self::Lt | Self::Le | Self::Gt | Self::Ge => 7,
+            Self::BitOr => 8,
+            Self::BitXor => 9,
+            Self::BitAnd => 10,
+            Self::Shl | Self::Shr | Self::Sar => 11,
+            Self::Add | Self::Sub => 12,
+            Self::Mul | Self::Div | Self::Rem => 13,
+            Self::Pow => 14,
+        }
+    }
+
     /// Returns `true` if the operator is able to be used in an assignment.
     pub const fn assignable(self) -> bool {
         // https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.expression
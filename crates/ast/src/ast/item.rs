@@ -342,9 +342,20 @@ pub struct ItemContract<'ast> {
     pub kind: ContractKind,
     pub name: Ident,
     pub bases: Box<'ast, [Modifier<'ast>]>,
+    pub layout: Option<StorageLayoutSpecifier<'ast>>,
     pub body: Box<'ast, [Item<'ast>]>,
 }
 
+/// A storage layout specifier: `layout at <slot>`.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.storageLayoutSpecifier>
+#[derive(Debug)]
+pub struct StorageLayoutSpecifier<'ast> {
+    pub span: Span,
+    /// The base slot expression.
+    pub slot: Box<'ast, Expr<'ast>>,
+}
+
 /// The kind of contract.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumIs)]
 pub enum ContractKind {
@@ -6,6 +6,8 @@ use std::fmt;
 pub use crate::token::CommentKind;
 pub use solar_interface::{Ident, Span, Symbol};
 
+mod build;
+
 mod expr;
 pub use expr::*;
 
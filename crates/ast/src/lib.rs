@@ -10,5 +10,6 @@ pub use bumpalo;
 pub use solar_interface as interface;
 
 pub mod ast;
+pub mod print;
 pub mod token;
 pub mod visit;
@@ -0,0 +1,65 @@
+use solar_interface::BytePos;
+
+/// The position of a [`Comment`] relative to the surrounding code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CommentStyle {
+    /// No code on either side of the comment: it is on its own line(s).
+    Isolated,
+    /// Code to the left of the comment, and no code to the right of it.
+    Trailing,
+    /// Code to the right of the comment: `/* a */ b`.
+    Mixed,
+    /// An empty line, used to preserve blank lines between statements and items.
+    BlankLine,
+}
+
+/// A comment, or a blank line, in source code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comment {
+    /// The position of the comment.
+    pub style: CommentStyle,
+    /// The lines of the comment, including the `//`, `/*`, and `*/` delimiters.
+    ///
+    /// The indentation of the lines following the first one is relative to the column of the
+    /// first one. Empty for [`CommentStyle::BlankLine`].
+    pub lines: Vec<String>,
+    /// The start of the comment in the source map.
+    pub pos: BytePos,
+}
+
+impl Comment {
+    /// Returns `true` if this is a line comment, `// ...`.
+    pub fn is_line(&self) -> bool {
+        self.lines.first().is_some_and(|line| line.starts_with("//"))
+    }
+}
+
+/// A cursor over the comments of a source file.
+#[derive(Debug, Default)]
+pub(super) struct Comments {
+    comments: Vec<Comment>,
+    next: usize,
+}
+
+impl Comments {
+    pub(super) fn new(comments: Vec<Comment>) -> Self {
+        Self { comments, next: 0 }
+    }
+
+    /// Returns the next comment without consuming it.
+    pub(super) fn peek(&self) -> Option<&Comment> {
+        self.comments.get(self.next)
+    }
+
+    /// Moves the cursor to the first comment that starts at or after `pos`.
+    pub(super) fn seek(&mut self, pos: BytePos) {
+        self.next = self.comments.partition_point(|c| c.pos < pos);
+    }
+
+    /// Consumes and returns the next comment if it starts before `pos`.
+    pub(super) fn next_before(&mut self, pos: BytePos) -> Option<Comment> {
+        let comment = self.comments.get(self.next).filter(|c| c.pos < pos)?.clone();
+        self.next += 1;
+        Some(comment)
+    }
+}
@@ -0,0 +1,386 @@
+use super::{
+    pp::{docs, Doc},
+    Printer,
+};
+use crate::ast::{self, ElementaryType, LitKind, StrKind};
+use solar_interface::{config::QuoteStyle, BytePos};
+use std::fmt::Write;
+
+impl Printer<'_> {
+    pub(super) fn expr(&mut self, expr: &ast::Expr<'_>) -> Doc {
+        let comments = self.leading_comments(expr.span.lo());
+        let kind = match &expr.kind {
+            ast::ExprKind::Array(exprs) => self.comma_list(
+                "[",
+                "]",
+                exprs,
+                Some(expr.span.hi()),
+                false,
+                |e| Some(e.span),
+                |this, e| this.expr(e),
+            ),
+            ast::ExprKind::Assign(lhs, op, rhs) => {
+                let op = match op {
+                    Some(op) => format!(" {}= ", op.kind.to_str()),
+                    None => " = ".to_string(),
+                };
+                let lhs = self.expr_prec(lhs, prec::TERNARY + 1);
+                Doc::concat(docs![lhs, op, self.expr(rhs)])
+            }
+            ast::ExprKind::Binary(..) => self.binary(expr),
+            ast::ExprKind::Call(callee, args) => {
+                let callee = self.expr_prec(callee, prec::PRIMARY);
+                Doc::concat(docs![callee, self.call_args(args, Some(expr.span.hi()))])
+            }
+            ast::ExprKind::CallOptions(callee, options) => Doc::concat(docs![
+                self.expr_prec(callee, prec::PRIMARY),
+                self.named_args(options, Some(expr.span.hi()))
+            ]),
+            ast::ExprKind::Delete(expr) => {
+                Doc::concat(docs!["delete ", self.expr_prec(expr, prec::PREFIX)])
+            }
+            ast::ExprKind::Ident(ident) => ident.to_string().into(),
+            ast::ExprKind::Index(base, index) => {
+                let base = self.expr_prec(base, prec::PRIMARY);
+                let index = match index {
+                    ast::IndexKind::Index(index) => {
+                        Doc::concat(index.iter().map(|index| self.expr(index)).collect())
+                    }
+                    ast::IndexKind::Range(start, end) => {
+                        let start = start.as_ref().map(|start| self.expr(start));
+                        let end = end.as_ref().map(|end| self.expr(end));
+                        Doc::concat(start.into_iter().chain([":".into()]).chain(end).collect())
+                    }
+                };
+                Doc::concat(docs![base, "[", index, "]"])
+            }
+            ast::ExprKind::Lit(lit, denomination) => match denomination {
+                Some(denomination) => format!("{} {denomination}", self.lit(lit)).into(),
+                None => self.lit(lit).into(),
+            },
+            ast::ExprKind::Member(base, member) => {
+                Doc::concat(docs![self.expr_prec(base, prec::PRIMARY), ".", member.to_string()])
+            }
+            ast::ExprKind::New(ty) => Doc::concat(docs!["new ", self.ty(ty)]),
+            ast::ExprKind::Payable(args) => {
+                Doc::concat(docs!["payable", self.call_args(args, Some(expr.span.hi()))])
+            }
+            ast::ExprKind::Ternary(cond, then, else_) => {
+                let cond = self.expr_prec(cond, prec::TERNARY + 1);
+                let then = self.expr(then);
+                let else_ = self.expr(else_);
+                Doc::group(docs![
+                    cond,
+                    Doc::indent(docs![Doc::Line, "? ", then, Doc::Line, ": ", else_]),
+                ])
+            }
+            ast::ExprKind::Tuple(exprs) => self.comma_list(
+                "(",
+                ")",
+                exprs,
+                Some(expr.span.hi()),
+                false,
+                |e| e.as_ref().map(|e| e.span),
+                |this, e| match e {
+                    Some(e) => this.expr(e),
+                    None => Doc::concat(Vec::new()),
+                },
+            ),
+            ast::ExprKind::TypeCall(ty) => Doc::concat(docs!["type(", self.ty(ty), ")"]),
+            ast::ExprKind::Type(ty) => self.ty(ty),
+            ast::ExprKind::Unary(op, operand) => {
+                if op.kind.is_prefix() {
+                    let operand_doc = self.expr_prec(operand, prec::PREFIX);
+                    // Avoid printing `- -x` as `--x`.
+                    let is_minus = |op: ast::UnOpKind| {
+                        matches!(op, ast::UnOpKind::Neg | ast::UnOpKind::PreDec)
+                    };
+                    let space = is_minus(op.kind)
+                        && matches!(&operand.kind, ast::ExprKind::Unary(inner, _) if is_minus(inner.kind));
+                    let op = if space {
+                        format!("{} ", op.kind.to_str())
+                    } else {
+                        op.kind.to_str().to_string()
+                    };
+                    Doc::concat(docs![op, operand_doc])
+                } else {
+                    Doc::concat(docs![self.expr_prec(operand, prec::PRIMARY), op.kind.to_str()])
+                }
+            }
+        };
+        Doc::concat(docs![comments, kind])
+    }
+
+    /// Prints an expression, with parentheses around it if its precedence is lower than `min`.
+    fn expr_prec(&mut self, expr: &ast::Expr<'_>, min: u8) -> Doc {
+        let doc = self.expr(expr);
+        if precedence(expr) < min {
+            Doc::concat(docs!["(", doc, ")"])
+        } else {
+            doc
+        }
+    }
+
+    /// Prints a chain of binary operations with the same operator in a single group, breaking the
+    /// lines after the operators.
+    fn binary(&mut self, expr: &ast::Expr<'_>) -> Doc {
+        let ast::ExprKind::Binary(_, op, _) = &expr.kind else { unreachable!() };
+        // `**` is right-associative, so `(a ** b) ** c` can't be flattened.
+        let is_pow = op.kind == ast::BinOpKind::Pow;
+        let prec = op.kind.precedence();
+        let mut operands = Vec::new();
+        let mut lhs = expr;
+        while let ast::ExprKind::Binary(l, o, r) = &lhs.kind {
+            if o.kind != op.kind || (is_pow && !operands.is_empty()) {
+                break;
+            }
+            operands.push(&**r);
+            lhs = l;
+        }
+        let (lhs_prec, rhs_prec) = if is_pow { (prec + 1, prec) } else { (prec, prec + 1) };
+        let mut rest = Vec::new();
+        let first = self.expr_prec(lhs, lhs_prec);
+        for operand in operands.into_iter().rev() {
+            rest.extend(docs![" ", op.kind.to_str(), Doc::Line, self.expr_prec(operand, rhs_prec)]);
+        }
+        Doc::group(docs![first, Doc::indent(rest)])
+    }
+
+    pub(super) fn call_args(&mut self, args: &ast::CallArgs<'_>, end: Option<BytePos>) -> Doc {
+        match args {
+            ast::CallArgs::Unnamed(exprs) => self.comma_list(
+                "(",
+                ")",
+                exprs,
+                end,
+                false,
+                |e| Some(e.span),
+                |this, e| this.expr(e),
+            ),
+            ast::CallArgs::Named(args) => Doc::concat(docs!["(", self.named_args(args, end), ")"]),
+        }
+    }
+
+    fn named_args(&mut self, args: &[ast::NamedArg<'_>], end: Option<BytePos>) -> Doc {
+        self.comma_list(
+            "{",
+            "}",
+            args,
+            end,
+            self.config.bracket_spacing,
+            |arg| Some(arg.name.span.to(arg.value.span)),
+            |this, arg| {
+                let comments = this.leading_comments(arg.name.span.lo());
+                Doc::concat(docs![comments, format!("{}: ", arg.name), this.expr(arg.value)])
+            },
+        )
+    }
+
+    pub(super) fn ty(&mut self, ty: &ast::Type<'_>) -> Doc {
+        let comments = self.leading_comments(ty.span.lo());
+        let kind = match &ty.kind {
+            ast::TypeKind::Elementary(ty) => elementary_type(*ty).into(),
+            ast::TypeKind::Array(array) => {
+                let element = self.ty(&array.element);
+                let size = array.size.as_ref().map(|size| self.expr(size));
+                Doc::concat(docs![element, "[", Doc::concat(size.into_iter().collect()), "]"])
+            }
+            ast::TypeKind::Function(f) => {
+                let mut out = docs!["function", self.parameter_list(f.parameters, None)];
+                if let Some(visibility) = f.visibility {
+                    out.extend(docs![" ", visibility.to_str()]);
+                }
+                if !f.state_mutability.is_non_payable() {
+                    out.extend(docs![" ", f.state_mutability.to_str()]);
+                }
+                if !f.returns.is_empty() {
+                    out.extend(docs![
+                        " returns ",
+                        self.parameter_list(f.returns, Some(ty.span.hi()))
+                    ]);
+                }
+                Doc::concat(out)
+            }
+            ast::TypeKind::Mapping(mapping) => {
+                let key = self.ty(&mapping.key);
+                let key_name = mapping.key_name.map(|name| format!(" {name}")).unwrap_or_default();
+                let value = self.ty(&mapping.value);
+                let value_name =
+                    mapping.value_name.map(|name| format!(" {name}")).unwrap_or_default();
+                Doc::concat(docs!["mapping(", key, key_name, " => ", value, value_name, ")"])
+            }
+            ast::TypeKind::Custom(path) => path.to_string().into(),
+        };
+        Doc::concat(docs![comments, kind])
+    }
+
+    pub(super) fn parameter_list(
+        &mut self,
+        parameters: &[ast::VariableDefinition<'_>],
+        end: Option<BytePos>,
+    ) -> Doc {
+        self.comma_list(
+            "(",
+            ")",
+            parameters,
+            end,
+            false,
+            |var| Some(var.span),
+            |this, var| this.variable_definition(var),
+        )
+    }
+
+    /// Prints a variable definition without the trailing semicolon.
+    pub(super) fn variable_definition(&mut self, var: &ast::VariableDefinition<'_>) -> Doc {
+        let comments = self.leading_comments(var.span.lo());
+        let mut out = docs![comments, self.ty(&var.ty)];
+        if let Some(visibility) = var.visibility {
+            out.extend(docs![" ", visibility.to_str()]);
+        }
+        if let Some(mutability) = var.mutability {
+            out.extend(docs![" ", mutability.to_str()]);
+        }
+        if let Some(data_location) = var.data_location {
+            out.extend(docs![" ", data_location.to_str()]);
+        }
+        if let Some(override_) = &var.override_ {
+            out.extend(docs![" ", self.override_(override_)]);
+        }
+        if var.indexed {
+            out.push(" indexed".into());
+        }
+        if let Some(name) = var.name {
+            out.push(format!(" {name}").into());
+        }
+        if let Some(initializer) = &var.initializer {
+            out.extend(docs![" = ", self.expr(initializer)]);
+        }
+        Doc::concat(out)
+    }
+
+    pub(super) fn override_(&mut self, override_: &ast::Override<'_>) -> Doc {
+        if override_.paths.is_empty() {
+            return "override".into();
+        }
+        let list = self.comma_list(
+            "(",
+            ")",
+            override_.paths,
+            Some(override_.span.hi()),
+            false,
+            |path| Some(path.span()),
+            |_, path| path.to_string().into(),
+        );
+        Doc::concat(docs!["override", list])
+    }
+
+    /// Prints a literal.
+    pub(super) fn lit(&self, lit: &ast::Lit) -> String {
+        match &lit.kind {
+            LitKind::Str(kind, value) => {
+                if let Some(s) = self.snippet(lit.span).and_then(|s| self.requote_all(s)) {
+                    return s;
+                }
+                let quote = if self.config.quote_style == QuoteStyle::Single { '\'' } else { '"' };
+                escape_str(*kind, value, quote)
+            }
+            LitKind::Number(_) | LitKind::Rational(_) => {
+                self.number(lit.symbol.as_str()).into_owned()
+            }
+            LitKind::Address(_) | LitKind::Bool(_) | LitKind::Err(_) => lit.symbol.to_string(),
+        }
+    }
+
+    /// Prints a string literal that is not unescaped, like import paths.
+    pub(super) fn str_lit(&self, lit: &ast::StrLit) -> String {
+        self.quoted(lit.span, lit.value.as_str(), '"')
+    }
+}
+
+/// Returns the source code of an elementary type.
+pub(super) fn elementary_type(ty: ElementaryType) -> String {
+    match ty {
+        ElementaryType::Address(false) => "address".into(),
+        ElementaryType::Address(true) => "address payable".into(),
+        ElementaryType::Bool => "bool".into(),
+        ElementaryType::String => "string".into(),
+        ElementaryType::Bytes => "bytes".into(),
+        ElementaryType::Fixed(m, n) if m.bytes_raw() == 0 && n.get() == 0 => "fixed".into(),
+        ElementaryType::Fixed(m, n) => format!("fixed{}x{}", m.bits_raw(), n.get()),
+        ElementaryType::UFixed(m, n) if m.bytes_raw() == 0 && n.get() == 0 => "ufixed".into(),
+        ElementaryType::UFixed(m, n) => format!("ufixed{}x{}", m.bits_raw(), n.get()),
+        ElementaryType::Int(size) if size.bytes_raw() == 0 => "int".into(),
+        ElementaryType::Int(size) => format!("int{}", size.bits_raw()),
+        ElementaryType::UInt(size) if size.bytes_raw() == 0 => "uint".into(),
+        ElementaryType::UInt(size) => format!("uint{}", size.bits_raw()),
+        ElementaryType::FixedBytes(size) => format!("bytes{}", size.bytes()),
+    }
+}
+
+/// Escapes the unescaped value of a string literal.
+fn escape_str(kind: StrKind, value: &[u8], quote: char) -> String {
+    let mut s = String::with_capacity(value.len() + 2);
+    match kind {
+        StrKind::Str => {}
+        StrKind::Unicode => s.push_str("unicode"),
+        StrKind::Hex => {
+            s.push_str("hex");
+            s.push(quote);
+            s.push_str(&alloy_primitives::hex::encode(value));
+            s.push(quote);
+            return s;
+        }
+    }
+    s.push(quote);
+    for chunk in value.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '\\' => s.push_str("\\\\"),
+                '\n' => s.push_str("\\n"),
+                '\r' => s.push_str("\\r"),
+                '\t' => s.push_str("\\t"),
+                c if c == quote => {
+                    s.push('\\');
+                    s.push(c);
+                }
+                c if c.is_ascii_graphic() || c == ' ' => s.push(c),
+                c if kind == StrKind::Unicode && !c.is_control() => s.push(c),
+                c => {
+                    let mut buf = [0; 4];
+                    for byte in c.encode_utf8(&mut buf).bytes() {
+                        write!(s, "\\x{byte:02x}").unwrap();
+                    }
+                }
+            }
+        }
+        for byte in chunk.invalid() {
+            write!(s, "\\x{byte:02x}").unwrap();
+        }
+    }
+    s.push(quote);
+    s
+}
+
+/// Precedence levels of expressions, in addition to the ones of the binary operators in
+/// [`ast::BinOpKind::precedence`].
+mod prec {
+    pub(super) const ASSIGN: u8 = 2;
+    pub(super) const TERNARY: u8 = 3;
+    pub(super) const PREFIX: u8 = 15;
+    pub(super) const POSTFIX: u8 = 16;
+    pub(super) const PRIMARY: u8 = 17;
+}
+
+/// Returns the precedence of an expression. An operand with a lower precedence than required by
+/// its parent is parenthesized.
+fn precedence(expr: &ast::Expr<'_>) -> u8 {
+    match &expr.kind {
+        ast::ExprKind::Assign(..) => prec::ASSIGN,
+        ast::ExprKind::Ternary(..) => prec::TERNARY,
+        ast::ExprKind::Binary(_, op, _) => op.kind.precedence(),
+        ast::ExprKind::Delete(_) => prec::PREFIX,
+        ast::ExprKind::Unary(op, _) if op.kind.is_prefix() => prec::PREFIX,
+        ast::ExprKind::Unary(..) => prec::POSTFIX,
+        _ => prec::PRIMARY,
+    }
+}
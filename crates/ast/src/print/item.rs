@@ -0,0 +1,260 @@
+use super::{
+    braces, close_brace, comment_doc,
+    pp::{docs, Doc},
+    CommentStyle, Printer,
+};
+use crate::ast::{self, Span};
+
+impl Printer<'_> {
+    pub(super) fn item(&mut self, item: &ast::Item<'_>) -> Doc {
+        let comments = self.leading_comments(item.span.lo());
+        let docs = self.doc_comments(item.docs);
+        let kind = match &item.kind {
+            ast::ItemKind::Pragma(pragma) => self.pragma(pragma),
+            ast::ItemKind::Import(import) => self.import(import, item.span),
+            ast::ItemKind::Using(using) => self.using(using, item.span),
+            ast::ItemKind::Contract(contract) => self.contract(contract, item.span),
+            ast::ItemKind::Function(function) => self.function(function, item.span),
+            ast::ItemKind::Variable(var) => Doc::concat(docs![self.variable_definition(var), ";"]),
+            ast::ItemKind::Struct(strukt) => {
+                let fields = self.list(
+                    strukt.fields,
+                    Some(item.span.hi()),
+                    |field| field.span,
+                    |_, _| false,
+                    |this, field| Doc::concat(docs![this.variable_definition(field), ";"]),
+                );
+                Doc::concat(docs![format!("struct {} ", strukt.name), braces(fields)])
+            }
+            ast::ItemKind::Enum(enumm) => {
+                let last = enumm.variants.last().map(|variant| variant.span);
+                let variants = self.list(
+                    enumm.variants,
+                    Some(item.span.hi()),
+                    |variant| variant.span,
+                    |_, _| false,
+                    |_, variant| {
+                        let comma = if Some(variant.span) == last { "" } else { "," };
+                        format!("{variant}{comma}").into()
+                    },
+                );
+                Doc::concat(docs![format!("enum {} ", enumm.name), braces(variants)])
+            }
+            ast::ItemKind::Udvt(udvt) => {
+                Doc::concat(docs![format!("type {} is ", udvt.name), self.ty(&udvt.ty), ";"])
+            }
+            ast::ItemKind::Error(error) => {
+                let parameters = self.parameter_list(error.parameters, Some(item.span.hi()));
+                Doc::concat(docs![format!("error {}", error.name), parameters, ";"])
+            }
+            ast::ItemKind::Event(event) => {
+                let parameters = self.parameter_list(event.parameters, Some(item.span.hi()));
+                let anonymous = if event.anonymous { " anonymous" } else { "" };
+                Doc::concat(docs![format!("event {}", event.name), parameters, anonymous, ";"])
+            }
+        };
+        Doc::concat(docs![comments, docs, kind])
+    }
+
+    fn pragma(&mut self, pragma: &ast::PragmaDirective<'_>) -> Doc {
+        let tokens = match &pragma.tokens {
+            ast::PragmaTokens::Version(name, req) => format!("{name} {req}"),
+            ast::PragmaTokens::Custom(name, value) => {
+                let mut s = self.ident_or_str_lit(name);
+                if let Some(value) = value {
+                    s.push(' ');
+                    s.push_str(&self.ident_or_str_lit(value));
+                }
+                s
+            }
+            ast::PragmaTokens::Verbatim(tokens) => {
+                let source = match (tokens.first(), tokens.last()) {
+                    (Some(first), Some(last)) => self.snippet(first.span.to(last.span)),
+                    _ => None,
+                };
+                match source {
+                    Some(source) => source.to_string(),
+                    None => tokens
+                        .iter()
+                        .map(|token| match token.lit() {
+                            Some(lit) => lit.to_string(),
+                            None => token.kind.to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                }
+            }
+        };
+        format!("pragma {tokens};").into()
+    }
+
+    fn ident_or_str_lit(&self, value: &ast::IdentOrStrLit) -> String {
+        match value {
+            ast::IdentOrStrLit::Ident(ident) => ident.to_string(),
+            ast::IdentOrStrLit::StrLit(lit) => self.str_lit(lit),
+        }
+    }
+
+    fn import(&mut self, import: &ast::ImportDirective<'_>, span: Span) -> Doc {
+        let path = self.str_lit(&import.path);
+        match &import.items {
+            ast::ImportItems::Plain(None) => format!("import {path};").into(),
+            ast::ImportItems::Plain(Some(alias)) => format!("import {path} as {alias};").into(),
+            ast::ImportItems::Aliases(aliases) => {
+                let aliases = self.comma_list(
+                    "{",
+                    "}",
+                    aliases,
+                    Some(span.hi()),
+                    self.config.bracket_spacing,
+                    |(name, alias)| Some(name.span.to(alias.unwrap_or(*name).span)),
+                    |_, (name, alias)| match alias {
+                        Some(alias) => format!("{name} as {alias}").into(),
+                        None => name.to_string().into(),
+                    },
+                );
+                Doc::concat(docs!["import ", aliases, format!(" from {path};")])
+            }
+            ast::ImportItems::Glob(alias) => match alias {
+                Some(alias) => format!("import * as {alias} from {path};").into(),
+                None => format!("import * from {path};").into(),
+            },
+        }
+    }
+
+    fn using(&mut self, using: &ast::UsingDirective<'_>, span: Span) -> Doc {
+        let list = match &using.list {
+            ast::UsingList::Single(path) => path.to_string().into(),
+            ast::UsingList::Multiple(paths) => self.comma_list(
+                "{",
+                "}",
+                paths,
+                Some(span.hi()),
+                self.config.bracket_spacing,
+                |(path, _)| Some(path.span()),
+                |_, (path, op)| match op {
+                    Some(op) => {
+                        let op = op.to_op().either(|op| op.to_str(), |op| op.to_str());
+                        format!("{path} as {op}").into()
+                    }
+                    None => path.to_string().into(),
+                },
+            ),
+        };
+        let ty = match &using.ty {
+            Some(ty) => self.ty(ty),
+            None => "*".into(),
+        };
+        let global = if using.global { " global" } else { "" };
+        Doc::concat(docs!["using ", list, " for ", ty, global, ";"])
+    }
+
+    fn contract(&mut self, contract: &ast::ItemContract<'_>, span: Span) -> Doc {
+        let mut header = docs![format!("{} {}", contract.kind.to_str(), contract.name)];
+        if !contract.bases.is_empty() {
+            header.push(" is".into());
+            let mut bases = Vec::new();
+            for (i, base) in contract.bases.iter().enumerate() {
+                if i > 0 {
+                    bases.push(",".into());
+                }
+                bases.push(Doc::Line);
+                bases.push(self.modifier(base));
+            }
+            header.push(Doc::indent(bases));
+        }
+        if let Some(layout) = &contract.layout {
+            header.push(Doc::indent(docs![Doc::Line, "layout at ", self.expr(layout.slot)]));
+        }
+        header.push(Doc::if_break(docs![Doc::HardLine, "{"], " {"));
+
+        let body = self.list(
+            contract.body,
+            Some(span.hi()),
+            |item| item.span,
+            Self::needs_blank_line,
+            Self::item,
+        );
+        Doc::concat(docs![Doc::group(header), close_brace(body)])
+    }
+
+    /// Prints a modifier invocation or an inheritance specifier.
+    fn modifier(&mut self, modifier: &ast::Modifier<'_>) -> Doc {
+        let name = modifier.name.to_string();
+        match &modifier.arguments {
+            ast::CallArgs::Unnamed([]) => name.into(),
+            args => Doc::concat(docs![name, self.call_args(args, None)]),
+        }
+    }
+
+    fn function(&mut self, function: &ast::ItemFunction<'_>, span: Span) -> Doc {
+        let header = &function.header;
+        let mut out = vec![match header.name {
+            Some(name) => format!("{} {name}", function.kind.to_str()).into(),
+            None => function.kind.to_str().into(),
+        }];
+        if !(function.kind.is_modifier() && header.parameters.is_empty()) {
+            out.push(self.parameter_list(header.parameters, None));
+        }
+
+        let mut attributes = Vec::new();
+        if let Some(visibility) = header.visibility {
+            attributes.push(visibility.to_str().into());
+        }
+        if !header.state_mutability.is_non_payable() {
+            attributes.push(header.state_mutability.to_str().into());
+        }
+        for modifier in header.modifiers.iter() {
+            attributes.push(self.modifier(modifier));
+        }
+        if header.virtual_ {
+            attributes.push("virtual".into());
+        }
+        if let Some(override_) = &header.override_ {
+            attributes.push(self.override_(override_));
+        }
+        if !header.returns.is_empty() {
+            let end = if function.body.is_none() { Some(span.hi()) } else { None };
+            let returns = self.parameter_list(header.returns, end);
+            attributes.push(Doc::concat(docs!["returns ", returns]));
+        }
+
+        let mut attributes_group = Vec::new();
+        if !attributes.is_empty() {
+            let mut indented = Vec::new();
+            for attribute in attributes {
+                indented.extend(docs![Doc::Line, attribute]);
+            }
+            attributes_group.push(Doc::indent(indented));
+        }
+        match &function.body {
+            Some(body) => {
+                // Comments before the opening brace stay in the header, which puts the brace on
+                // its own line after a line comment.
+                if let Some(brace) = self.find_token(span.lo(), '{', true) {
+                    while let Some(comment) = self.comments.next_before(brace) {
+                        if comment.style == CommentStyle::BlankLine {
+                            continue;
+                        }
+                        attributes_group.extend(docs![" ", comment_doc(&comment)]);
+                        if comment.is_line() {
+                            attributes_group.push(Doc::BreakParent);
+                        }
+                    }
+                }
+                if attributes_group.is_empty() {
+                    attributes_group.push(" ".into());
+                } else {
+                    attributes_group.push(Doc::if_break(Doc::HardLine, " "));
+                }
+                out.push(Doc::group(attributes_group));
+                out.push(self.block(body, Some(span.hi())));
+            }
+            None => {
+                attributes_group.push(";".into());
+                out.push(Doc::group(attributes_group));
+            }
+        }
+        Doc::group(out)
+    }
+}
@@ -0,0 +1,684 @@
+//! Solidity source code printer.
+//!
+//! [`Printer`] pretty-prints an AST as Solidity source code, breaking lines that are longer than
+//! the configured width. When given the source file that the AST was parsed from, it also prints
+//! all of its comments and keeps blank lines between statements and items, which makes it
+//! suitable for formatting source code.
+
+use crate::ast::{self, DocComment};
+use pp::{docs, Doc};
+use solar_interface::{
+    config::{NumberUnderscore, QuoteStyle},
+    source_map::SourceFile,
+    BytePos, Span,
+};
+use std::borrow::Cow;
+
+mod comments;
+use comments::Comments;
+pub use comments::{Comment, CommentStyle};
+
+mod expr;
+mod item;
+mod stmt;
+
+mod pp;
+
+/// Configuration of a [`Printer`].
+#[derive(Clone, Debug)]
+pub struct PrintConfig {
+    /// Maximum line width.
+    pub line_width: usize,
+    /// Number of spaces per indentation level.
+    pub tab_width: usize,
+    /// Print spaces inside of curly braces that delimit lists, like import aliases and named
+    /// arguments: `{ a: 1 }` instead of `{a: 1}`.
+    pub bracket_spacing: bool,
+    /// Quotes to use for string literals.
+    pub quote_style: QuoteStyle,
+    /// How to write underscores in number literals.
+    pub number_underscore: NumberUnderscore,
+}
+
+impl Default for PrintConfig {
+    fn default() -> Self {
+        Self {
+            line_width: 120,
+            tab_width: 4,
+            bracket_spacing: false,
+            quote_style: QuoteStyle::Double,
+            number_underscore: NumberUnderscore::Preserve,
+        }
+    }
+}
+
+/// Solidity AST pretty-printer.
+///
+/// # Examples
+///
+/// Formatting a source file, using [`gather_comments`] from `solar_parse`:
+///
+/// ```ignore
+/// let comments = solar_parse::lexer::gather_comments(&file);
+/// let mut printer = Printer::new(PrintConfig::default()).with_source(&file, comments);
+/// let formatted = printer.print_source_unit(&ast);
+/// ```
+///
+/// [`gather_comments`]: https://docs.rs/solar-parse/latest/solar_parse/lexer/fn.gather_comments.html
+#[derive(Debug)]
+pub struct Printer<'a> {
+    config: PrintConfig,
+    source: Option<&'a SourceFile>,
+    comments: Comments,
+}
+
+impl<'a> Printer<'a> {
+    /// Creates a new printer with the given configuration.
+    pub fn new(config: PrintConfig) -> Self {
+        Self { config, source: None, comments: Comments::default() }
+    }
+
+    /// Sets the source file that the printed AST was parsed from, and its comments.
+    ///
+    /// The comments are printed at their original positions relative to the AST nodes instead of
+    /// the doc-comments stored in the AST, and the original text of string literals is used to
+    /// print them.
+    pub fn with_source(mut self, file: &'a SourceFile, comments: Vec<Comment>) -> Self {
+        self.source = Some(file);
+        self.comments = Comments::new(comments);
+        self
+    }
+
+    /// Prints a source unit, followed by a newline.
+    pub fn print_source_unit(&mut self, source_unit: &ast::SourceUnit<'_>) -> String {
+        let items = self.list(
+            &source_unit.items.raw,
+            Some(BytePos(u32::MAX)),
+            |item| item.span,
+            Self::needs_blank_line,
+            Self::item,
+        );
+        let mut s = self.print(&Doc::concat(items));
+        if !s.is_empty() {
+            s.push('\n');
+        }
+        s
+    }
+
+    /// Prints an item.
+    pub fn print_item(&mut self, item: &ast::Item<'_>) -> String {
+        self.print_node(node_lo(item.docs, item.span), item, Self::item)
+    }
+
+    /// Prints a statement.
+    pub fn print_stmt(&mut self, stmt: &ast::Stmt<'_>) -> String {
+        self.print_node(node_lo(stmt.docs, stmt.span), stmt, Self::stmt)
+    }
+
+    /// Prints an expression.
+    ///
+    /// Parentheses are inserted where needed to preserve the structure of the expression, so
+    /// expressions built programmatically print correctly: `a - (b - c)` is printed for
+    /// `Binary(a, -, Binary(b, -, c))`.
+    pub fn print_expr(&mut self, expr: &ast::Expr<'_>) -> String {
+        self.print_node(expr.span.lo(), expr, Self::expr)
+    }
+
+    /// Prints a type.
+    pub fn print_ty(&mut self, ty: &ast::Type<'_>) -> String {
+        self.print_node(ty.span.lo(), ty, Self::ty)
+    }
+
+    /// Prints a single node. The comments of the source file before `lo` are not printed.
+    fn print_node<T>(
+        &mut self,
+        lo: BytePos,
+        node: &T,
+        print: impl FnOnce(&mut Self, &T) -> Doc,
+    ) -> String {
+        self.comments.seek(lo);
+        let doc = print(self, node);
+        self.print(&doc)
+    }
+
+    fn print(&self, doc: &Doc) -> String {
+        pp::print(doc, self.config.line_width, self.config.tab_width)
+    }
+
+    /// Returns `true` if comments are printed from the source instead of the AST doc-comments.
+    fn has_source(&self) -> bool {
+        self.source.is_some()
+    }
+
+    /// Returns the source code of `span` in the source file, if any.
+    fn snippet(&self, span: Span) -> Option<&'a str> {
+        let file = self.source?;
+        let lo = span.lo().0.checked_sub(file.start_pos.0)? as usize;
+        let hi = span.hi().0.checked_sub(file.start_pos.0)? as usize;
+        file.src.get(lo..hi)
+    }
+
+    /// Prints the doc-comments of a node when not printing comments from the source.
+    fn doc_comments(&mut self, docs: &[DocComment]) -> Doc {
+        if self.has_source() {
+            return Doc::concat(Vec::new());
+        }
+        let mut out = Vec::new();
+        for doc in docs {
+            let text = match doc.kind {
+                ast::CommentKind::Line => format!("///{}", doc.symbol),
+                ast::CommentKind::Block => format!("/**{}*/", doc.symbol),
+            };
+            for (i, line) in text.lines().enumerate() {
+                if i == 0 {
+                    out.push(line.to_string().into());
+                    continue;
+                }
+                // Re-indent the lines of block comments, which keep their original indentation.
+                let line = line.trim_start();
+                let line =
+                    if line.starts_with('*') { format!(" {line}") } else { line.to_string() };
+                out.extend(docs![Doc::HardLine, line]);
+            }
+            out.push(Doc::HardLine);
+        }
+        Doc::concat(out)
+    }
+
+    /// Prints the comments that start before `pos`, each followed by a line break, or by a space
+    /// if it is followed by code on the same line.
+    fn leading_comments(&mut self, pos: BytePos) -> Doc {
+        let mut out = Vec::new();
+        while let Some(comment) = self.comments.next_before(pos) {
+            if comment.style == CommentStyle::BlankLine {
+                continue;
+            }
+            out.push(comment_doc(&comment));
+            if comment.is_line() || comment.style == CommentStyle::Isolated {
+                out.push(Doc::HardLine);
+            } else {
+                out.push(" ".into());
+            }
+        }
+        Doc::concat(out)
+    }
+
+    /// Prints elements one per line, with the comments and blank lines between them.
+    ///
+    /// `end` is the position of the end of the list, if known, like the closing brace of a block.
+    fn list<T>(
+        &mut self,
+        elems: &[T],
+        end: Option<BytePos>,
+        span: impl Fn(&T) -> Span,
+        blank_line: impl Fn(&T, &T) -> bool,
+        mut print: impl FnMut(&mut Self, &T) -> Doc,
+    ) -> Vec<Doc> {
+        let mut out = Vec::new();
+        for (i, elem) in elems.iter().enumerate() {
+            let elem_span = span(elem);
+            let blank_line = i > 0 && blank_line(&elems[i - 1], elem);
+            let (same_line, blank_line) = self.list_comments(elem_span.lo(), blank_line, &mut out);
+            if same_line {
+                out.push(" ".into());
+            } else {
+                list_separator(blank_line, &mut out);
+            }
+            out.push(print(self, elem));
+
+            let next = elems.get(i + 1).map(|next| span(next).lo()).or(end);
+            self.trailing_comments(elem_span.hi(), next, &mut out);
+        }
+        if let Some(end) = end {
+            self.list_comments(end, false, &mut out);
+        }
+        out
+    }
+
+    /// Prints the comments before `pos` in a [list](Self::list).
+    ///
+    /// Returns whether the next element continues on the line of the last comment, and whether
+    /// there is a blank line before it.
+    fn list_comments(
+        &mut self,
+        pos: BytePos,
+        mut blank_line: bool,
+        out: &mut Vec<Doc>,
+    ) -> (bool, bool) {
+        let mut same_line = false;
+        while let Some(comment) = self.comments.next_before(pos) {
+            if comment.style == CommentStyle::BlankLine {
+                blank_line = true;
+                continue;
+            }
+            if same_line {
+                out.push(" ".into());
+            } else {
+                list_separator(blank_line, out);
+            }
+            out.push(comment_doc(&comment));
+            blank_line = false;
+            same_line = comment.style == CommentStyle::Mixed && !comment.is_line();
+        }
+        (same_line, blank_line)
+    }
+
+    /// Prints the comments left inside of an element that ends at `hi`, and the comment on the
+    /// same line after it, if any.
+    fn trailing_comments(&mut self, hi: BytePos, next: Option<BytePos>, out: &mut Vec<Doc>) {
+        let mut after_line_comment = false;
+        while let Some(comment) = self.comments.next_before(hi) {
+            if comment.style == CommentStyle::BlankLine {
+                continue;
+            }
+            out.push(if after_line_comment { Doc::HardLine } else { " ".into() });
+            out.push(comment_doc(&comment));
+            after_line_comment = comment.is_line();
+        }
+        if let Some(comment) = self.trailing_comment(next) {
+            out.push(if after_line_comment { Doc::HardLine } else { " ".into() });
+            out.push(comment_doc(&comment));
+        }
+    }
+
+    /// Consumes the next comment if it is on the same line as the preceding code and starts
+    /// before `next`.
+    fn trailing_comment(&mut self, next: Option<BytePos>) -> Option<Comment> {
+        let comment = self.comments.peek()?;
+        if comment.style != CommentStyle::Trailing {
+            return None;
+        }
+        self.comments.next_before(next?)
+    }
+
+    /// Returns the position of the first `token` at or after `pos` in the source file that is not
+    /// in a comment, a string literal, or parentheses.
+    ///
+    /// If `skip_code` is `false`, only whitespace and comments may precede the token.
+    fn find_token(&self, pos: BytePos, token: char, skip_code: bool) -> Option<BytePos> {
+        let file = self.source?;
+        let src = file.src.get(pos.0.checked_sub(file.start_pos.0)? as usize..)?;
+        let mut depth = 0usize;
+        let mut i = 0;
+        while let Some(c) = src[i..].chars().next() {
+            let rest = &src[i..];
+            if c.is_whitespace() {
+                i += c.len_utf8();
+            } else if rest.starts_with("//") {
+                i += rest.find('\n').unwrap_or(rest.len());
+            } else if rest.starts_with("/*") {
+                i += rest.find("*/")? + 2;
+            } else if c == token && depth == 0 {
+                return Some(BytePos(pos.0 + i as u32));
+            } else if !skip_code {
+                return None;
+            } else if c == '"' || c == '\'' {
+                i += 1 + find_unescaped(&rest[1..], c)? + 1;
+            } else {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                i += c.len_utf8();
+            }
+        }
+        None
+    }
+
+    /// Prints elements separated by commas, in a group delimited by `open` and `close` that is
+    /// broken into one element per line if it does not fit.
+    ///
+    /// Elements without a span are empty, like the omitted components of a tuple: `(a, , b)`.
+    #[allow(clippy::too_many_arguments)]
+    fn comma_list<T>(
+        &mut self,
+        open: &'static str,
+        close: &'static str,
+        elems: &[T],
+        end: Option<BytePos>,
+        spaced: bool,
+        span: impl Fn(&T) -> Option<Span>,
+        mut print: impl FnMut(&mut Self, &T) -> Doc,
+    ) -> Doc {
+        if elems.is_empty() {
+            return Doc::concat(docs![open, close]);
+        }
+        let line = if spaced { Doc::Line } else { Doc::SoftLine };
+        let mut inner = vec![line.clone()];
+        for (i, elem) in elems.iter().enumerate() {
+            inner.push(print(self, elem));
+            let next = elems.get(i + 1);
+            if next.is_some() {
+                inner.push(",".into());
+            }
+            if let Some(elem_span) = span(elem) {
+                let next_pos = elems[i + 1..].iter().find_map(&span).map(Span::lo).or(end);
+                while let Some(comment) = self.comments.next_before(elem_span.hi()) {
+                    if comment.style != CommentStyle::BlankLine {
+                        inner.extend(docs![" ", comment_doc(&comment), Doc::BreakParent]);
+                    }
+                }
+                // Comments before the closing delimiter belong to the last element.
+                let close_pos = elems[i + 1..]
+                    .iter()
+                    .all(|elem| span(elem).is_none())
+                    .then(|| self.find_token(elem_span.hi(), close.chars().next()?, false))
+                    .flatten();
+                if let Some(close_pos) = close_pos {
+                    while let Some(comment) = self.comments.next_before(close_pos) {
+                        match comment.style {
+                            CommentStyle::BlankLine => continue,
+                            CommentStyle::Isolated => inner.push(Doc::HardLine),
+                            CommentStyle::Trailing | CommentStyle::Mixed => inner.push(" ".into()),
+                        }
+                        inner.push(comment_doc(&comment));
+                        if comment.is_line() {
+                            inner.push(Doc::BreakParent);
+                        }
+                    }
+                }
+                if let Some(comment) = self.trailing_comment(next_pos) {
+                    inner.push(" ".into());
+                    inner.push(comment_doc(&comment));
+                    if comment.is_line() {
+                        inner.push(Doc::BreakParent);
+                    }
+                }
+            }
+            if next.is_some_and(|next| span(next).is_some()) {
+                inner.push(Doc::Line);
+            }
+        }
+        Doc::group(docs![open, Doc::indent(inner), line, close])
+    }
+
+    /// Prints a string literal with the configured quotes.
+    ///
+    /// `content` and `quote` are used if the source code of the literal is not available.
+    fn quoted(&self, span: Span, content: &str, quote: char) -> String {
+        self.snippet(span)
+            .and_then(|snippet| self.requote_all(snippet))
+            .unwrap_or_else(|| self.requote(content, quote))
+    }
+
+    /// Re-quotes the source code of a string literal, which may be a concatenation of multiple
+    /// literals with prefixes: `unicode"a" 'b'`.
+    ///
+    /// Returns `None` if the source code is not a plain list of string literals.
+    fn requote_all(&self, mut src: &str) -> Option<String> {
+        let mut out = String::new();
+        loop {
+            src = src.trim_start();
+            if src.is_empty() {
+                return Some(out);
+            }
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            let quote_idx = src.find(['"', '\''])?;
+            let prefix = &src[..quote_idx];
+            if !matches!(prefix, "" | "unicode" | "hex") {
+                return None;
+            }
+            let quote = src.as_bytes()[quote_idx] as char;
+            let content_start = quote_idx + 1;
+            let content_len = find_unescaped(&src[content_start..], quote)?;
+            out.push_str(prefix);
+            out.push_str(&self.requote(&src[content_start..content_start + content_len], quote));
+            src = &src[content_start + content_len + 1..];
+        }
+    }
+
+    /// Quotes the source code of the contents of a string literal, written with `quote`, with the
+    /// configured quotes.
+    fn requote(&self, content: &str, quote: char) -> String {
+        let target = match self.config.quote_style {
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single => '\'',
+            QuoteStyle::Preserve => quote,
+        };
+        if target == quote || find_unescaped(content, target).is_some() {
+            return format!("{quote}{content}{quote}");
+        }
+        let mut s = String::with_capacity(content.len() + 2);
+        s.push(target);
+        let mut chars = content.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some(c) if c == quote => s.push(c),
+                    Some(c) => {
+                        s.push('\\');
+                        s.push(c);
+                    }
+                    None => s.push('\\'),
+                }
+            } else {
+                s.push(c);
+            }
+        }
+        s.push(target);
+        s
+    }
+
+    /// Formats the source code of a number literal with the configured underscores.
+    fn number<'s>(&self, s: &'s str) -> Cow<'s, str> {
+        match self.config.number_underscore {
+            NumberUnderscore::Preserve => s.into(),
+            NumberUnderscore::Remove => s.replace('_', "").into(),
+            NumberUnderscore::Thousands => {
+                if s.starts_with("0x") || s.starts_with("0X") {
+                    return s.into();
+                }
+                let s = s.replace('_', "");
+                let (mantissa, exp) = match s.find(['e', 'E']) {
+                    Some(i) => s.split_at(i),
+                    None => (&s[..], ""),
+                };
+                let (int, frac) = match mantissa.split_once('.') {
+                    Some((int, frac)) => (int, Some(frac)),
+                    None => (mantissa, None),
+                };
+                let mut out = group_thousands(int, true);
+                if let Some(frac) = frac {
+                    out.push('.');
+                    out.push_str(&group_thousands(frac, false));
+                }
+                out.push_str(exp);
+                out.into()
+            }
+        }
+    }
+
+    /// Returns `true` if a blank line is always printed between two items.
+    fn needs_blank_line(prev: &ast::Item<'_>, next: &ast::Item<'_>) -> bool {
+        let is_block = |item: &ast::Item<'_>| match &item.kind {
+            ast::ItemKind::Contract(_) => true,
+            ast::ItemKind::Function(f) => f.body.is_some(),
+            _ => false,
+        };
+        is_block(prev) || is_block(next)
+    }
+}
+
+/// Returns the start of a node, including its doc-comments.
+fn node_lo(docs: &[DocComment], span: Span) -> BytePos {
+    docs.first().map_or(span.lo(), |doc| doc.span.lo().min(span.lo()))
+}
+
+/// Prints a line break between the elements of a [list](Printer::list), if any.
+fn list_separator(blank_line: bool, out: &mut Vec<Doc>) {
+    if !out.is_empty() {
+        out.push(Doc::HardLine);
+        if blank_line {
+            out.push(Doc::HardLine);
+        }
+    }
+}
+
+fn comment_doc(comment: &Comment) -> Doc {
+    let mut out = Vec::with_capacity(comment.lines.len() * 2);
+    for (i, line) in comment.lines.iter().enumerate() {
+        if i > 0 {
+            out.push(Doc::HardLine);
+        }
+        out.push(line.clone().into());
+    }
+    if comment.is_line() {
+        out.push(Doc::BreakParent);
+    }
+    Doc::concat(out)
+}
+
+/// Wraps the lines of a block in braces, or returns `{}` if it is empty.
+fn braces(body: Vec<Doc>) -> Doc {
+    if body.is_empty() {
+        return "{}".into();
+    }
+    Doc::concat(docs!["{", close_brace(body)])
+}
+
+/// Prints the lines of a block, indented, and its closing brace.
+fn close_brace(body: Vec<Doc>) -> Doc {
+    if body.is_empty() {
+        return "}".into();
+    }
+    let mut inner = vec![Doc::HardLine];
+    inner.extend(body);
+    Doc::concat(docs![Doc::indent(inner), Doc::HardLine, "}"])
+}
+
+/// Returns the byte index of the first occurrence of `quote` in `s` that is not escaped with a
+/// backslash.
+fn find_unescaped(s: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Separates groups of three digits of numbers with at least 5 digits with underscores, starting
+/// from the right for integer parts, and from the left for fractional parts.
+fn group_thousands(digits: &str, from_right: bool) -> String {
+    if digits.len() < 5 {
+        return digits.to_string();
+    }
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        let n = if from_right { digits.len() - i } else { i };
+        if i > 0 && n % 3 == 0 {
+            out.push('_');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence() {
+        use ast::{BinOpKind::*, ExprKind, UnOpKind::*};
+
+        solar_interface::enter(|| {
+            let arena = ast::Arena::new();
+            let expr = |kind| &mut *arena.alloc(ast::Expr { span: Span::DUMMY, kind });
+            let ident = |name| expr(ExprKind::Ident(solar_interface::Ident::from_str(name)));
+            let bin = |lhs, kind, rhs| {
+                expr(ExprKind::Binary(lhs, ast::BinOp { span: Span::DUMMY, kind }, rhs))
+            };
+            let un = |kind, operand| {
+                expr(ExprKind::Unary(ast::UnOp { span: Span::DUMMY, kind }, operand))
+            };
+
+            let cases = [
+                (bin(bin(ident("a"), Sub, ident("b")), Sub, ident("c")), "a - b - c"),
+                (bin(ident("a"), Sub, bin(ident("b"), Sub, ident("c"))), "a - (b - c)"),
+                (bin(bin(ident("a"), Add, ident("b")), Mul, ident("c")), "(a + b) * c"),
+                (bin(ident("a"), Add, bin(ident("b"), Mul, ident("c"))), "a + b * c"),
+                (bin(ident("a"), Pow, bin(ident("b"), Pow, ident("c"))), "a ** b ** c"),
+                (bin(bin(ident("a"), Pow, ident("b")), Pow, ident("c")), "(a ** b) ** c"),
+                (un(Neg, bin(ident("a"), Add, ident("b"))), "-(a + b)"),
+                (un(Neg, un(Neg, ident("a"))), "- -a"),
+                (un(PostInc, un(Neg, ident("a"))), "(-a)++"),
+                (bin(un(Neg, ident("a")), Pow, ident("b")), "-a ** b"),
+                (
+                    expr(ExprKind::Member(
+                        bin(ident("a"), Add, ident("b")),
+                        solar_interface::Ident::from_str("c"),
+                    )),
+                    "(a + b).c",
+                ),
+                (
+                    expr(ExprKind::Ternary(
+                        expr(ExprKind::Ternary(ident("a"), ident("b"), ident("c"))),
+                        expr(ExprKind::Assign(ident("d"), None, ident("e"))),
+                        expr(ExprKind::Ternary(ident("f"), ident("g"), ident("h"))),
+                    )),
+                    "(a ? b : c) ? d = e : f ? g : h",
+                ),
+                (
+                    expr(ExprKind::Assign(
+                        expr(ExprKind::Assign(ident("a"), None, ident("b"))),
+                        None,
+                        expr(ExprKind::Assign(ident("c"), None, ident("d"))),
+                    )),
+                    "(a = b) = c = d",
+                ),
+            ];
+            for (expr, expected) in cases {
+                assert_eq!(Printer::new(PrintConfig::default()).print_expr(expr), expected);
+            }
+        });
+    }
+
+    #[test]
+    fn numbers() {
+        let printer = |number_underscore| {
+            Printer::new(PrintConfig { number_underscore, ..Default::default() })
+        };
+        let cases = [
+            ("1000", "1000", "1000"),
+            ("10000", "10000", "10_000"),
+            ("1_000_000", "1000000", "1_000_000"),
+            ("1234567.1234567e1_8", "1234567.1234567e18", "1_234_567.123_456_7e18"),
+            ("0x1234_5678", "0x12345678", "0x1234_5678"),
+        ];
+        for (s, removed, thousands) in cases {
+            assert_eq!(printer(NumberUnderscore::Preserve).number(s), s);
+            assert_eq!(printer(NumberUnderscore::Remove).number(s), removed);
+            assert_eq!(printer(NumberUnderscore::Thousands).number(s), thousands);
+        }
+    }
+
+    #[test]
+    fn quotes() {
+        let printer = |quote_style| Printer::new(PrintConfig { quote_style, ..Default::default() });
+        let cases = [
+            (r#""a""#, r#""a""#, "'a'"),
+            (r"'a\'b'", r#""a'b""#, r"'a\'b'"),
+            (r#""a\"b""#, r#""a\"b""#, r#"'a"b'"#),
+            (r#"'a"b'"#, r#"'a"b'"#, r#"'a"b'"#),
+            (r#"unicode"a"   hex'00'"#, r#"unicode"a" hex"00""#, "unicode'a' hex'00'"),
+        ];
+        for (s, double, single) in cases {
+            assert_eq!(
+                printer(QuoteStyle::Preserve).requote_all(s).as_deref(),
+                Some(s.replace("   ", " ").as_str())
+            );
+            assert_eq!(printer(QuoteStyle::Double).requote_all(s).as_deref(), Some(double));
+            assert_eq!(printer(QuoteStyle::Single).requote_all(s).as_deref(), Some(single));
+        }
+        assert_eq!(printer(QuoteStyle::Double).requote_all(r#""a" /* b */ "c""#), None);
+    }
+}
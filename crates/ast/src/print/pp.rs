@@ -0,0 +1,239 @@
+//! A document-based pretty-printing engine.
+//!
+//! The algorithm is Philip Wadler's ["A prettier printer"], in the form popularized by
+//! [Prettier]: a document is a tree of text, line breaks, indentation and groups, and a group is
+//! printed on a single line if it fits in the remaining width, or with all of its direct line
+//! breaks broken otherwise.
+//!
+//! ["A prettier printer"]: https://homepages.inf.ed.ac.uk/wadler/papers/prettier/prettier.pdf
+//! [Prettier]: https://github.com/prettier/prettier/blob/main/commands.md
+
+use std::borrow::Cow;
+
+/// A document to pretty-print.
+#[derive(Clone, Debug)]
+pub(crate) enum Doc {
+    /// Text without newlines.
+    Text(Cow<'static, str>),
+    /// A space if the enclosing group is flat, or a newline otherwise.
+    Line,
+    /// Nothing if the enclosing group is flat, or a newline otherwise.
+    SoftLine,
+    /// A newline. Breaks all the enclosing groups.
+    HardLine,
+    /// Prints nothing, but breaks all the enclosing groups.
+    BreakParent,
+    /// The given documents, indented by one level after newlines.
+    Indent(Vec<Self>, bool),
+    /// The given documents, printed flat if they fit in the remaining width.
+    Group(Vec<Self>, bool),
+    /// The given documents.
+    Concat(Vec<Self>, bool),
+    /// The first document if the enclosing group is broken, the second one otherwise.
+    IfBreak(Box<Self>, Box<Self>),
+}
+
+impl From<&'static str> for Doc {
+    fn from(s: &'static str) -> Self {
+        Self::Text(s.into())
+    }
+}
+
+impl From<String> for Doc {
+    fn from(s: String) -> Self {
+        Self::Text(s.into())
+    }
+}
+
+impl From<Vec<Self>> for Doc {
+    fn from(docs: Vec<Self>) -> Self {
+        Self::concat(docs)
+    }
+}
+
+/// Creates a `Vec<Doc>` from a list of values convertible into [`Doc`].
+macro_rules! docs {
+    ($($doc:expr),* $(,)?) => {
+        vec![$($crate::print::pp::Doc::from($doc)),*]
+    };
+}
+pub(crate) use docs;
+
+impl Doc {
+    /// Concatenates the given documents.
+    pub(crate) fn concat(docs: Vec<Self>) -> Self {
+        let breaks = Self::any_breaks(&docs);
+        Self::Concat(docs, breaks)
+    }
+
+    /// Indents the given documents.
+    pub(crate) fn indent(docs: Vec<Self>) -> Self {
+        let breaks = Self::any_breaks(&docs);
+        Self::Indent(docs, breaks)
+    }
+
+    /// Groups the given documents.
+    pub(crate) fn group(docs: Vec<Self>) -> Self {
+        let breaks = Self::any_breaks(&docs);
+        Self::Group(docs, breaks)
+    }
+
+    /// Returns `if_break` if the enclosing group is broken, and `flat` otherwise.
+    pub(crate) fn if_break(if_break: impl Into<Self>, flat: impl Into<Self>) -> Self {
+        Self::IfBreak(Box::new(if_break.into()), Box::new(flat.into()))
+    }
+
+    /// Returns `true` if the document breaks the group that contains it.
+    fn breaks(&self) -> bool {
+        match self {
+            Self::Text(_) | Self::Line | Self::SoftLine => false,
+            Self::HardLine | Self::BreakParent => true,
+            Self::Indent(_, breaks) | Self::Group(_, breaks) | Self::Concat(_, breaks) => *breaks,
+            Self::IfBreak(_, flat) => flat.breaks(),
+        }
+    }
+
+    fn any_breaks(docs: &[Self]) -> bool {
+        docs.iter().any(Self::breaks)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Prints a document with the given maximum line width and indentation width.
+///
+/// Trailing whitespace is removed from all lines.
+pub(crate) fn print(doc: &Doc, width: usize, indent_width: usize) -> String {
+    let mut out = String::new();
+    // The current column, including pending indentation.
+    let mut column = 0;
+    // Indentation is only written before the first text of a line, so that blank lines stay
+    // empty.
+    let mut pending_indent = None;
+    let mut cmds = vec![(0, Mode::Break, doc)];
+    while let Some((indent, mode, doc)) = cmds.pop() {
+        match doc {
+            Doc::Text(s) => {
+                if let Some(n) = pending_indent.take() {
+                    out.extend(std::iter::repeat_n(' ', n));
+                }
+                out.push_str(s);
+                column += text_width(s);
+            }
+            Doc::Line | Doc::SoftLine if mode == Mode::Flat => {
+                if let Doc::Line = doc {
+                    out.push(' ');
+                    column += 1;
+                }
+            }
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                let trimmed = out.trim_end_matches(' ').len();
+                out.truncate(trimmed);
+                out.push('\n');
+                pending_indent = Some(indent);
+                column = indent;
+            }
+            Doc::BreakParent => {}
+            Doc::Indent(docs, _) => {
+                cmds.extend(docs.iter().rev().map(|doc| (indent + indent_width, mode, doc)));
+            }
+            Doc::Concat(docs, _) => cmds.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+            Doc::Group(docs, breaks) => {
+                let mode = if mode == Mode::Flat
+                    || (!breaks && fits(docs, &cmds, width as isize - column as isize))
+                {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                cmds.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+            Doc::IfBreak(if_break, flat) => {
+                cmds.push((indent, mode, if mode == Mode::Break { if_break } else { flat }));
+            }
+        }
+    }
+    let trimmed = out.trim_end_matches(' ').len();
+    out.truncate(trimmed);
+    out
+}
+
+/// Returns `true` if `docs` printed flat, followed by the rest of the commands up to their first
+/// possible line break, fit in `remaining` columns.
+fn fits(docs: &[Doc], rest: &[(usize, Mode, &Doc)], mut remaining: isize) -> bool {
+    let mut stack = docs.iter().rev().map(|doc| (Mode::Flat, doc)).collect::<Vec<_>>();
+    let mut rest = rest.iter().rev();
+    loop {
+        if remaining < 0 {
+            return false;
+        }
+        let Some((mode, doc)) =
+            stack.pop().or_else(|| rest.next().map(|&(_, mode, doc)| (mode, doc)))
+        else {
+            return true;
+        };
+        match doc {
+            Doc::Text(s) => remaining -= text_width(s) as isize,
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+            Doc::BreakParent => {}
+            Doc::Indent(docs, _) | Doc::Concat(docs, _) => {
+                stack.extend(docs.iter().rev().map(|doc| (mode, doc)));
+            }
+            Doc::Group(docs, breaks) => {
+                let mode = if *breaks { Mode::Break } else { mode };
+                stack.extend(docs.iter().rev().map(|doc| (mode, doc)));
+            }
+            Doc::IfBreak(if_break, flat) => {
+                stack.push((mode, if mode == Mode::Break { if_break } else { flat }));
+            }
+        }
+    }
+}
+
+fn text_width(s: &str) -> usize {
+    s.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &'static str, args: &[&'static str]) -> Doc {
+        let mut inner = vec![Doc::SoftLine];
+        for (i, &arg) in args.iter().enumerate() {
+            if i > 0 {
+                inner.extend(docs![",", Doc::Line]);
+            }
+            inner.push(arg.into());
+        }
+        Doc::group(docs![name, "(", Doc::indent(inner), Doc::SoftLine, ")"])
+    }
+
+    #[test]
+    fn groups() {
+        let doc = Doc::concat(docs![call("foo", &["a", "b", "c"]), ";"]);
+        assert_eq!(print(&doc, 13, 4), "foo(a, b, c);");
+        assert_eq!(print(&doc, 12, 4), "foo(\n    a,\n    b,\n    c\n);");
+
+        let doc =
+            Doc::group(docs!["{", Doc::indent(docs![Doc::HardLine, "x"]), Doc::HardLine, "}"]);
+        assert_eq!(print(&doc, 80, 4), "{\n    x\n}");
+
+        let doc = Doc::group(docs!["a", Doc::if_break("!", "?")]);
+        assert_eq!(print(&doc, 80, 4), "a?");
+        let doc = Doc::group(docs!["a", Doc::if_break("!", "?"), Doc::BreakParent]);
+        assert_eq!(print(&doc, 80, 4), "a!");
+    }
+
+    #[test]
+    fn blank_lines() {
+        let doc = Doc::indent(docs!["a", Doc::HardLine, Doc::HardLine, "b "]);
+        assert_eq!(print(&doc, 80, 4), "a\n\n    b");
+    }
+}
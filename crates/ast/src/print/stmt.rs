@@ -0,0 +1,274 @@
+use super::{
+    braces,
+    pp::{docs, Doc},
+    Printer,
+};
+use crate::ast::{self, yul};
+use solar_interface::BytePos;
+
+impl Printer<'_> {
+    /// Prints a block of statements. `end` is the position of its closing brace, if known.
+    pub(super) fn block(&mut self, block: &[ast::Stmt<'_>], end: Option<BytePos>) -> Doc {
+        let stmts = self.list(block, end, |stmt| stmt.span, |_, _| false, Self::stmt);
+        braces(stmts)
+    }
+
+    pub(super) fn stmt(&mut self, stmt: &ast::Stmt<'_>) -> Doc {
+        let comments = self.leading_comments(stmt.span.lo());
+        let docs = self.doc_comments(stmt.docs);
+        let end = Some(stmt.span.hi());
+        let kind = match &stmt.kind {
+            ast::StmtKind::Assembly(assembly) => {
+                let mut out = docs!["assembly "];
+                if let Some(dialect) = &assembly.dialect {
+                    out.extend(docs![self.str_lit(dialect), " "]);
+                }
+                if !assembly.flags.is_empty() {
+                    let flags = self.comma_list(
+                        "(",
+                        ")",
+                        assembly.flags,
+                        None,
+                        false,
+                        |flag| Some(flag.span),
+                        |this, flag| this.str_lit(flag).into(),
+                    );
+                    out.extend(docs![flags, " "]);
+                }
+                out.push(self.yul_block(assembly.block, end));
+                Doc::concat(out)
+            }
+            ast::StmtKind::DeclSingle(var) => {
+                Doc::concat(docs![self.variable_definition(var), ";"])
+            }
+            ast::StmtKind::DeclMulti(vars, expr) => {
+                let vars = self.comma_list(
+                    "(",
+                    ")",
+                    vars,
+                    Some(expr.span.lo()),
+                    false,
+                    |var| var.as_ref().map(|var| var.span),
+                    |this, var| match var {
+                        Some(var) => this.variable_definition(var),
+                        None => Doc::concat(Vec::new()),
+                    },
+                );
+                Doc::concat(docs![vars, " = ", self.expr(expr), ";"])
+            }
+            ast::StmtKind::Block(block) => self.block(block, end),
+            ast::StmtKind::Break => "break;".into(),
+            ast::StmtKind::Continue => "continue;".into(),
+            ast::StmtKind::DoWhile(body, cond) => {
+                let separator = if matches!(body.kind, ast::StmtKind::Block(_)) {
+                    " ".into()
+                } else {
+                    Doc::HardLine
+                };
+                Doc::concat(docs![
+                    "do",
+                    self.stmt_body(body),
+                    separator,
+                    "while (",
+                    self.expr(cond),
+                    ");",
+                ])
+            }
+            ast::StmtKind::Emit(path, args) => {
+                Doc::concat(docs![format!("emit {path}"), self.call_args(args, end), ";"])
+            }
+            ast::StmtKind::Expr(expr) => Doc::concat(docs![self.expr(expr), ";"]),
+            ast::StmtKind::For { init, cond, next, body } => {
+                let mut out = docs!["for ("];
+                match init {
+                    Some(init) => out.push(self.stmt(init)),
+                    None => out.push(";".into()),
+                }
+                if let Some(cond) = cond {
+                    out.extend(docs![" ", self.expr(cond)]);
+                }
+                out.push(";".into());
+                if let Some(next) = next {
+                    out.extend(docs![" ", self.expr(next)]);
+                }
+                out.extend(docs![")", self.stmt_body(body)]);
+                Doc::concat(out)
+            }
+            ast::StmtKind::If(cond, then, else_) => {
+                let mut out = docs!["if (", self.expr(cond), ")", self.stmt_body(then)];
+                if let Some(else_) = else_ {
+                    out.push(if matches!(then.kind, ast::StmtKind::Block(_)) {
+                        " ".into()
+                    } else {
+                        Doc::HardLine
+                    });
+                    out.push("else".into());
+                    if let ast::StmtKind::If(..) = else_.kind {
+                        out.extend(docs![" ", self.stmt(else_)]);
+                    } else {
+                        out.push(self.stmt_body(else_));
+                    }
+                }
+                Doc::concat(out)
+            }
+            ast::StmtKind::Return(expr) => match expr {
+                Some(expr) => Doc::concat(docs!["return ", self.expr(expr), ";"]),
+                None => "return;".into(),
+            },
+            ast::StmtKind::Revert(path, args) => {
+                Doc::concat(docs![format!("revert {path}"), self.call_args(args, end), ";"])
+            }
+            ast::StmtKind::Try(try_) => {
+                let mut out = docs!["try ", self.expr(try_.expr)];
+                if !try_.returns.is_empty() {
+                    out.extend(docs![" returns ", self.parameter_list(try_.returns, None)]);
+                }
+                out.extend(docs![" ", self.block(try_.block, None)]);
+                for (i, catch) in try_.catch.iter().enumerate() {
+                    out.push(" catch ".into());
+                    if let Some(name) = catch.name {
+                        out.push(name.to_string().into());
+                    }
+                    if !catch.args.is_empty() {
+                        out.extend(docs![self.parameter_list(catch.args, None), " "]);
+                    } else if catch.name.is_some() {
+                        out.push(" ".into());
+                    }
+                    let end = if i == try_.catch.len() - 1 { end } else { None };
+                    out.push(self.block(catch.block, end));
+                }
+                Doc::concat(out)
+            }
+            ast::StmtKind::UncheckedBlock(block) => {
+                Doc::concat(docs!["unchecked ", self.block(block, end)])
+            }
+            ast::StmtKind::While(cond, body) => {
+                Doc::concat(docs!["while (", self.expr(cond), ")", self.stmt_body(body)])
+            }
+            ast::StmtKind::Placeholder => "_;".into(),
+        };
+        Doc::concat(docs![comments, docs, kind])
+    }
+
+    /// Prints the body of a control flow statement, including the leading space or line break.
+    fn stmt_body(&mut self, body: &ast::Stmt<'_>) -> Doc {
+        if matches!(body.kind, ast::StmtKind::Block(_)) {
+            Doc::concat(docs![" ", self.stmt(body)])
+        } else {
+            Doc::group(docs![Doc::indent(docs![Doc::Line, self.stmt(body)])])
+        }
+    }
+
+    /// Prints a Yul block. `end` is the position of its closing brace, if known.
+    fn yul_block(&mut self, block: &[yul::Stmt<'_>], end: Option<BytePos>) -> Doc {
+        let stmts = self.list(block, end, |stmt| stmt.span, |_, _| false, Self::yul_stmt);
+        braces(stmts)
+    }
+
+    /// Prints a Yul block on a single line if it fits, like the initialization block of a `for`
+    /// loop.
+    fn yul_inline_block(&mut self, block: &[yul::Stmt<'_>]) -> Doc {
+        if block.is_empty() {
+            return "{}".into();
+        }
+        let mut inner = Vec::new();
+        for stmt in block {
+            inner.extend(docs![Doc::Line, self.yul_stmt(stmt)]);
+        }
+        Doc::group(docs!["{", Doc::indent(inner), Doc::Line, "}"])
+    }
+
+    fn yul_stmt(&mut self, stmt: &yul::Stmt<'_>) -> Doc {
+        let comments = self.leading_comments(stmt.span.lo());
+        let docs = self.doc_comments(stmt.docs);
+        let end = Some(stmt.span.hi());
+        let kind = match &stmt.kind {
+            yul::StmtKind::Block(block) => self.yul_block(block, end),
+            yul::StmtKind::AssignSingle(path, expr) => {
+                Doc::concat(docs![format!("{path} := "), self.yul_expr(expr)])
+            }
+            yul::StmtKind::AssignMulti(paths, call) => {
+                let paths = paths.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                Doc::concat(docs![format!("{paths} := "), self.yul_call(call, end)])
+            }
+            yul::StmtKind::Expr(call) => self.yul_call(call, end),
+            yul::StmtKind::If(cond, block) => {
+                Doc::concat(docs!["if ", self.yul_expr(cond), " ", self.yul_block(block, end)])
+            }
+            yul::StmtKind::For { init, cond, step, body } => Doc::concat(docs![
+                "for ",
+                self.yul_inline_block(init),
+                " ",
+                self.yul_expr(cond),
+                " ",
+                self.yul_inline_block(step),
+                " ",
+                self.yul_block(body, end),
+            ]),
+            yul::StmtKind::Switch(switch) => {
+                let mut out = docs!["switch ", self.yul_expr(&switch.selector)];
+                for (i, case) in switch.branches.iter().enumerate() {
+                    let is_last = i == switch.branches.len() - 1 && switch.default_case.is_none();
+                    out.extend(docs![
+                        Doc::HardLine,
+                        format!("case {} ", self.lit(case.constant)),
+                        self.yul_block(case.body, if is_last { end } else { None }),
+                    ]);
+                }
+                if let Some(default) = &switch.default_case {
+                    out.extend(docs![Doc::HardLine, "default ", self.yul_block(default, end)]);
+                }
+                Doc::concat(out)
+            }
+            yul::StmtKind::Leave => "leave".into(),
+            yul::StmtKind::Break => "break".into(),
+            yul::StmtKind::Continue => "continue".into(),
+            yul::StmtKind::FunctionDef(function) => {
+                let join = |idents: &[ast::Ident]| {
+                    idents.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+                };
+                let mut header =
+                    format!("function {}({})", function.name, join(function.parameters));
+                if !function.returns.is_empty() {
+                    header.push_str(" -> ");
+                    header.push_str(&join(function.returns));
+                }
+                header.push(' ');
+                Doc::concat(docs![header, self.yul_block(function.body, end)])
+            }
+            yul::StmtKind::VarDecl(names, init) => {
+                let names = names.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                match init {
+                    Some(init) => {
+                        Doc::concat(docs![format!("let {names} := "), self.yul_expr(init)])
+                    }
+                    None => format!("let {names}").into(),
+                }
+            }
+        };
+        Doc::concat(docs![comments, docs, kind])
+    }
+
+    fn yul_expr(&mut self, expr: &yul::Expr<'_>) -> Doc {
+        let comments = self.leading_comments(expr.span.lo());
+        let kind = match &expr.kind {
+            yul::ExprKind::Path(path) => path.to_string().into(),
+            yul::ExprKind::Call(call) => self.yul_call(call, Some(expr.span.hi())),
+            yul::ExprKind::Lit(lit) => self.lit(lit).into(),
+        };
+        Doc::concat(docs![comments, kind])
+    }
+
+    fn yul_call(&mut self, call: &yul::ExprCall<'_>, end: Option<BytePos>) -> Doc {
+        let args = self.comma_list(
+            "(",
+            ")",
+            call.arguments,
+            end,
+            false,
+            |arg| Some(arg.span),
+            |this, arg| this.yul_expr(arg),
+        );
+        Doc::concat(docs![call.name.to_string(), args])
+    }
+}
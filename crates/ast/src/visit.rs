@@ -84,11 +84,14 @@ declare_visitors! {
         }
 
         fn visit_item_contract(&mut self, contract: &'ast #mut ItemContract<'ast>) {
-            let ItemContract { kind: _, name, bases, body } = contract;
+            let ItemContract { kind: _, name, bases, layout, body } = contract;
             self.visit_ident #_mut(name);
             for base in bases.iter #_mut() {
                 self.visit_modifier #_mut(base);
             }
+            if let Some(StorageLayoutSpecifier { span: _, slot }) = layout {
+                self.visit_expr #_mut(slot);
+            }
             for item in body.iter #_mut() {
                 self.visit_item #_mut(item);
             }
@@ -1,7 +1,10 @@
 //! Solar CLI arguments.
 
 use clap::{ColorChoice, Parser, ValueHint};
-use solar_config::{CompilerOutput, CompilerStage, Dump, EvmVersion, Language};
+use solar_config::{
+    CompilerOutput, CompilerStage, Dump, EvmVersion, GraphFormat, Language, NumberUnderscore,
+    OutputLayout, ProxyPair, QuoteStyle,
+};
 use std::path::PathBuf;
 
 /// Blazingly fast Solidity compiler.
@@ -14,6 +17,10 @@ use std::path::PathBuf;
 )]
 #[non_exhaustive]
 pub struct Args {
+    /// Command to run instead of compiling the input files.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Files to compile or import remappings.
     #[arg(value_hint = ValueHint::FilePath)]
     pub input: Vec<PathBuf>,
@@ -36,13 +43,24 @@ pub struct Args {
     /// Stop execution after the given compiler stage.
     #[arg(long, value_enum)]
     pub stop_after: Option<CompilerStage>,
+    /// Check a proxy and an implementation contract for function selector collisions.
+    ///
+    /// Contracts are specified by name or fully qualified name. Can be specified multiple times.
+    #[arg(long = "proxy", value_name = "PROXY=IMPLEMENTATION")]
+    pub proxies: Vec<ProxyPair>,
 
     /// Directory to write output files.
     #[arg(long, value_hint = ValueHint::DirPath)]
     pub out_dir: Option<PathBuf>,
+    /// How to lay out the output files in the output directory.
+    #[arg(long, value_enum, default_value_t, requires = "out_dir")]
+    pub output_layout: OutputLayout,
     /// Comma separated list of types of output for the compiler to emit.
     #[arg(long, value_delimiter = ',')]
     pub emit: Vec<CompilerOutput>,
+    /// Output format of `--emit inheritance-graph` and `--emit call-graph`.
+    #[arg(long, value_enum, default_value_t)]
+    pub graph_format: GraphFormat,
 
     /// Coloring.
     #[arg(help_heading = "Display options", long, value_enum, default_value = "auto")]
@@ -89,6 +107,123 @@ impl Args {
     }
 }
 
+/// Solar subcommands.
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum Command {
+    /// Convert a JSON ABI into a Solidity interface.
+    Abi2sol(Abi2SolArgs),
+    /// Flatten Solidity sources and their imports into a single file.
+    Flatten(FlattenArgs),
+    /// Format Solidity source files.
+    Fmt(FmtArgs),
+    /// Print the resolved import graph of Solidity sources, including import cycles.
+    Imports(ImportsArgs),
+    /// Run a language server over stdin and stdout.
+    Lsp(LspArgs),
+    /// Rename a declaration and all of its references.
+    Rename(RenameArgs),
+}
+
+/// Arguments for `solar abi2sol`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct Abi2SolArgs {
+    /// JSON ABI file, or `-` for stdin. Artifacts with an `abi` field are also accepted.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+    /// Name of the interface. Defaults to the file name without extensions.
+    #[arg(long, short)]
+    pub name: Option<String>,
+    /// File to write the interface to. Defaults to stdout.
+    #[arg(long, short, value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+    /// Parse and type-check the generated interface.
+    #[arg(long)]
+    pub check: bool,
+}
+
+/// Arguments for `solar flatten`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct FlattenArgs {
+    /// Files to flatten, along with all of their imports.
+    #[arg(required = true, value_hint = ValueHint::FilePath)]
+    pub paths: Vec<PathBuf>,
+    /// File to write the flattened source to. Defaults to stdout.
+    #[arg(long, short, value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for `solar fmt`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct FmtArgs {
+    /// Files or directories to format. Directories are searched recursively for `.sol` files.
+    #[arg(required = true, value_hint = ValueHint::AnyPath)]
+    pub paths: Vec<PathBuf>,
+    /// Check that the files are formatted instead of formatting them, and list the ones that are
+    /// not.
+    #[arg(long)]
+    pub check: bool,
+    /// Maximum line width.
+    #[arg(long, default_value_t = 120)]
+    pub line_width: usize,
+    /// Number of spaces per indentation level.
+    #[arg(long, default_value_t = 4)]
+    pub tab_width: usize,
+    /// Print spaces inside of curly braces in imports, `using` directives, and named arguments.
+    #[arg(long)]
+    pub bracket_spacing: bool,
+    /// Quotes to use for string literals.
+    #[arg(long, value_enum, default_value_t)]
+    pub quote_style: QuoteStyle,
+    /// Underscore style of number literals.
+    #[arg(long, value_enum, default_value_t)]
+    pub number_underscore: NumberUnderscore,
+}
+
+/// Arguments for `solar imports`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct ImportsArgs {
+    /// Files to print the import graph of, along with all of their imports.
+    #[arg(required = true, value_hint = ValueHint::FilePath)]
+    pub paths: Vec<PathBuf>,
+    /// Output format.
+    #[arg(long, value_enum, default_value_t)]
+    pub format: GraphFormat,
+    /// File to write the import graph to. Defaults to stdout.
+    #[arg(long, short, value_hint = ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments for `solar lsp`.
+///
+/// Import remappings and import paths are taken from the global options.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct LspArgs {}
+
+/// Arguments for `solar rename`.
+#[derive(Clone, Debug, clap::Args)]
+#[non_exhaustive]
+pub struct RenameArgs {
+    /// File containing the name to rename.
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub path: PathBuf,
+    /// Byte offset of the name in the file. This may be a declaration or any reference to it.
+    pub offset: usize,
+    /// The new name.
+    pub new_name: String,
+    /// Other files or directories to rename references in, such as the files that import `path`.
+    /// Directories are searched recursively for `.sol` files.
+    #[arg(value_hint = ValueHint::AnyPath)]
+    pub paths: Vec<PathBuf>,
+    /// Write the changes to the files instead of printing them as a unified diff.
+    #[arg(long, short)]
+    pub write: bool,
+}
+
 /// Internal options.
 #[derive(Clone, Debug, Default, Parser)]
 #[clap(
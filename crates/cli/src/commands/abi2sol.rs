@@ -0,0 +1,222 @@
+//! `solar abi2sol`.
+
+use crate::{cli::Abi2SolArgs, Compiler};
+use alloy_json_abi::{InternalType, JsonAbi, Param};
+use solar_interface::{source_map::FileName, Result, Session};
+use std::{io::Read, path::Path};
+
+impl Compiler {
+    /// Converts a JSON ABI into a Solidity interface, with structs reconstructed from the
+    /// `internalType` of parameters.
+    pub(super) fn run_abi2sol(&self, args: &Abi2SolArgs) -> Result<()> {
+        let sess = &self.sess;
+        let is_stdin = args.path == Path::new("-");
+        let json = if is_stdin {
+            let mut s = String::new();
+            std::io::stdin().read_to_string(&mut s).map(|_| s)
+        } else {
+            std::fs::read_to_string(&args.path)
+        }
+        .map_err(|e| sess.dcx.err(format!("failed to read {}: {e}", args.path.display())).emit())?;
+        let abi = parse_abi(&json)
+            .map_err(|e| sess.dcx.err(format!("failed to parse ABI: {e}")).emit())?;
+
+        let name = match &args.name {
+            Some(name) => name.clone(),
+            None if is_stdin => "Interface".to_string(),
+            None => {
+                let file_name = args.path.file_name().unwrap_or_default().to_string_lossy();
+                file_name.split('.').next().unwrap_or_default().to_string()
+            }
+        };
+        let sol = abi_to_sol(abi, &name);
+        if args.check {
+            check_sol(sess, &name, &sol)?;
+        }
+
+        match &args.output {
+            Some(path) => std::fs::write(path, sol).map_err(|e| {
+                sess.dcx.err(format!("failed to write to {}: {e}", path.display())).emit()
+            }),
+            None => {
+                print!("{sol}");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Formats a JSON ABI as a Solidity interface named `name`.
+fn abi_to_sol(mut abi: JsonAbi, name: &str) -> String {
+    qualify_file_level_types(&mut abi, name);
+    // Enums are printed as user-defined value types, which require 0.8.8.
+    let mut sol =
+        String::from("// SPDX-License-Identifier: UNLICENSED\npragma solidity ^0.8.8;\n\n");
+    abi.to_sol_raw(name, &mut sol, None);
+    sol.push('\n');
+    sol
+}
+
+/// Parses and resolves a generated interface, to check that it is valid.
+fn check_sol(sess: &Session, name: &str, sol: &str) -> Result<()> {
+    let file = sess
+        .source_map()
+        .new_source_file(FileName::Custom(format!("{name}.sol")), || Ok(sol.to_string()))
+        .map_err(|e| sess.dcx.err(e.to_string()).emit())?;
+    let mut pcx = solar_sema::ParsingContext::new(sess);
+    pcx.add_file(file);
+    pcx.parse_and_resolve()
+}
+
+/// Qualifies the file-level structs, enums and user-defined value types of `abi` with the name of
+/// the interface.
+///
+/// `to_sol_raw` declares file-level types in the interface, and the types of other contracts in
+/// libraries named after them, which must refer to the former by their qualified name.
+fn qualify_file_level_types(abi: &mut JsonAbi, name: &str) {
+    fn qualify(internal_type: &mut Option<InternalType>, components: &mut [Param], name: &str) {
+        if let Some(it) = internal_type {
+            let is_udvt = it.other_specifier().is_some_and(|s| s.try_basic_solidity().is_err());
+            match it {
+                InternalType::Struct { contract, .. } | InternalType::Enum { contract, .. } => {
+                    contract.get_or_insert_with(|| name.to_string());
+                }
+                InternalType::Other { contract, .. } if is_udvt => {
+                    contract.get_or_insert_with(|| name.to_string());
+                }
+                _ => {}
+            }
+        }
+        for param in components {
+            qualify(&mut param.internal_type, &mut param.components, name);
+        }
+    }
+
+    let JsonAbi { constructor, fallback: _, receive: _, functions, events, errors } = abi;
+    let functions = functions.values_mut().flatten();
+    let params = constructor
+        .iter_mut()
+        .flat_map(|c| &mut c.inputs)
+        .chain(functions.flat_map(|f| f.inputs.iter_mut().chain(&mut f.outputs)))
+        .chain(errors.values_mut().flatten().flat_map(|e| &mut e.inputs));
+    for param in params {
+        qualify(&mut param.internal_type, &mut param.components, name);
+    }
+    for param in events.values_mut().flatten().flat_map(|e| &mut e.inputs) {
+        qualify(&mut param.internal_type, &mut param.components, name);
+    }
+}
+
+/// Parses a JSON ABI, or the `abi` field of a compiler artifact.
+fn parse_abi(json: &str) -> serde_json::Result<JsonAbi> {
+    let mut value = serde_json::from_str::<serde_json::Value>(json)?;
+    if let Some(abi) = value.get_mut("abi") {
+        value = abi.take();
+    }
+    serde_json::from_value(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_abi_or_artifact() {
+        let abi =
+            r#"[{"type":"function","name":"f","inputs":[],"outputs":[],"stateMutability":"view"}]"#;
+        let artifact = format!(r#"{{"abi":{abi},"bytecode":"0x"}}"#);
+        let abi = parse_abi(abi).unwrap();
+        assert_eq!(abi.functions().count(), 1);
+        assert_eq!(parse_abi(&artifact).unwrap(), abi);
+        assert!(parse_abi(r#"{"bytecode":"0x"}"#).is_err());
+    }
+
+    #[test]
+    fn nested_types() {
+        // `solc` output for:
+        // ```solidity
+        // type Price is uint128;
+        // enum Side { Buy, Sell }
+        // struct Fill { uint256 amount; Side side; }
+        // library Types { struct Meta { Price price; } }
+        // contract Exchange {
+        //     enum Status { Open, Closed }
+        //     struct Order { address maker; Side side; Fill[] fills; Types.Meta meta; Status status; }
+        //     event Placed(Fill fill);
+        //     error Invalid(Order order);
+        //     function place(Order calldata order) external returns (Status) {}
+        // }
+        // ```
+        let artifact = r#"{"abi":[
+            {"type":"function","name":"place","stateMutability":"nonpayable","inputs":[
+                {"name":"order","type":"tuple","internalType":"struct Exchange.Order","components":[
+                    {"name":"maker","type":"address","internalType":"address"},
+                    {"name":"side","type":"uint8","internalType":"enum Side"},
+                    {"name":"fills","type":"tuple[]","internalType":"struct Fill[]","components":[
+                        {"name":"amount","type":"uint256","internalType":"uint256"},
+                        {"name":"side","type":"uint8","internalType":"enum Side"}]},
+                    {"name":"meta","type":"tuple","internalType":"struct Types.Meta","components":[
+                        {"name":"price","type":"uint128","internalType":"Price"}]},
+                    {"name":"status","type":"uint8","internalType":"enum Exchange.Status"}]}],
+            "outputs":[{"name":"","type":"uint8","internalType":"enum Exchange.Status"}]},
+            {"type":"event","name":"Placed","anonymous":false,"inputs":[
+                {"name":"fill","type":"tuple","indexed":false,"internalType":"struct Fill","components":[
+                    {"name":"amount","type":"uint256","internalType":"uint256"},
+                    {"name":"side","type":"uint8","internalType":"enum Side"}]}]},
+            {"type":"error","name":"Invalid","inputs":[
+                {"name":"order","type":"tuple","internalType":"struct Exchange.Order","components":[
+                    {"name":"maker","type":"address","internalType":"address"},
+                    {"name":"side","type":"uint8","internalType":"enum Side"},
+                    {"name":"fills","type":"tuple[]","internalType":"struct Fill[]","components":[
+                        {"name":"amount","type":"uint256","internalType":"uint256"},
+                        {"name":"side","type":"uint8","internalType":"enum Side"}]},
+                    {"name":"meta","type":"tuple","internalType":"struct Types.Meta","components":[
+                        {"name":"price","type":"uint128","internalType":"Price"}]},
+                    {"name":"status","type":"uint8","internalType":"enum Exchange.Status"}]}]}
+        ],"bytecode":"0x"}"#;
+        let sol = abi_to_sol(parse_abi(artifact).unwrap(), "IExchange");
+        let expected = "\
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.8;
+
+library Exchange {
+    type Status is uint8;
+    struct Order {
+        address maker;
+        IExchange.Side side;
+        IExchange.Fill[] fills;
+        Types.Meta meta;
+        Status status;
+    }
+}
+
+library Types {
+    struct Meta {
+        IExchange.Price price;
+    }
+}
+
+interface IExchange {
+    type Side is uint8;
+    type Price is uint128;
+    struct Fill {
+        uint256 amount;
+        Side side;
+    }
+
+    error Invalid(Exchange.Order order);
+
+    event Placed(Fill fill);
+
+    function place(Exchange.Order memory order) external returns (Exchange.Status);
+}
+";
+        assert_eq!(sol, expected);
+
+        crate::utils::run_in_thread_pool_with_globals(1, |_| {
+            let sess = Session::builder().with_test_emitter().build();
+            sess.enter(|| check_sol(&sess, "IExchange", &sol))
+        })
+        .unwrap();
+    }
+}
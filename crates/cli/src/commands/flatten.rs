@@ -0,0 +1,30 @@
+//! `solar flatten`.
+
+use crate::{cli::FlattenArgs, Compiler};
+use solar_interface::Result;
+use solar_sema::{ast, thread_local::ThreadLocal};
+
+impl Compiler {
+    /// Flattens the given sources and their imports into a single Solidity source.
+    pub(super) fn run_flatten(&self, args: &FlattenArgs) -> Result<()> {
+        let sess = &self.sess;
+        let mut pcx = self.parsing_context()?;
+        pcx.load_files(&args.paths)?;
+
+        let arenas = ThreadLocal::<ast::Arena>::new();
+        let mut sources = pcx.parse(&arenas);
+        sess.dcx.has_errors()?;
+        sources.topo_sort();
+        let flattened = solar_sema::flatten(sess, &sources)?;
+
+        match &args.output {
+            Some(path) => std::fs::write(path, flattened).map_err(|e| {
+                sess.dcx.err(format!("failed to write to {}: {e}", path.display())).emit()
+            }),
+            None => {
+                print!("{flattened}");
+                Ok(())
+            }
+        }
+    }
+}
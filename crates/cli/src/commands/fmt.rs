@@ -0,0 +1,232 @@
+//! `solar fmt`.
+
+use crate::{cli::FmtArgs, Compiler};
+use solar_ast::print::{PrintConfig, Printer};
+use solar_interface::{source_map::SourceFile, Result, Session};
+use solar_parse::{lexer::gather_comments, Parser};
+use solar_sema::ast;
+use std::path::{Path, PathBuf};
+
+impl Compiler {
+    /// Formats the given sources in place, or checks that they are formatted.
+    pub(super) fn run_fmt(&self, args: &FmtArgs) -> Result<()> {
+        let sess = &self.sess;
+        let config = PrintConfig {
+            line_width: args.line_width,
+            tab_width: args.tab_width,
+            bracket_spacing: args.bracket_spacing,
+            quote_style: args.quote_style,
+            number_underscore: args.number_underscore,
+        };
+
+        let mut paths = Vec::new();
+        for path in &args.paths {
+            collect_sol_files(path, &mut paths).map_err(|e| {
+                sess.dcx.err(format!("failed to read {}: {e}", path.display())).emit()
+            })?;
+        }
+
+        let mut failed = false;
+        let mut unformatted = 0;
+        for path in &paths {
+            let file = match sess.source_map().load_file(path) {
+                Ok(file) => file,
+                Err(e) => {
+                    sess.dcx.err(format!("failed to read {}: {e}", path.display())).emit();
+                    failed = true;
+                    continue;
+                }
+            };
+            let Ok(formatted) = format_file(sess, &file, &config) else {
+                failed = true;
+                continue;
+            };
+            if formatted == *file.src {
+                continue;
+            }
+            if args.check {
+                println!("{}", path.display());
+                unformatted += 1;
+            } else if let Err(e) = std::fs::write(path, formatted) {
+                sess.dcx.err(format!("failed to write to {}: {e}", path.display())).emit();
+                failed = true;
+            }
+        }
+
+        if unformatted > 0 {
+            let s = if unformatted == 1 { "" } else { "s" };
+            return Err(sess.dcx.err(format!("{unformatted} file{s} not formatted")).emit());
+        }
+        if failed {
+            return Err(sess.dcx.err("failed to format all files").emit());
+        }
+        Ok(())
+    }
+}
+
+/// Parses and formats a source file. Parse errors are emitted.
+fn format_file(sess: &Session, file: &SourceFile, config: &PrintConfig) -> Result<String> {
+    let errors = sess.dcx.err_count();
+    let arena = ast::Arena::new();
+    let mut parser = Parser::from_source_file(sess, &arena, file);
+    let source_unit = parser.parse_file().map_err(|e| e.emit())?;
+    if sess.dcx.err_count() > errors {
+        return Err(sess.dcx.err(format!("failed to parse {}", file.name.display())).emit());
+    }
+    let comments = gather_comments(file);
+    Ok(Printer::new(config.clone()).with_source(file, comments).print_source_unit(&source_unit))
+}
+
+/// Collects `path` if it is a file, or all the `.sol` files in it recursively if it is a directory.
+pub(super) fn collect_sol_files(path: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        out.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect_sol_files(&entry, out)?;
+        } else if entry.extension().is_some_and(|ext| ext == "sol") {
+            out.push(entry);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::{
+        config::{NumberUnderscore, QuoteStyle},
+        source_map::FileName,
+    };
+
+    fn format(src: &str, config: &PrintConfig) -> String {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| {
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::Real("test.sol".into()), || Ok(src.to_string()))
+                .unwrap();
+            format_file(&sess, &file, config).unwrap()
+        })
+    }
+
+    #[track_caller]
+    fn assert_formats(src: &str, expected: &str, config: &PrintConfig) {
+        let formatted = format(src, config);
+        assert_eq!(formatted, expected);
+        assert_eq!(format(&formatted, config), expected, "formatting is not idempotent");
+    }
+
+    #[test]
+    fn comments() {
+        let src = "\
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+import {A,B} from 'a.sol'; // trailing
+
+
+/// Docs.
+contract C is A,B {
+    uint x = 1; /* block */
+    // Leading.
+    function f(uint a) public pure returns (uint) {
+        // Inside.
+        uint b = a + /* mixed */ 1;
+
+        return b; // Returned.
+        // Last.
+    }
+
+    function g(
+        uint a, // first param
+        uint b // last param
+    ) public {}
+
+    function h() public // trailing header
+    {
+        g(1, 2);
+    }
+}
+";
+        let expected = "\
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+import {A, B} from \"a.sol\"; // trailing
+
+/// Docs.
+contract C is A, B {
+    uint x = 1; /* block */
+
+    // Leading.
+    function f(uint a) public pure returns (uint) {
+        // Inside.
+        uint b = a + /* mixed */ 1;
+
+        return b; // Returned.
+        // Last.
+    }
+
+    function g(
+        uint a, // first param
+        uint b // last param
+    ) public {}
+
+    function h()
+        public // trailing header
+    {
+        g(1, 2);
+    }
+}
+";
+        assert_formats(src, expected, &PrintConfig::default());
+    }
+
+    #[test]
+    fn line_width() {
+        let src = "contract C { function f() public { g(aaaaaaaa, bbbbbbbb, cccccccc); } }";
+        let expected = "\
+contract C {
+    function f() public {
+        g(
+            aaaaaaaa,
+            bbbbbbbb,
+            cccccccc
+        );
+    }
+}
+";
+        let config = PrintConfig { line_width: 30, ..Default::default() };
+        assert_formats(src, expected, &config);
+    }
+
+    #[test]
+    fn options() {
+        let src = "\
+import {A} from \"a.sol\";
+contract C { string s = \"it's\"; string t = 'a'; uint n = 1_000 + 1000000; }
+";
+        let expected = "\
+import { A } from 'a.sol';
+
+contract C {
+    string s = \"it's\";
+    string t = 'a';
+    uint n = 1000 + 1_000_000;
+}
+";
+        let config = PrintConfig {
+            tab_width: 4,
+            bracket_spacing: true,
+            quote_style: QuoteStyle::Single,
+            number_underscore: NumberUnderscore::Thousands,
+            ..Default::default()
+        };
+        assert_formats(src, expected, &config);
+    }
+}
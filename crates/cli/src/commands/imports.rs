@@ -0,0 +1,154 @@
+//! `solar imports`.
+
+use crate::{cli::ImportsArgs, Compiler};
+use solar_interface::{config::GraphFormat, Result};
+use solar_sema::{ast, hir::SourceId, thread_local::ThreadLocal, ParsedSources};
+use std::fmt::Write;
+
+impl Compiler {
+    /// Prints the resolved import graph of the given sources and their imports.
+    pub(super) fn run_imports(&self, args: &ImportsArgs) -> Result<()> {
+        let sess = &self.sess;
+        let mut pcx = self.parsing_context()?;
+        pcx.load_files(&args.paths)?;
+
+        let arenas = ThreadLocal::<ast::Arena>::new();
+        let mut sources = pcx.parse(&arenas);
+        sess.dcx.has_errors()?;
+        sources.topo_sort();
+
+        let mut out = match args.format {
+            GraphFormat::Json => {
+                let json = to_json(&sources);
+                if self.args.pretty_json {
+                    serde_json::to_string_pretty(&json).unwrap()
+                } else {
+                    json.to_string()
+                }
+            }
+            GraphFormat::Dot => to_dot(&sources),
+        };
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+
+        match &args.output {
+            Some(path) => std::fs::write(path, out).map_err(|e| {
+                sess.dcx.err(format!("failed to write to {}: {e}", path.display())).emit()
+            }),
+            None => {
+                print!("{out}");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Returns the import graph as JSON: the sources with their resolved imports, and the import
+/// cycles as lists of source IDs.
+fn to_json(sources: &ParsedSources<'_>) -> serde_json::Value {
+    let path = |id: SourceId| sources[id].file.name.display().to_string();
+    let sources_json = sources
+        .iter_enumerated()
+        .map(|(id, source)| {
+            let imports = source
+                .imports
+                .iter()
+                .map(|&(item_id, import_id)| {
+                    serde_json::json!({
+                        "item": item_id.get(),
+                        "source": import_id.get(),
+                        "path": path(import_id),
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "id": id.get(), "path": path(id), "imports": imports })
+        })
+        .collect::<Vec<_>>();
+    let cycles = sources
+        .import_cycles()
+        .into_iter()
+        .map(|cycle| cycle.into_iter().map(|id| id.get()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    serde_json::json!({ "sources": sources_json, "cycles": cycles })
+}
+
+/// Returns the import graph in Graphviz DOT format. Imports that are part of a cycle are red.
+fn to_dot(sources: &ParsedSources<'_>) -> String {
+    let cycles = sources.import_cycles();
+    let in_cycle = |a, b| cycles.iter().any(|cycle| cycle.contains(&a) && cycle.contains(&b));
+
+    let mut out = String::from("digraph imports {\n");
+    for (id, source) in sources.iter_enumerated() {
+        let label = source.file.name.display().to_string();
+        let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+        writeln!(out, "    {} [label=\"{label}\"];", id.get()).unwrap();
+    }
+    for (id, source) in sources.iter_enumerated() {
+        for &(_, import_id) in &source.imports {
+            write!(out, "    {} -> {}", id.get(), import_id.get()).unwrap();
+            if in_cycle(id, import_id) {
+                out.push_str(" [color=red]");
+            }
+            out.push_str(";\n");
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::{source_map::FileName, Session};
+    use solar_sema::ParsingContext;
+
+    #[test]
+    fn import_graph() {
+        let files = [
+            ("a.sol", "import \"b.sol\";\nimport {C} from \"c.sol\";"),
+            ("b.sol", "import \"c.sol\";"),
+            ("c.sol", "import \"b.sol\"; contract C {}"),
+        ];
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| {
+            let mut pcx = ParsingContext::new(&sess);
+            for (name, src) in files {
+                let file = sess
+                    .source_map()
+                    .new_source_file(FileName::Real(name.into()), || Ok(src.to_string()))
+                    .unwrap();
+                pcx.add_file(file);
+            }
+            let arenas = ThreadLocal::new();
+            let mut sources = pcx.parse(&arenas);
+            sources.topo_sort();
+
+            let json = serde_json::json!({
+                "sources": [
+                    { "id": 0, "path": "c.sol", "imports": [{ "item": 0, "source": 1, "path": "b.sol" }] },
+                    { "id": 1, "path": "b.sol", "imports": [{ "item": 0, "source": 0, "path": "c.sol" }] },
+                    { "id": 2, "path": "a.sol", "imports": [
+                        { "item": 0, "source": 1, "path": "b.sol" },
+                        { "item": 1, "source": 0, "path": "c.sol" },
+                    ] },
+                ],
+                "cycles": [[0, 1]],
+            });
+            assert_eq!(to_json(&sources), json);
+
+            let dot = "\
+digraph imports {
+    0 [label=\"c.sol\"];
+    1 [label=\"b.sol\"];
+    2 [label=\"a.sol\"];
+    0 -> 1 [color=red];
+    1 -> 0 [color=red];
+    2 -> 1;
+    2 -> 0;
+}
+";
+            assert_eq!(to_dot(&sources), dot);
+        });
+    }
+}
@@ -0,0 +1,513 @@
+//! Workspace analysis for the language server.
+
+use super::path_to_uri;
+use lsp_types as lsp;
+use solar_interface::{
+    diagnostics::{DiagCtxt, Diagnostic, Emitter, Level},
+    source_map::{FileName, SourceFile},
+    BytePos, Ident, Pos, Session, Span, Symbol,
+};
+use solar_sema::{
+    builtins::Builtin,
+    hir::{self, Visit},
+    ty::{Gcx, TyKind},
+    ParsingContext,
+};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Options used to analyze the workspace.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct AnalysisOptions {
+    /// Import remappings, as `(map, path)` pairs.
+    pub(crate) import_maps: Vec<(PathBuf, PathBuf)>,
+    /// Directories to search for imported files.
+    pub(crate) import_paths: Vec<PathBuf>,
+}
+
+/// The result of analyzing a set of files.
+#[derive(Debug, Default)]
+pub(crate) struct Analysis {
+    /// Diagnostics of each analyzed file, including files without diagnostics.
+    pub(crate) diagnostics: HashMap<PathBuf, Vec<lsp::Diagnostic>>,
+    files: HashMap<PathBuf, FileIndex>,
+}
+
+#[derive(Debug, Default)]
+struct FileIndex {
+    /// Names that refer to an item, including the names in item declarations.
+    references: Vec<Reference>,
+    /// The document symbols of the file.
+    symbols: Vec<lsp::DocumentSymbol>,
+}
+
+#[derive(Debug)]
+struct Reference {
+    range: lsp::Range,
+    definition: lsp::Location,
+    /// The declaration of the referenced item.
+    declaration: String,
+}
+
+impl Analysis {
+    /// Parses and analyzes the given files and the files they import.
+    ///
+    /// `documents` contains the contents of open documents, which take precedence over the files on
+    /// disk.
+    pub(crate) fn run(
+        options: &AnalysisOptions,
+        files: &[PathBuf],
+        documents: &HashMap<PathBuf, String>,
+    ) -> Self {
+        let mut this = Self::default();
+        if files.is_empty() {
+            return this;
+        }
+
+        let diagnostics = Arc::new(Mutex::new(Vec::new()));
+        let collector = DiagnosticCollector(diagnostics.clone());
+        let dcx = DiagCtxt::new(Box::new(collector)).set_flags(|f| f.track_diagnostics = false);
+        let sess = Session::builder().dcx(dcx).build();
+        sess.enter(|| {
+            let pcx = parsing_context(&sess, options, files, documents);
+            let sm = sess.source_map();
+            let _ = solar_sema::parse_and_resolve_with(pcx, |gcx| {
+                let mut indexer = Indexer { gcx, contract: None, files: HashMap::new() };
+                indexer.index();
+                this.files = indexer.files;
+            });
+
+            for file in sm.files().iter() {
+                if let Some(path) = file_path(file) {
+                    this.diagnostics.entry(path).or_default();
+                }
+            }
+            for diag in diagnostics.lock().unwrap().iter() {
+                if let Some((path, diag)) = convert_diagnostic(&sess, diag) {
+                    this.diagnostics.entry(path).or_default().push(diag);
+                }
+            }
+        });
+        this
+    }
+
+    /// Renames the name at the given position in `path`, by analyzing the given files and
+    /// documents like [`run`](Self::run).
+    ///
+    /// Returns an error message if there is no renameable name at the position, or if the new name
+    /// is invalid.
+    pub(crate) fn rename(
+        options: &AnalysisOptions,
+        files: &[PathBuf],
+        documents: &HashMap<PathBuf, String>,
+        path: &Path,
+        pos: lsp::Position,
+        new_name: &str,
+    ) -> Result<lsp::WorkspaceEdit, String> {
+        let sess = Session::builder().with_silent_emitter(None).build();
+        sess.enter(|| {
+            let pcx = parsing_context(&sess, options, files, documents);
+            let name = FileName::Real(path.to_path_buf());
+            let pos = sess
+                .source_map()
+                .source_file_by_file_name(&name)
+                .and_then(|file| byte_pos(&file, pos))
+                .ok_or_else(|| format!("{} is not analyzed", path.display()))?;
+
+            let mut edits = None;
+            let _ = solar_sema::parse_and_resolve_with(pcx, |gcx| {
+                edits = Some(gcx.rename(pos, new_name));
+            });
+            let edits =
+                edits.ok_or("failed to analyze the workspace")?.map_err(|e| e.to_string())?;
+
+            // Edits are sorted by span, so the edits of each file are contiguous.
+            let mut changes = Vec::<lsp::TextDocumentEdit>::new();
+            for edit in edits {
+                let Some((path, range)) = span_range(&sess, edit.span) else { continue };
+                let uri = path_to_uri(&path);
+                let edit = lsp::OneOf::Left(lsp::TextEdit { range, new_text: edit.text });
+                match changes.last_mut() {
+                    Some(change) if change.text_document.uri == uri => change.edits.push(edit),
+                    _ => changes.push(lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri,
+                            version: None,
+                        },
+                        edits: vec![edit],
+                    }),
+                }
+            }
+            changes.sort_by(|a, b| a.text_document.uri.as_str().cmp(b.text_document.uri.as_str()));
+            let changes = lsp::DocumentChanges::Edits(changes);
+            Ok(lsp::WorkspaceEdit { document_changes: Some(changes), ..Default::default() })
+        })
+    }
+
+    /// Returns the location of the definition of the name at the given position.
+    pub(crate) fn definition(&self, path: &Path, pos: lsp::Position) -> Option<lsp::Location> {
+        self.reference(path, pos).map(|r| r.definition.clone())
+    }
+
+    /// Returns the hover information of the name at the given position.
+    pub(crate) fn hover(&self, path: &Path, pos: lsp::Position) -> Option<lsp::Hover> {
+        let reference = self.reference(path, pos)?;
+        Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value: format!("```solidity\n{}\n```", reference.declaration),
+            }),
+            range: Some(reference.range),
+        })
+    }
+
+    /// Returns the document symbols of the given file.
+    pub(crate) fn document_symbols(&self, path: &Path) -> Vec<lsp::DocumentSymbol> {
+        self.files.get(path).map(|file| file.symbols.clone()).unwrap_or_default()
+    }
+
+    fn reference(&self, path: &Path, pos: lsp::Position) -> Option<&Reference> {
+        let file = self.files.get(path)?;
+        file.references.iter().filter(|r| contains(r.range, pos)).min_by_key(|r| {
+            let (start, end) = (r.range.start, r.range.end);
+            (end.line - start.line, end.character.wrapping_sub(start.character))
+        })
+    }
+}
+
+/// Emitter that collects the emitted diagnostics.
+struct DiagnosticCollector(Arc<Mutex<Vec<Diagnostic>>>);
+
+impl Emitter for DiagnosticCollector {
+    fn emit_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        self.0.lock().unwrap().push(diagnostic.clone());
+    }
+}
+
+fn convert_diagnostic(sess: &Session, diag: &Diagnostic) -> Option<(PathBuf, lsp::Diagnostic)> {
+    let span = diag.span.primary_span().filter(|span| !span.is_dummy())?;
+    let (path, range) = span_range(sess, span)?;
+    let severity = match diag.level() {
+        Level::Bug | Level::Fatal | Level::Error => lsp::DiagnosticSeverity::ERROR,
+        Level::Warning => lsp::DiagnosticSeverity::WARNING,
+        Level::Note | Level::OnceNote | Level::FailureNote | Level::Allow => {
+            lsp::DiagnosticSeverity::INFORMATION
+        }
+        Level::Help | Level::OnceHelp => lsp::DiagnosticSeverity::HINT,
+    };
+    let mut message = diag.label().into_owned();
+    for child in &diag.children {
+        message.push_str(&format!("\n{}: {}", child.level.to_str(), child.label()));
+    }
+    let diag = lsp::Diagnostic {
+        range,
+        severity: Some(severity),
+        code: diag.id().map(lsp::NumberOrString::String),
+        source: Some("solar".into()),
+        message,
+        ..Default::default()
+    };
+    Some((path, diag))
+}
+
+/// Builds the [`FileIndex`] of every source.
+struct Indexer<'gcx> {
+    gcx: Gcx<'gcx>,
+    /// The contract being visited.
+    contract: Option<hir::ContractId>,
+    files: HashMap<PathBuf, FileIndex>,
+}
+
+impl<'gcx> Indexer<'gcx> {
+    fn index(&mut self) {
+        let gcx = self.gcx;
+        for id in gcx.hir.item_ids() {
+            if let hir::ItemId::Function(f) = id {
+                if gcx.hir.function(f).is_getter() {
+                    continue;
+                }
+            }
+            if let Some(name) = gcx.item_name_opt(id) {
+                self.add_reference(name.span, id);
+            }
+        }
+        for id in gcx.hir.source_ids() {
+            let source = gcx.hir.source(id);
+            let Some(path) = file_path(&source.file) else { continue };
+            let symbols = source.items.iter().filter_map(|&item| self.symbol(item)).collect();
+            self.files.entry(path).or_default().symbols = symbols;
+            let _ = self.visit_nested_source(id);
+        }
+    }
+
+    fn add_reference(&mut self, span: Span, id: hir::ItemId) {
+        let gcx = self.gcx;
+        let Some(name) = gcx.item_name_opt(id) else { return };
+        let Some((path, range)) = span_range(gcx.sess, span) else { return };
+        let Some((def_path, def_range)) = span_range(gcx.sess, name.span) else { return };
+        let definition = lsp::Location { uri: path_to_uri(&def_path), range: def_range };
+        let declaration = gcx.item_declaration(id);
+        self.files.entry(path).or_default().references.push(Reference {
+            range,
+            definition,
+            declaration,
+        });
+    }
+
+    #[allow(deprecated)]
+    fn symbol(&self, id: hir::ItemId) -> Option<lsp::DocumentSymbol> {
+        let gcx = self.gcx;
+        let item = gcx.hir.item(id);
+        let mut children = Vec::new();
+        let (name, kind) = match item {
+            hir::Item::Contract(contract) => {
+                let items = contract.items.iter().copied();
+                let special = [contract.ctor, contract.fallback, contract.receive];
+                let functions = special.into_iter().flatten().map(hir::ItemId::Function);
+                children.extend(functions.chain(items).filter_map(|item| self.symbol(item)));
+                let kind = match contract.kind {
+                    hir::ContractKind::Interface => lsp::SymbolKind::INTERFACE,
+                    hir::ContractKind::Library => lsp::SymbolKind::MODULE,
+                    _ => lsp::SymbolKind::CLASS,
+                };
+                (contract.name, kind)
+            }
+            hir::Item::Function(f) => {
+                if f.is_getter() {
+                    return None;
+                }
+                let kind = match f.kind {
+                    hir::FunctionKind::Constructor => lsp::SymbolKind::CONSTRUCTOR,
+                    hir::FunctionKind::Modifier => lsp::SymbolKind::METHOD,
+                    _ => lsp::SymbolKind::FUNCTION,
+                };
+                // Unnamed functions are named by their keyword.
+                let name = f.name.unwrap_or_else(|| {
+                    let span = f.span.with_hi(f.span.lo() + BytePos(f.kind.to_str().len() as u32));
+                    Ident::new(Symbol::intern(f.kind.to_str()), span)
+                });
+                (name, kind)
+            }
+            hir::Item::Variable(var) => {
+                let kind = if var.mutability == Some(hir::VarMut::Constant) {
+                    lsp::SymbolKind::CONSTANT
+                } else {
+                    lsp::SymbolKind::FIELD
+                };
+                (var.name?, kind)
+            }
+            hir::Item::Struct(strukt) => {
+                let fields = strukt.fields.iter().map(|&f| hir::ItemId::Variable(f));
+                children.extend(fields.filter_map(|field| self.symbol(field)));
+                (strukt.name, lsp::SymbolKind::STRUCT)
+            }
+            hir::Item::Enum(enumm) => {
+                for &variant in enumm.variants {
+                    let Some((_, range)) = span_range(gcx.sess, variant.span) else { continue };
+                    children.push(lsp::DocumentSymbol {
+                        name: variant.to_string(),
+                        detail: None,
+                        kind: lsp::SymbolKind::ENUM_MEMBER,
+                        tags: None,
+                        deprecated: None,
+                        range,
+                        selection_range: range,
+                        children: None,
+                    });
+                }
+                (enumm.name, lsp::SymbolKind::ENUM)
+            }
+            hir::Item::Udvt(udvt) => (udvt.name, lsp::SymbolKind::TYPE_PARAMETER),
+            hir::Item::Event(event) => (event.name, lsp::SymbolKind::EVENT),
+            hir::Item::Error(error) => (error.name, lsp::SymbolKind::OBJECT),
+        };
+        let (_, range) = span_range(gcx.sess, item.span())?;
+        let (_, selection_range) = span_range(gcx.sess, name.span)?;
+        Some(lsp::DocumentSymbol {
+            name: name.to_string(),
+            detail: Some(gcx.item_declaration(id)),
+            kind,
+            tags: None,
+            deprecated: None,
+            range,
+            selection_range,
+            children: (!children.is_empty()).then_some(children),
+        })
+    }
+
+    /// Resolves an expression that refers to an item, a namespace or a builtin.
+    fn resolve(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<hir::Res> {
+        match expr.peel_parens().kind {
+            hir::ExprKind::Ident(res) => res.first().copied(),
+            hir::ExprKind::Member(base, member) => {
+                self.member(self.resolve(base)?, member).map(hir::Res::Item)
+            }
+            // Conversions to contract types, like `IERC20(token)`.
+            hir::ExprKind::Call(callee, _) => match self.resolve(callee)? {
+                res @ hir::Res::Item(hir::ItemId::Contract(_)) => Some(res),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Resolves `base.member` to the item it refers to.
+    fn member(&self, base: hir::Res, member: Ident) -> Option<hir::ItemId> {
+        let gcx = self.gcx;
+        let named =
+            |id: &hir::ItemId| gcx.item_name_opt(*id).is_some_and(|n| n.name == member.name);
+        match base {
+            hir::Res::Item(hir::ItemId::Contract(c)) => gcx.hir.contract_item_ids(c).find(named),
+            hir::Res::Namespace(source) => gcx.hir.source(source).items.iter().copied().find(named),
+            hir::Res::Item(hir::ItemId::Variable(var)) => {
+                let mut ty = gcx.type_of_item(var.into());
+                while let TyKind::Ref(inner, _) = ty.kind {
+                    ty = inner;
+                }
+                match ty.kind {
+                    TyKind::Contract(c) => gcx.hir.contract_item_ids(c).find(named),
+                    TyKind::Struct(s) => {
+                        gcx.hir.strukt(s).fields.iter().map(|&f| f.into()).find(named)
+                    }
+                    _ => None,
+                }
+            }
+            hir::Res::Builtin(Builtin::This) => {
+                gcx.hir.contract_item_ids(self.contract?).find(named)
+            }
+            hir::Res::Builtin(Builtin::Super) => gcx.hir.contract(self.contract?).linearized_bases
+                [1..]
+                .iter()
+                .find_map(|&base| gcx.hir.contract(base).items.iter().copied().find(named)),
+            _ => None,
+        }
+    }
+}
+
+impl<'gcx> Visit<'gcx> for Indexer<'gcx> {
+    type BreakValue = Infallible;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_nested_contract(&mut self, id: hir::ContractId) -> ControlFlow<Self::BreakValue> {
+        let contract = self.gcx.hir.contract(id);
+        let prev = self.contract.replace(id);
+        self.walk_contract(contract)?;
+        for f in [contract.ctor, contract.fallback, contract.receive].into_iter().flatten() {
+            self.visit_nested_function(f)?;
+        }
+        self.contract = prev;
+        ControlFlow::Continue(())
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            hir::ExprKind::Ident(res) => {
+                if let Some(&hir::Res::Item(id)) = res.first() {
+                    self.add_reference(expr.span, id);
+                }
+            }
+            hir::ExprKind::Member(base, member) => {
+                if let Some(id) = self.resolve(base).and_then(|base| self.member(base, member)) {
+                    self.add_reference(member.span, id);
+                }
+            }
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+
+    fn visit_ty(&mut self, ty: &'gcx hir::Type<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let hir::TypeKind::Custom(id) = ty.kind {
+            self.add_reference(ty.span, id);
+        }
+        self.walk_ty(ty)
+    }
+}
+
+/// Returns the path of a file on disk.
+fn file_path(file: &SourceFile) -> Option<PathBuf> {
+    match &file.name {
+        FileName::Real(path) if path.is_absolute() => Some(path.clone()),
+        FileName::Real(path) => Some(std::env::current_dir().ok()?.join(path)),
+        _ => None,
+    }
+}
+
+/// Creates a parsing context for the given files. Open documents are added to the source map first,
+/// so that imports resolve to them.
+fn parsing_context<'sess>(
+    sess: &'sess Session,
+    options: &AnalysisOptions,
+    files: &[PathBuf],
+    documents: &HashMap<PathBuf, String>,
+) -> ParsingContext<'sess> {
+    let mut pcx = ParsingContext::new(sess);
+    for (map, path) in &options.import_maps {
+        pcx.file_resolver.add_import_map(map.clone(), path.clone());
+    }
+    for path in &options.import_paths {
+        pcx.file_resolver.add_import_path(path.clone());
+    }
+
+    let sm = sess.source_map();
+    for (path, text) in documents {
+        let name = FileName::Real(path.clone());
+        let _ = sm.new_source_file(name, || Ok(text.clone()));
+    }
+    for path in files {
+        let name = FileName::Real(path.clone());
+        match sm.new_source_file(name, || std::fs::read_to_string(path)) {
+            Ok(file) => pcx.add_file(file),
+            Err(e) => {
+                let msg = format!("failed to read {}: {e}", path.display());
+                let _ = sess.dcx.err(msg).emit();
+            }
+        }
+    }
+    pcx
+}
+
+/// Returns the file path and LSP range of a span.
+fn span_range(sess: &Session, span: Span) -> Option<(PathBuf, lsp::Range)> {
+    let file = sess.source_map().lookup_source_file(span.lo());
+    let path = file_path(&file)?;
+    let range = lsp::Range { start: position(&file, span.lo()), end: position(&file, span.hi()) };
+    Some((path, range))
+}
+
+/// Returns the LSP position of a byte position, with the column in UTF-16 code units.
+fn position(file: &SourceFile, pos: BytePos) -> lsp::Position {
+    let offset = file.relative_position(pos).to_usize();
+    let line = file.lookup_line(file.relative_position(pos)).unwrap_or(0);
+    let line_start = file.lines()[line].to_usize();
+    let character = file.src[line_start..offset].encode_utf16().count();
+    lsp::Position { line: line as u32, character: character as u32 }
+}
+
+/// Returns the byte position of an LSP position, with the column in UTF-16 code units.
+fn byte_pos(file: &SourceFile, pos: lsp::Position) -> Option<BytePos> {
+    let line_start = file.lines().get(pos.line as usize)?.to_usize();
+    let mut offset = line_start;
+    let mut character = 0;
+    for c in file.src[line_start..].chars() {
+        if character >= pos.character as usize || c == '\n' {
+            break;
+        }
+        character += c.len_utf16();
+        offset += c.len_utf8();
+    }
+    Some(file.start_pos + BytePos::from_usize(offset))
+}
+
+fn contains(range: lsp::Range, pos: lsp::Position) -> bool {
+    let key = |pos: lsp::Position| (pos.line, pos.character);
+    key(range.start) <= key(pos) && key(pos) <= key(range.end)
+}
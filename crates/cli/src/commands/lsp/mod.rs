@@ -0,0 +1,553 @@
+//! `solar lsp`.
+
+use crate::{cli::LspArgs, Compiler};
+use analysis::{Analysis, AnalysisOptions};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    self as lsp,
+    notification::{self, Notification as _},
+    request::{self, Request as _},
+    Uri,
+};
+use solar_interface::Result;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+mod analysis;
+
+impl Compiler {
+    /// Runs a language server over stdin and stdout.
+    pub(super) fn run_lsp(&self, _args: &LspArgs) -> Result<()> {
+        let sess = &self.sess;
+        let options = AnalysisOptions {
+            import_maps: sess.import_maps.clone(),
+            import_paths: self.args.import_path.clone(),
+        };
+        let (connection, io_threads) = Connection::stdio();
+        Server::new(connection, options)
+            .run()
+            .map_err(|e| sess.dcx.err(format!("language server error: {e}")).emit())?;
+        io_threads.join().map_err(|e| sess.dcx.err(format!("language server error: {e}")).emit())
+    }
+}
+
+type ServerResult<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+/// A language server for Solidity.
+///
+/// The workspace is re-analyzed when a document is opened or saved. Requests are answered using the
+/// results of the last analysis.
+struct Server {
+    connection: Connection,
+    options: AnalysisOptions,
+    /// The workspace folders, which are searched for Solidity files.
+    roots: Vec<PathBuf>,
+    /// The contents of the open documents.
+    documents: HashMap<PathBuf, String>,
+    /// The files that diagnostics were last published for.
+    published: HashSet<PathBuf>,
+    analysis: Analysis,
+}
+
+impl Server {
+    fn new(connection: Connection, options: AnalysisOptions) -> Self {
+        Self {
+            connection,
+            options,
+            roots: Vec::new(),
+            documents: HashMap::new(),
+            published: HashSet::new(),
+            analysis: Analysis::default(),
+        }
+    }
+
+    /// Initializes the connection and handles messages until the client shuts the server down.
+    fn run(mut self) -> ServerResult<()> {
+        let capabilities = lsp::ServerCapabilities {
+            text_document_sync: Some(lsp::TextDocumentSyncCapability::Options(
+                lsp::TextDocumentSyncOptions {
+                    open_close: Some(true),
+                    change: Some(lsp::TextDocumentSyncKind::FULL),
+                    save: Some(lsp::TextDocumentSyncSaveOptions::Supported(true)),
+                    ..Default::default()
+                },
+            )),
+            definition_provider: Some(lsp::OneOf::Left(true)),
+            hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+            document_symbol_provider: Some(lsp::OneOf::Left(true)),
+            rename_provider: Some(lsp::OneOf::Left(true)),
+            ..Default::default()
+        };
+        let params = self.connection.initialize(serde_json::to_value(capabilities)?)?;
+        let params: lsp::InitializeParams = serde_json::from_value(params)?;
+        #[allow(deprecated)]
+        let roots = match (params.workspace_folders, params.root_uri) {
+            (Some(folders), _) => folders.into_iter().map(|folder| folder.uri).collect(),
+            (None, Some(root)) => vec![root],
+            (None, None) => Vec::new(),
+        };
+        self.roots = roots.iter().filter_map(uri_to_path).collect();
+        self.analyze()?;
+
+        while let Ok(msg) = self.connection.receiver.recv() {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    self.handle_request(req)?;
+                }
+                Message::Notification(not) => self.handle_notification(not)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, req: Request) -> ServerResult<()> {
+        let id = req.id.clone();
+        macro_rules! params {
+            ($r:ty) => {
+                match extract::<$r>(req) {
+                    Ok(params) => params,
+                    Err(response) => return self.respond(response),
+                }
+            };
+        }
+        let result = match req.method.as_str() {
+            request::GotoDefinition::METHOD => {
+                let params = params!(request::GotoDefinition);
+                let pos = params.text_document_position_params;
+                let location = uri_to_path(&pos.text_document.uri)
+                    .and_then(|path| self.analysis.definition(&path, pos.position));
+                serde_json::to_value(location.map(lsp::GotoDefinitionResponse::Scalar))
+            }
+            request::HoverRequest::METHOD => {
+                let params = params!(request::HoverRequest);
+                let pos = params.text_document_position_params;
+                let hover = uri_to_path(&pos.text_document.uri)
+                    .and_then(|path| self.analysis.hover(&path, pos.position));
+                serde_json::to_value(hover)
+            }
+            request::DocumentSymbolRequest::METHOD => {
+                let params = params!(request::DocumentSymbolRequest);
+                let symbols = uri_to_path(&params.text_document.uri)
+                    .map(|path| self.analysis.document_symbols(&path))
+                    .unwrap_or_default();
+                serde_json::to_value(lsp::DocumentSymbolResponse::Nested(symbols))
+            }
+            request::Rename::METHOD => {
+                let params = params!(request::Rename);
+                let pos = params.text_document_position;
+                let Some(path) = uri_to_path(&pos.text_document.uri) else {
+                    return self.respond(Response::new_ok(id, serde_json::Value::Null));
+                };
+                // Re-analyze so that the edits apply to the current contents of the documents.
+                let files = self.files();
+                let (options, documents) = (&self.options, &self.documents);
+                match Analysis::rename(
+                    options,
+                    &files,
+                    documents,
+                    &path,
+                    pos.position,
+                    &params.new_name,
+                ) {
+                    Ok(edit) => serde_json::to_value(edit),
+                    Err(msg) => {
+                        let code = ErrorCode::RequestFailed as i32;
+                        return self.respond(Response::new_err(id, code, msg));
+                    }
+                }
+            }
+            _ => {
+                let msg = format!("unsupported request: {}", req.method);
+                return self.respond(Response::new_err(id, ErrorCode::MethodNotFound as i32, msg));
+            }
+        };
+        self.respond(Response::new_ok(id, result?))
+    }
+
+    fn handle_notification(&mut self, not: Notification) -> ServerResult<()> {
+        macro_rules! params {
+            ($n:ty) => {
+                match extract_notification::<$n>(not) {
+                    Some(params) => params,
+                    None => return Ok(()),
+                }
+            };
+        }
+        match not.method.as_str() {
+            notification::DidOpenTextDocument::METHOD => {
+                let params = params!(notification::DidOpenTextDocument);
+                if let Some(path) = uri_to_path(&params.text_document.uri) {
+                    self.documents.insert(path, params.text_document.text);
+                    self.analyze()?;
+                }
+            }
+            notification::DidChangeTextDocument::METHOD => {
+                let mut params = params!(notification::DidChangeTextDocument);
+                // Changes are full documents, so only the last one matters.
+                if let (Some(path), Some(change)) =
+                    (uri_to_path(&params.text_document.uri), params.content_changes.pop())
+                {
+                    self.documents.insert(path, change.text);
+                }
+            }
+            notification::DidSaveTextDocument::METHOD => self.analyze()?,
+            notification::DidCloseTextDocument::METHOD => {
+                let params = params!(notification::DidCloseTextDocument);
+                if let Some(path) = uri_to_path(&params.text_document.uri) {
+                    self.documents.remove(&path);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Re-analyzes the workspace and the open documents, and publishes the diagnostics.
+    fn analyze(&mut self) -> ServerResult<()> {
+        let files = self.files();
+        self.analysis = Analysis::run(&self.options, &files, &self.documents);
+
+        // Clear the diagnostics of files that are no longer analyzed.
+        let stale =
+            self.published.iter().filter(|path| !self.analysis.diagnostics.contains_key(*path));
+        let mut diagnostics = stale.map(|path| (path.clone(), Vec::new())).collect::<Vec<_>>();
+        diagnostics.extend(self.analysis.diagnostics.iter().map(|(k, v)| (k.clone(), v.clone())));
+        diagnostics.sort_by(|a, b| a.0.cmp(&b.0));
+        self.published = self.analysis.diagnostics.keys().cloned().collect();
+        for (path, diagnostics) in diagnostics {
+            let params = lsp::PublishDiagnosticsParams {
+                uri: path_to_uri(&path),
+                diagnostics,
+                version: None,
+            };
+            let not = Notification::new(notification::PublishDiagnostics::METHOD.into(), params);
+            self.connection.sender.send(not.into())?;
+        }
+        Ok(())
+    }
+
+    /// Returns the Solidity files in the workspace folders and the open documents.
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for root in &self.roots {
+            collect_solidity_files(root, &mut files);
+        }
+        files.extend(self.documents.keys().cloned());
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    fn respond(&self, response: Response) -> ServerResult<()> {
+        self.connection.sender.send(response.into())?;
+        Ok(())
+    }
+}
+
+/// Deserializes the parameters of a request, or returns an `InvalidParams` error response.
+fn extract<R: request::Request>(req: Request) -> std::result::Result<R::Params, Response> {
+    serde_json::from_value(req.params).map_err(|e| {
+        let msg = format!("invalid parameters for {}: {e}", R::METHOD);
+        Response::new_err(req.id, ErrorCode::InvalidParams as i32, msg)
+    })
+}
+
+/// Deserializes the parameters of a notification. Invalid notifications are logged and ignored,
+/// since they cannot be answered.
+fn extract_notification<N: notification::Notification>(not: Notification) -> Option<N::Params> {
+    serde_json::from_value(not.params)
+        .inspect_err(|e| tracing::warn!("invalid parameters for {}: {e}", N::METHOD))
+        .ok()
+}
+
+/// Recursively collects the Solidity files in a directory, skipping hidden directories and
+/// `node_modules`.
+fn collect_solidity_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "node_modules" {
+                collect_solidity_files(&path, files);
+            }
+        } else if path.extension() == Some("sol".as_ref()) {
+            files.push(path);
+        }
+    }
+}
+
+/// Converts a `file://` URI to a path.
+fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    let rest = uri.as_str().strip_prefix("file://")?;
+    // Skip the authority, which is usually empty or `localhost`.
+    let path = &rest[rest.find('/')?..];
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+/// Converts an absolute path to a `file://` URI.
+fn path_to_uri(path: &Path) -> Uri {
+    let mut uri = String::from("file://");
+    for b in path.to_string_lossy().bytes() {
+        if b.is_ascii_alphanumeric() || b"/-._~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    uri.parse().expect("invalid file URI")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::RequestId;
+
+    /// A minimal LSP client connected to a server running in another thread.
+    struct Client {
+        connection: Connection,
+        next_id: i32,
+    }
+
+    impl Client {
+        fn start() -> (Self, std::thread::JoinHandle<()>) {
+            let (server, client) = Connection::memory();
+            let handle = std::thread::spawn(move || {
+                Server::new(server, AnalysisOptions::default()).run().unwrap();
+            });
+            let mut client = Self { connection: client, next_id: 0 };
+            #[allow(deprecated)]
+            let params = lsp::InitializeParams::default();
+            let _: lsp::InitializeResult = client.request::<request::Initialize>(params);
+            client.notify::<notification::Initialized>(lsp::InitializedParams {});
+            (client, handle)
+        }
+
+        fn request<R: request::Request>(&mut self, params: R::Params) -> R::Result {
+            let res = self.request_raw(R::METHOD, serde_json::to_value(params).unwrap());
+            assert!(res.error.is_none(), "{:?}", res.error);
+            serde_json::from_value(res.result.unwrap()).unwrap()
+        }
+
+        fn request_raw(&mut self, method: &str, params: serde_json::Value) -> Response {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            let req = Request::new(id.clone(), method.into(), params);
+            self.connection.sender.send(req.into()).unwrap();
+            loop {
+                match self.connection.receiver.recv().unwrap() {
+                    Message::Response(res) if res.id == id => return res,
+                    _ => {}
+                }
+            }
+        }
+
+        fn notify<N: notification::Notification>(&self, params: N::Params) {
+            let not = Notification::new(N::METHOD.into(), params);
+            self.connection.sender.send(not.into()).unwrap();
+        }
+
+        /// Receives the diagnostics published after the next analysis, sorted by URI.
+        fn diagnostics(&self, n_files: usize) -> Vec<lsp::PublishDiagnosticsParams> {
+            let mut all = Vec::new();
+            while all.len() < n_files {
+                if let Message::Notification(not) = self.connection.receiver.recv().unwrap() {
+                    assert_eq!(not.method, notification::PublishDiagnostics::METHOD);
+                    all.push(serde_json::from_value(not.params).unwrap());
+                }
+            }
+            all
+        }
+
+        fn open(&self, path: &str, text: &str) {
+            self.notify::<notification::DidOpenTextDocument>(lsp::DidOpenTextDocumentParams {
+                text_document: lsp::TextDocumentItem {
+                    uri: uri(path),
+                    language_id: "solidity".into(),
+                    version: 0,
+                    text: text.into(),
+                },
+            });
+        }
+
+        fn shutdown(mut self, handle: std::thread::JoinHandle<()>) {
+            self.request::<request::Shutdown>(());
+            self.notify::<notification::Exit>(());
+            handle.join().unwrap();
+        }
+    }
+
+    fn uri(path: &str) -> Uri {
+        path_to_uri(Path::new(path))
+    }
+
+    fn position(path: &str, line: u32, character: u32) -> lsp::TextDocumentPositionParams {
+        lsp::TextDocumentPositionParams {
+            text_document: lsp::TextDocumentIdentifier { uri: uri(path) },
+            position: lsp::Position { line, character },
+        }
+    }
+
+    #[test]
+    fn uris() {
+        let path = Path::new("/a b/ü.sol");
+        let uri = path_to_uri(path);
+        assert_eq!(uri.as_str(), "file:///a%20b/%C3%BC.sol");
+        assert_eq!(uri_to_path(&uri).as_deref(), Some(path));
+        assert_eq!(
+            uri_to_path(&"file://localhost/a.sol".parse().unwrap()).unwrap(),
+            Path::new("/a.sol")
+        );
+        assert_eq!(uri_to_path(&"untitled:Untitled-1".parse().unwrap()), None);
+    }
+
+    #[test]
+    fn invalid_params() {
+        let (mut client, handle) = Client::start();
+
+        // Malformed notifications are ignored.
+        let not = Notification::new(
+            notification::DidOpenTextDocument::METHOD.into(),
+            serde_json::json!({ "textDocument": 0 }),
+        );
+        client.connection.sender.send(not.into()).unwrap();
+
+        let res = client
+            .request_raw(request::HoverRequest::METHOD, serde_json::json!({ "position": "0:0" }));
+        let error = res.error.unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidParams as i32);
+        assert!(
+            error.message.starts_with("invalid parameters for textDocument/hover"),
+            "{error:?}"
+        );
+
+        // The server keeps handling requests.
+        let hover = client.request::<request::HoverRequest>(lsp::HoverParams {
+            text_document_position_params: position("/solar-lsp-test/A.sol", 0, 0),
+            work_done_progress_params: Default::default(),
+        });
+        assert_eq!(hover, None);
+
+        client.shutdown(handle);
+    }
+
+    #[test]
+    fn server() {
+        const B: &str = "/solar-lsp-test/B.sol";
+        const A: &str = "/solar-lsp-test/A.sol";
+        let (mut client, handle) = Client::start();
+
+        client.open(B, "contract B {\n    function f() public {}\n}\n");
+        let diagnostics = client.diagnostics(1);
+        assert_eq!(diagnostics[0].uri, uri(B));
+        assert!(diagnostics[0].diagnostics.is_empty());
+
+        let a = "\
+import \"./B.sol\";
+
+contract A is B {
+    uint256 x;
+
+    function g() public {
+        f();
+        x = y;
+    }
+}
+";
+        client.open(A, a);
+        let mut diagnostics = client.diagnostics(2);
+        diagnostics.sort_by(|a, b| a.uri.cmp(&b.uri));
+        assert_eq!(diagnostics[0].uri, uri(A));
+        assert_eq!(diagnostics[0].diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0].diagnostics[0];
+        assert_eq!(diagnostic.severity, Some(lsp::DiagnosticSeverity::ERROR));
+        assert!(diagnostic.message.contains("unresolved symbol"), "{}", diagnostic.message);
+        let start = lsp::Position { line: 7, character: 12 };
+        let end = lsp::Position { line: 7, character: 13 };
+        assert_eq!(diagnostic.range, lsp::Range { start, end });
+        assert_eq!(diagnostics[1].uri, uri(B));
+        assert!(diagnostics[1].diagnostics.is_empty());
+
+        // Go to `f` in `B.sol`.
+        let definition = client.request::<request::GotoDefinition>(lsp::GotoDefinitionParams {
+            text_document_position_params: position(A, 6, 9),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let start = lsp::Position { line: 1, character: 13 };
+        let end = lsp::Position { line: 1, character: 14 };
+        let location = lsp::Location { uri: uri(B), range: lsp::Range { start, end } };
+        assert_eq!(definition, Some(lsp::GotoDefinitionResponse::Scalar(location)));
+
+        let hover = client.request::<request::HoverRequest>(lsp::HoverParams {
+            text_document_position_params: position(A, 7, 8),
+            work_done_progress_params: Default::default(),
+        });
+        let Some(lsp::Hover { contents: lsp::HoverContents::Markup(contents), .. }) = hover else {
+            panic!("unexpected hover: {hover:?}");
+        };
+        assert_eq!(contents.value, "```solidity\nuint256 x\n```");
+
+        let symbols = client.request::<request::DocumentSymbolRequest>(lsp::DocumentSymbolParams {
+            text_document: lsp::TextDocumentIdentifier { uri: uri(A) },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let Some(lsp::DocumentSymbolResponse::Nested(symbols)) = symbols else {
+            panic!("unexpected symbols: {symbols:?}");
+        };
+        let names = |symbols: &[lsp::DocumentSymbol]| {
+            symbols.iter().map(|s| (s.name.clone(), s.kind)).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&symbols), [("A".to_string(), lsp::SymbolKind::CLASS)]);
+        assert_eq!(symbols[0].detail.as_deref(), Some("contract A is B"));
+        assert_eq!(
+            names(symbols[0].children.as_deref().unwrap()),
+            [
+                ("x".to_string(), lsp::SymbolKind::FIELD),
+                ("g".to_string(), lsp::SymbolKind::FUNCTION)
+            ]
+        );
+
+        // Rename `f` in both files.
+        let edit = client.request::<request::Rename>(lsp::RenameParams {
+            text_document_position: position(A, 6, 9),
+            new_name: "h".into(),
+            work_done_progress_params: Default::default(),
+        });
+        let change = |path, line, character| lsp::TextDocumentEdit {
+            text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                uri: uri(path),
+                version: None,
+            },
+            edits: vec![lsp::OneOf::Left(lsp::TextEdit {
+                range: lsp::Range {
+                    start: lsp::Position { line, character },
+                    end: lsp::Position { line, character: character + 1 },
+                },
+                new_text: "h".into(),
+            })],
+        };
+        let changes = lsp::DocumentChanges::Edits(vec![change(A, 6, 8), change(B, 1, 13)]);
+        assert_eq!(edit.and_then(|edit| edit.document_changes), Some(changes));
+
+        client.shutdown(handle);
+    }
+}
@@ -0,0 +1,25 @@
+//! Solar subcommands.
+
+use crate::{cli::Command, Compiler};
+use solar_interface::Result;
+
+mod abi2sol;
+mod flatten;
+mod fmt;
+mod imports;
+mod lsp;
+mod rename;
+
+impl Compiler {
+    /// Runs the given subcommand.
+    pub(crate) fn run_command(&self, command: &Command) -> Result<()> {
+        match command {
+            Command::Abi2sol(args) => self.run_abi2sol(args),
+            Command::Flatten(args) => self.run_flatten(args),
+            Command::Fmt(args) => self.run_fmt(args),
+            Command::Imports(args) => self.run_imports(args),
+            Command::Lsp(args) => self.run_lsp(args),
+            Command::Rename(args) => self.run_rename(args),
+        }
+    }
+}
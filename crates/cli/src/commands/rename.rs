@@ -0,0 +1,55 @@
+//! `solar rename`.
+
+use super::fmt::collect_sol_files;
+use crate::{cli::RenameArgs, Compiler};
+use solar_interface::{
+    source_map::{FileName, Rewriter},
+    BytePos, Pos, Result,
+};
+
+impl Compiler {
+    /// Renames the declaration at the given position in all of the loaded sources.
+    pub(super) fn run_rename(&self, args: &RenameArgs) -> Result<()> {
+        let sess = &self.sess;
+        let mut pcx = self.parsing_context()?;
+        pcx.load_file(&args.path)?;
+        // The file was just added to the source map.
+        let file = sess.source_map().files().last().unwrap().clone();
+        if args.offset > file.src.len() {
+            let msg = format!("offset {} is out of bounds of {}", args.offset, args.path.display());
+            return Err(sess.dcx.err(msg).emit());
+        }
+        let pos = file.start_pos + BytePos::from_usize(args.offset);
+
+        let mut paths = Vec::new();
+        for path in &args.paths {
+            collect_sol_files(path, &mut paths).map_err(|e| {
+                sess.dcx.err(format!("failed to read {}: {e}", path.display())).emit()
+            })?;
+        }
+        pcx.load_files(&paths)?;
+
+        let mut edits = None;
+        solar_sema::parse_and_resolve_with(pcx, |gcx| {
+            edits = Some(gcx.rename(pos, &args.new_name))
+        })?;
+        let edits = edits.unwrap().map_err(|e| sess.dcx.err(e.to_string()).emit())?;
+
+        let mut rewriter = Rewriter::new(sess.source_map());
+        for edit in edits {
+            rewriter.edit(edit);
+        }
+        let patched = rewriter.apply().map_err(|e| sess.dcx.err(e.to_string()).emit())?;
+        for patched in patched.iter().filter(|patched| patched.is_changed()) {
+            if !args.write {
+                print!("{}", patched.unified_diff());
+                continue;
+            }
+            let FileName::Real(path) = &patched.file.name else { continue };
+            std::fs::write(path, &patched.src).map_err(|e| {
+                sess.dcx.err(format!("failed to write to {}: {e}", path.display())).emit()
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -14,6 +14,7 @@ use solar_interface::{
 use std::{collections::BTreeSet, num::NonZeroUsize, path::Path, sync::Arc};
 
 pub mod cli;
+mod commands;
 pub mod utils;
 pub mod version;
 
@@ -57,26 +58,40 @@ impl Compiler {
     pub fn run_default(&self) -> Result<()> {
         let Self { sess, args } = self;
 
+        if let Some(command) = &args.command {
+            return self.run_command(command);
+        }
+
         if sess.language.is_yul() && !args.unstable.parse_yul {
             return Err(sess.dcx.err("Yul is not supported yet").emit());
         }
 
         // Partition arguments into three categories:
         // - `stdin`: `-`, occurrences after the first are ignored
-        // - remappings: `path=mapped`
+        // - remappings: `path=mapped`, already collected in `sess.import_maps`
         // - paths: everything else
         let stdin = args.input.iter().any(|arg| *arg == Path::new("-"));
         let non_stdin_args = args.input.iter().filter(|arg| *arg != Path::new("-"));
-        let arg_remappings = non_stdin_args
-            .clone()
-            .filter_map(|arg| arg.to_str().unwrap_or("").parse::<cli::ImportMap>().ok());
         let paths =
             non_stdin_args.filter(|arg| !arg.as_os_str().as_encoded_bytes().contains(&b'='));
 
+        let mut pcx = self.parsing_context()?;
+        if stdin {
+            pcx.load_stdin()?;
+        }
+        pcx.load_files(paths)?;
+
+        pcx.parse_and_resolve()?;
+
+        Ok(())
+    }
+
+    /// Creates a parsing context with the configured import remappings and import paths.
+    pub(crate) fn parsing_context(&self) -> Result<solar_sema::ParsingContext<'_>> {
+        let Self { sess, args } = self;
         let mut pcx = solar_sema::ParsingContext::new(sess);
-        let remappings = arg_remappings.chain(args.import_map.iter().cloned());
-        for map in remappings {
-            pcx.file_resolver.add_import_map(map.map, map.path);
+        for (map, path) in &sess.import_maps {
+            pcx.file_resolver.add_import_map(map.clone(), path.clone());
         }
         for path in &args.import_path {
             let new = pcx.file_resolver.add_import_path(path.clone());
@@ -85,15 +100,7 @@ impl Compiler {
                 return Err(sess.dcx.err(msg).emit());
             }
         }
-
-        if stdin {
-            pcx.load_stdin()?;
-        }
-        pcx.load_files(paths)?;
-
-        pcx.parse_and_resolve()?;
-
-        Ok(())
+        Ok(pcx)
     }
 
     fn finish_diagnostics(&self) -> Result {
@@ -154,7 +161,17 @@ fn run_compiler_with(args: Args, f: impl FnOnce(&Compiler) -> Result + Send) ->
             set
         };
         sess.out_dir = args.out_dir.clone();
+        sess.output_layout = args.output_layout;
+        sess.graph_format = args.graph_format;
         sess.pretty_json = args.pretty_json;
+        sess.proxies = args.proxies.clone();
+        sess.import_maps = args
+            .input
+            .iter()
+            .filter_map(|arg| arg.to_str().unwrap_or("").parse::<cli::ImportMap>().ok())
+            .chain(args.import_map.iter().cloned())
+            .map(|map| (map.map, map.path))
+            .collect();
 
         let compiler = Compiler { sess, args };
         compiler.sess.enter(|| {
@@ -109,6 +109,104 @@ str_enum! {
         // BinRuntime,
         /// Function signature hashes.
         Hashes,
+        /// Function, event and error selectors, and the ERC-165 interface ID of interfaces.
+        Selectors,
+        /// Storage layout of state variables.
+        StorageLayout,
+        /// EIP-712 `encodeType` and type hash of structs.
+        Eip712,
+        /// Contract metadata, as in the solc `metadata` output.
+        Metadata,
+        /// Solidity interface source code.
+        Interface,
+        /// Inheritance graph of all contracts, with their direct and linearized bases.
+        InheritanceGraph,
+        /// Function-level call graph of all functions and modifiers.
+        CallGraph,
+    }
+}
+
+str_enum! {
+    /// How output files are laid out in the output directory.
+    #[derive(Default)]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum OutputLayout {
+        /// A single `combined.json` file.
+        #[default]
+        Combined,
+        /// One file per contract and type of output, like `solc -o`: `<Contract>.abi`,
+        /// `<Contract>.signatures`, ...
+        Split,
+        /// One JSON artifact per contract, like Foundry and Hardhat: `<File>.sol/<Contract>.json`.
+        Artifacts,
+    }
+}
+
+str_enum! {
+    /// Output format of graphs, like the inheritance and call graphs.
+    #[derive(Default)]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum GraphFormat {
+        /// JSON. The inheritance and call graphs are included in `combined.json`.
+        #[default]
+        Json,
+        /// Graphviz DOT.
+        Dot,
+    }
+}
+
+str_enum! {
+    /// Quotes to use for string literals when formatting source code.
+    #[derive(Default)]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum QuoteStyle {
+        /// Double quotes, unless the string contains double quotes.
+        #[default]
+        Double,
+        /// Single quotes, unless the string contains single quotes.
+        Single,
+        /// Keep the quotes as written.
+        Preserve,
+    }
+}
+
+str_enum! {
+    /// How to write underscores in number literals when formatting source code.
+    #[derive(Default)]
+    #[strum(serialize_all = "kebab-case")]
+    pub enum NumberUnderscore {
+        /// Keep the underscores as written.
+        #[default]
+        Preserve,
+        /// Remove all underscores.
+        Remove,
+        /// Separate the thousands of decimal numbers with at least 5 digits with underscores.
+        Thousands,
+    }
+}
+
+/// `--proxy proxy=implementation`.
+///
+/// A pair of contracts to check for function selector collisions, specified by name or fully
+/// qualified name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProxyPair {
+    /// The proxy contract.
+    pub proxy: String,
+    /// The implementation contract.
+    pub implementation: String,
+}
+
+impl std::str::FromStr for ProxyPair {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((proxy, implementation)) if !proxy.is_empty() && !implementation.is_empty() => {
+                Ok(Self { proxy: proxy.into(), implementation: implementation.into() })
+            }
+            _ => Err("expected `PROXY=IMPLEMENTATION`"),
+        }
     }
 }
 
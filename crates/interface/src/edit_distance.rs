@@ -0,0 +1,106 @@
+//! Edit distances, used to suggest similar names in diagnostics.
+
+use crate::Symbol;
+
+/// Returns the edit distance between two strings, or `None` if it is greater than `limit`.
+///
+/// This is the [optimal string alignment distance]: the number of insertions, deletions,
+/// substitutions and transpositions of adjacent characters needed to turn `a` into `b`.
+///
+/// [optimal string alignment distance]: https://en.wikipedia.org/wiki/Damerau%E2%80%93Levenshtein_distance#Optimal_string_alignment_distance
+pub fn edit_distance(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+
+    // Three rows of the distance matrix: `i - 2`, `i - 1` and `i`.
+    let mut prev_prev = vec![0; b.len() + 1];
+    let mut prev = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, &ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            let mut distance = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && ca == b[j - 1] && a[i - 1] == cb {
+                distance = distance.min(prev_prev[j - 1] + 1);
+            }
+            current[j + 1] = distance;
+        }
+        std::mem::swap(&mut prev_prev, &mut prev);
+        std::mem::swap(&mut prev, &mut current);
+    }
+    let distance = prev[b.len()];
+    (distance <= limit).then_some(distance)
+}
+
+/// Finds the candidate that is the most similar to `lookup`.
+///
+/// A candidate that differs from `lookup` only by case is preferred. Otherwise, this returns the
+/// first candidate with the smallest edit distance to `lookup`, if that distance is at most `dist`,
+/// which defaults to a third of the length of `lookup`, but less than the length of `lookup`.
+/// Candidates equal to `lookup` are ignored.
+pub fn find_best_match<'a>(
+    candidates: impl IntoIterator<Item = &'a str>,
+    lookup: &str,
+    dist: Option<usize>,
+) -> Option<&'a str> {
+    let len = lookup.chars().count();
+    // Replacing all the characters of a short name, like `a` with `i`, is not a useful suggestion.
+    let dist = dist.unwrap_or_else(|| (len.max(3) / 3).min(len.saturating_sub(1)));
+    let mut best: Option<(&str, usize)> = None;
+    for candidate in candidates {
+        if candidate == lookup {
+            continue;
+        }
+        if candidate.eq_ignore_ascii_case(lookup) {
+            return Some(candidate);
+        }
+        if let Some(distance) = edit_distance(lookup, candidate, dist) {
+            if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                best = Some((candidate, distance));
+            }
+        }
+    }
+    best.map(|(candidate, _)| candidate)
+}
+
+/// Finds the symbol that is the most similar to `lookup`, like [`find_best_match`].
+pub fn find_best_match_for_name(
+    candidates: &[Symbol],
+    lookup: Symbol,
+    dist: Option<usize>,
+) -> Option<Symbol> {
+    let best = find_best_match(candidates.iter().map(Symbol::as_str), lookup.as_str(), dist)?;
+    candidates.iter().copied().find(|candidate| candidate.as_str() == best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance("kitten", "sitting", 2), None);
+        assert_eq!(edit_distance("", "abc", 3), Some(3));
+        assert_eq!(edit_distance("balance", "balance", 0), Some(0));
+        assert_eq!(edit_distance("ü", "u", 1), Some(1));
+        assert_eq!(edit_distance("Piont", "Point", 1), Some(1));
+        assert_eq!(edit_distance("ab", "ba", 1), Some(1));
+        assert_eq!(edit_distance("ca", "abc", 3), Some(3));
+    }
+
+    #[test]
+    fn best_match() {
+        let candidates = ["balance", "balances", "owner", "Owner"];
+        assert_eq!(find_best_match(candidates, "balanse", None), Some("balance"));
+        assert_eq!(find_best_match(candidates, "OWNER", None), Some("owner"));
+        assert_eq!(find_best_match(candidates, "owner", None), Some("Owner"));
+        assert_eq!(find_best_match(candidates, "total", None), None);
+        assert_eq!(find_best_match(candidates, "bal", Some(4)), Some("balance"));
+        assert_eq!(find_best_match(["i", "ab"], "a", None), None);
+    }
+}
@@ -12,6 +12,8 @@ extern crate tracing;
 pub mod diagnostics;
 use diagnostics::ErrorGuaranteed;
 
+pub mod edit_distance;
+
 mod globals;
 pub use globals::SessionGlobals;
 
@@ -2,7 +2,9 @@ use crate::{
     diagnostics::{DiagCtxt, EmittedDiagnostics},
     ColorChoice, SessionGlobals, SourceMap,
 };
-use solar_config::{CompilerOutput, CompilerStage, Dump, EvmVersion, Language};
+use solar_config::{
+    CompilerOutput, CompilerStage, Dump, EvmVersion, GraphFormat, Language, OutputLayout, ProxyPair,
+};
 use std::{collections::BTreeSet, num::NonZeroUsize, path::PathBuf, sync::Arc};
 
 /// Information about the current compiler session.
@@ -34,12 +36,24 @@ pub struct Session {
     /// Output directory.
     #[builder(default)]
     pub out_dir: Option<PathBuf>,
+    /// How output files are laid out in `out_dir`.
+    #[builder(default)]
+    pub output_layout: OutputLayout,
+    /// Output format of the inheritance and call graphs.
+    #[builder(default)]
+    pub graph_format: GraphFormat,
     /// Internal state to dump to stdout.
     #[builder(default)]
     pub dump: Option<Dump>,
     /// Pretty-print any JSON output.
     #[builder(default)]
     pub pretty_json: bool,
+    /// Proxy and implementation contracts to check for function selector collisions.
+    #[builder(default)]
+    pub proxies: Vec<ProxyPair>,
+    /// Import remappings, as `(map, path)` pairs.
+    #[builder(default)]
+    pub import_maps: Vec<(PathBuf, PathBuf)>,
     /// Number of threads to use. Already resolved to a non-zero value.
     #[builder(default = "NonZeroUsize::MIN")]
     pub jobs: NonZeroUsize,
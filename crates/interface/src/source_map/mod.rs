@@ -19,6 +19,9 @@ pub use file::*;
 mod file_resolver;
 pub use file_resolver::{FileResolver, ResolveError};
 
+mod rewrite;
+pub use rewrite::{PatchedFile, RewriteError, Rewriter, TextEdit};
+
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,405 @@
+//! Span-based source code rewriting.
+
+use super::{SourceFile, SourceMap, SpanSnippetError};
+use crate::{BytePos, Pos, Span};
+use std::{fmt::Write, sync::Arc};
+
+/// The number of unchanged lines shown around changes in unified diffs.
+const DIFF_CONTEXT: usize = 3;
+
+/// A text edit: replaces the source code at `span` with `text`.
+///
+/// Edits with an empty span are insertions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The span of the replaced source code.
+    pub span: Span,
+    /// The replacement text.
+    pub text: String,
+}
+
+impl TextEdit {
+    /// Returns `true` if this edit inserts text without replacing anything.
+    pub fn is_insertion(&self) -> bool {
+        self.span.lo() == self.span.hi()
+    }
+}
+
+/// An error that occurred while applying edits.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum RewriteError {
+    /// Two edits modify the same source code.
+    #[error("conflicting edits at {:?} and {:?}", _0.span, _1.span)]
+    Conflict(TextEdit, TextEdit),
+    /// The span of an edit is not inside of a single source file.
+    #[error("invalid edit span {0:?}")]
+    InvalidSpan(Span),
+}
+
+/// Records span-based text edits and applies them to the source files in a [`SourceMap`].
+///
+/// Edits are usually recorded by an AST visitor that holds a `&mut Rewriter`, and then applied all
+/// at once with [`apply`](Self::apply), which checks that they do not conflict with each other.
+///
+/// Edits are relative to the original source code, so spans of AST nodes can be used directly,
+/// regardless of what other edits have been made.
+pub struct Rewriter<'sm> {
+    source_map: &'sm SourceMap,
+    edits: Vec<TextEdit>,
+}
+
+impl<'sm> Rewriter<'sm> {
+    /// Creates a new rewriter.
+    pub fn new(source_map: &'sm SourceMap) -> Self {
+        Self { source_map, edits: Vec::new() }
+    }
+
+    /// Returns the source map.
+    pub fn source_map(&self) -> &'sm SourceMap {
+        self.source_map
+    }
+
+    /// Returns the source code at `span`.
+    pub fn snippet(&self, span: Span) -> Result<String, SpanSnippetError> {
+        self.source_map.span_to_snippet(span)
+    }
+
+    /// Returns the recorded edits, in the order they were recorded.
+    pub fn edits(&self) -> &[TextEdit] {
+        &self.edits
+    }
+
+    /// Returns `true` if no edits have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Records an edit.
+    pub fn edit(&mut self, edit: TextEdit) {
+        self.edits.push(edit);
+    }
+
+    /// Replaces the source code at `span` with `text`.
+    pub fn replace(&mut self, span: Span, text: impl Into<String>) {
+        self.edit(TextEdit { span, text: text.into() });
+    }
+
+    /// Removes the source code at `span`.
+    pub fn remove(&mut self, span: Span) {
+        self.replace(span, String::new());
+    }
+
+    /// Inserts `text` before `span`.
+    ///
+    /// Multiple insertions at the same position are applied in the order they were recorded.
+    pub fn insert_before(&mut self, span: Span, text: impl Into<String>) {
+        self.replace(span.shrink_to_lo(), text);
+    }
+
+    /// Inserts `text` after `span`.
+    ///
+    /// Multiple insertions at the same position are applied in the order they were recorded.
+    pub fn insert_after(&mut self, span: Span, text: impl Into<String>) {
+        self.replace(span.shrink_to_hi(), text);
+    }
+
+    /// Applies the edits to the source files, returning the patched files ordered by their
+    /// position in the source map. Files without edits are not returned.
+    ///
+    /// Identical edits are only applied once. Other edits that modify the same source code, or
+    /// insertions inside of replaced source code, are conflicts.
+    pub fn apply(self) -> Result<Vec<PatchedFile>, RewriteError> {
+        let mut by_file = Vec::<(Arc<SourceFile>, Vec<TextEdit>)>::new();
+        for edit in self.edits {
+            if self.source_map.files().is_empty() {
+                return Err(RewriteError::InvalidSpan(edit.span));
+            }
+            let file = self.source_map.lookup_source_file(edit.span.lo());
+            if edit.span.hi() > file.end_position() {
+                return Err(RewriteError::InvalidSpan(edit.span));
+            }
+            match by_file.iter_mut().find(|(f, _)| Arc::ptr_eq(f, &file)) {
+                Some((_, edits)) => edits.push(edit),
+                None => by_file.push((file, vec![edit])),
+            }
+        }
+        by_file.sort_by_key(|(file, _)| file.start_pos);
+        by_file.into_iter().map(|(file, edits)| PatchedFile::new(file, edits)).collect()
+    }
+}
+
+/// A source file with edits applied, returned by [`Rewriter::apply`].
+#[derive(Debug)]
+pub struct PatchedFile {
+    /// The original source file.
+    pub file: Arc<SourceFile>,
+    /// The applied edits, sorted by position.
+    pub edits: Vec<TextEdit>,
+    /// The patched source code.
+    pub src: String,
+}
+
+impl PatchedFile {
+    fn new(file: Arc<SourceFile>, mut edits: Vec<TextEdit>) -> Result<Self, RewriteError> {
+        // Insertions come before replacements at the same position; the sort is stable so that
+        // insertions stay in the order they were recorded.
+        edits.sort_by_key(|edit| (edit.span.lo(), !edit.is_insertion()));
+        edits.dedup();
+
+        let mut end = file.start_pos;
+        let mut prev: Option<&TextEdit> = None;
+        for edit in &edits {
+            if let Some(prev) = prev {
+                if edit.span.lo() < end {
+                    return Err(RewriteError::Conflict(prev.clone(), edit.clone()));
+                }
+            }
+            if !edit.is_insertion() {
+                end = edit.span.hi();
+                prev = Some(edit);
+            }
+        }
+
+        let src = apply_edits(&file.src, file.start_pos, &edits);
+        Ok(Self { file, edits, src })
+    }
+
+    /// Returns `true` if the patched source code is different from the original.
+    pub fn is_changed(&self) -> bool {
+        *self.file.src != self.src
+    }
+
+    /// Returns the changes as a unified diff, with `a/` and `b/` prefixed file names.
+    pub fn unified_diff(&self) -> String {
+        let original = &*self.file.src;
+        let start_pos = self.file.start_pos;
+        let line_starts = line_starts(original);
+        let line_of =
+            |pos: BytePos| line_starts.partition_point(|&start| start <= pos.to_usize()) - 1;
+        let line_end = |line: usize| line_starts.get(line + 1).copied().unwrap_or(original.len());
+
+        // Group the edits into blocks of changed lines.
+        let mut groups = Vec::<((usize, usize), &[TextEdit])>::new();
+        for (i, edit) in self.edits.iter().enumerate() {
+            let (lo, hi) = (edit.span.lo() - start_pos, edit.span.hi() - start_pos);
+            let first = line_of(lo);
+            let last = if hi > lo { line_of(hi - BytePos(1)) } else { first };
+            match groups.last_mut() {
+                Some(((_, group_last), edits)) if first <= *group_last + 1 => {
+                    *group_last = (*group_last).max(last);
+                    *edits = &self.edits[i - edits.len()..=i];
+                }
+                _ => groups.push(((first, last), std::slice::from_ref(edit))),
+            }
+        }
+        let blocks = groups
+            .into_iter()
+            .map(|(lines, edits)| {
+                let (lo, hi) = (line_starts[lines.0], line_end(lines.1));
+                let old = &original[lo..hi];
+                let new = apply_edits(old, start_pos + BytePos::from_usize(lo), edits);
+                Block { lines, old, new }
+            })
+            .filter(|block| block.old != block.new)
+            .collect::<Vec<_>>();
+        if blocks.is_empty() {
+            return String::new();
+        }
+
+        let name = self.file.name.display();
+        let mut out = format!("--- a/{name}\n+++ b/{name}\n");
+        let n_lines = line_starts.len();
+        let mut line_delta = 0isize;
+        let mut rest = &blocks[..];
+        while !rest.is_empty() {
+            // Merge the blocks whose context overlaps into one hunk.
+            let mut n = 1;
+            while n < rest.len() && rest[n].lines.0 - rest[n - 1].lines.1 - 1 <= 2 * DIFF_CONTEXT {
+                n += 1;
+            }
+            let (hunk, next) = rest.split_at(n);
+            rest = next;
+
+            let first = hunk[0].lines.0.saturating_sub(DIFF_CONTEXT);
+            let last = (hunk[n - 1].lines.1 + DIFF_CONTEXT).min(n_lines.saturating_sub(1));
+            let context = |from: usize, to: usize| {
+                let text =
+                    if from < to { &original[line_starts[from]..line_end(to - 1)] } else { "" };
+                text.split_inclusive('\n').map(|line| (' ', line)).collect::<Vec<_>>()
+            };
+            let mut lines = Vec::new();
+            let mut line = first;
+            for block in hunk {
+                lines.extend(context(line, block.lines.0));
+                let old = block.old.split_inclusive('\n').collect::<Vec<_>>();
+                let new = block.new.split_inclusive('\n').collect::<Vec<_>>();
+                let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+                let suffix = old[prefix..]
+                    .iter()
+                    .rev()
+                    .zip(new[prefix..].iter().rev())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                lines.extend(old[..prefix].iter().map(|&line| (' ', line)));
+                lines.extend(old[prefix..old.len() - suffix].iter().map(|&line| ('-', line)));
+                lines.extend(new[prefix..new.len() - suffix].iter().map(|&line| ('+', line)));
+                lines.extend(old[old.len() - suffix..].iter().map(|&line| (' ', line)));
+                line = block.lines.1 + 1;
+            }
+            if line <= last {
+                lines.extend(context(line, last + 1));
+            }
+
+            let old_len = lines.iter().filter(|(kind, _)| *kind != '+').count();
+            let new_len = lines.iter().filter(|(kind, _)| *kind != '-').count();
+            let old_start = if old_len == 0 { first } else { first + 1 };
+            let new_start = (first as isize + line_delta) as usize;
+            let new_start = if new_len == 0 { new_start } else { new_start + 1 };
+            line_delta += new_len as isize - old_len as isize;
+            let _ = writeln!(out, "@@ -{old_start},{old_len} +{new_start},{new_len} @@");
+            for (kind, line) in lines {
+                out.push(kind);
+                out.push_str(line);
+                if !line.ends_with('\n') {
+                    out.push_str("\n\\ No newline at end of file\n");
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A run of lines that contains edits.
+struct Block<'a> {
+    /// The first and last line, inclusive.
+    lines: (usize, usize),
+    /// The original source code of the lines.
+    old: &'a str,
+    /// The patched source code of the lines.
+    new: String,
+}
+
+/// Applies sorted, non-overlapping edits to `src`, which starts at `start_pos`.
+fn apply_edits(src: &str, start_pos: BytePos, edits: &[TextEdit]) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        let lo = (edit.span.lo() - start_pos).to_usize();
+        out.push_str(&src[pos..lo]);
+        out.push_str(&edit.text);
+        pos = (edit.span.hi() - start_pos).to_usize();
+    }
+    out.push_str(&src[pos..]);
+    out
+}
+
+/// Returns the byte offsets of the starts of the lines in `src`.
+fn line_starts(src: &str) -> Vec<usize> {
+    let newlines = src.match_indices('\n').map(|(i, _)| i + 1).filter(|&i| i < src.len());
+    std::iter::once(0).chain(newlines).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_map::FileName;
+
+    fn file(sm: &SourceMap, name: &str, src: &str) -> Arc<SourceFile> {
+        sm.new_source_file(FileName::Real(name.into()), || Ok(src.to_string())).unwrap()
+    }
+
+    fn span(file: &SourceFile, lo: usize, hi: usize) -> Span {
+        Span::new(
+            file.start_pos + BytePos::from_usize(lo),
+            file.start_pos + BytePos::from_usize(hi),
+        )
+    }
+
+    #[test]
+    fn apply() {
+        let sm = SourceMap::empty();
+        let a = file(&sm, "a", "let x = 1;");
+        let b = file(&sm, "b", "foo(bar)");
+        let mut rewriter = Rewriter::new(&sm);
+        rewriter.replace(span(&b, 4, 7), "baz");
+        rewriter.insert_after(span(&a, 4, 5), ": u8");
+        rewriter.replace(span(&a, 8, 9), "2");
+        rewriter.insert_before(span(&a, 0, 3), "// a\n");
+        rewriter.insert_before(span(&a, 0, 3), "// b\n");
+        rewriter.remove(span(&a, 0, 4));
+        rewriter.replace(span(&b, 4, 7), "baz");
+
+        let patched = rewriter.apply().unwrap();
+        let patched = patched.iter().map(|p| (&p.file.name, &*p.src)).collect::<Vec<_>>();
+        assert_eq!(
+            patched,
+            [
+                (&FileName::Real("a".into()), "// a\n// b\nx: u8 = 2;"),
+                (&FileName::Real("b".into()), "foo(baz)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicts() {
+        let sm = SourceMap::empty();
+        let a = file(&sm, "a", "abcdef");
+        let conflict = |edits: &[(usize, usize, &str)]| {
+            let mut rewriter = Rewriter::new(&sm);
+            for &(lo, hi, text) in edits {
+                rewriter.replace(span(&a, lo, hi), text);
+            }
+            matches!(rewriter.apply(), Err(RewriteError::Conflict(..)))
+        };
+        assert!(conflict(&[(0, 3, "x"), (2, 4, "y")]));
+        assert!(conflict(&[(0, 3, "x"), (0, 3, "y")]));
+        assert!(conflict(&[(0, 6, "x"), (2, 3, "y")]));
+        assert!(conflict(&[(0, 3, "x"), (1, 1, "y")]));
+        assert!(!conflict(&[(0, 3, "x"), (3, 3, "y"), (3, 4, "z")]));
+        assert!(!conflict(&[(0, 3, "x"), (0, 0, "y"), (0, 3, "x")]));
+
+        let mut rewriter = Rewriter::new(&sm);
+        let invalid = span(&a, 4, 10);
+        rewriter.replace(invalid, "x");
+        assert_eq!(rewriter.apply().unwrap_err(), RewriteError::InvalidSpan(invalid));
+    }
+
+    #[test]
+    fn unified_diff() {
+        let sm = SourceMap::empty();
+        let src = (1..=12).map(|i| format!("line {i}\n")).collect::<String>() + "end";
+        let a = file(&sm, "a.sol", &src);
+        let pos = |line: usize| src.find(&format!("line {line}\n")).unwrap();
+        let mut rewriter = Rewriter::new(&sm);
+        rewriter.replace(span(&a, pos(2), pos(2) + 6), "LINE 2");
+        rewriter.insert_before(span(&a, pos(4), pos(4)), "new\n");
+        rewriter.remove(span(&a, pos(12), src.len()));
+        rewriter.insert_after(span(&a, src.len(), src.len()), "\n");
+        let patched = rewriter.apply().unwrap();
+        assert_eq!(
+            patched[0].unified_diff(),
+            "\
+--- a/a.sol
++++ b/a.sol
+@@ -1,7 +1,8 @@
+ line 1
+-line 2
++LINE 2
+ line 3
++new
+ line 4
+ line 5
+ line 6
+ line 7
+@@ -9,5 +10,4 @@
+ line 9
+ line 10
+ line 11
+-line 12
+-end
+\\ No newline at end of file
++
+"
+        );
+    }
+}
@@ -850,6 +850,7 @@ symbols! {
         abi,
         abicoder,
         assert,
+        at,
         block,
         code,
         codehash,
@@ -869,6 +870,7 @@ symbols! {
         gasleft,
         global,
         interfaceId,
+        layout,
         length,
         max,
         min,
@@ -0,0 +1,108 @@
+use super::{
+    cursor::token::{RawToken, RawTokenKind},
+    Cursor,
+};
+use solar_ast::print::{Comment, CommentStyle};
+use solar_interface::{source_map::SourceFile, BytePos};
+
+/// Collects the comments and blank lines of a source file, for use with
+/// [`Printer`](solar_ast::print::Printer).
+pub fn gather_comments(file: &SourceFile) -> Vec<Comment> {
+    let src = &file.src[..];
+    let mut comments = Vec::new();
+    let mut pos = 0;
+    // Whether there is code on the current line before the current position.
+    let mut code_to_the_left = false;
+    for RawToken { kind, len } in Cursor::new(src) {
+        let text = &src[pos..pos + len as usize];
+        match kind {
+            RawTokenKind::Whitespace => {
+                let newlines = text.matches('\n').count();
+                if newlines > 0 {
+                    code_to_the_left = false;
+                }
+                if newlines > 1 {
+                    comments.push(Comment {
+                        style: CommentStyle::BlankLine,
+                        lines: Vec::new(),
+                        pos: file.start_pos + BytePos(pos as u32),
+                    });
+                }
+            }
+            RawTokenKind::LineComment { .. } | RawTokenKind::BlockComment { .. } => {
+                let rest = src[pos + text.len()..].trim_start_matches([' ', '\t']);
+                let code_to_the_right = !(rest.is_empty() || rest.starts_with(['\r', '\n']));
+                let style = match kind {
+                    RawTokenKind::LineComment { .. } if code_to_the_left => CommentStyle::Trailing,
+                    RawTokenKind::LineComment { .. } => CommentStyle::Isolated,
+                    _ if code_to_the_right => CommentStyle::Mixed,
+                    _ if code_to_the_left => CommentStyle::Trailing,
+                    _ => CommentStyle::Isolated,
+                };
+                let line_start = src[..pos].rfind('\n').map_or(0, |i| i + 1);
+                let column = src[line_start..pos].chars().count();
+                let lines = text
+                    .lines()
+                    .enumerate()
+                    .map(|(i, line)| {
+                        let line = if i == 0 { line } else { trim_indentation(line, column) };
+                        line.trim_end().to_string()
+                    })
+                    .collect();
+                comments.push(Comment { style, lines, pos: file.start_pos + BytePos(pos as u32) });
+            }
+            _ => code_to_the_left = true,
+        }
+        pos += len as usize;
+    }
+    comments
+}
+
+/// Removes up to `column` characters of leading whitespace from `line`.
+fn trim_indentation(line: &str, column: usize) -> &str {
+    let n = line
+        .char_indices()
+        .take(column)
+        .find(|&(_, c)| !c.is_whitespace())
+        .map_or_else(|| line.chars().take(column).map(char::len_utf8).sum(), |(i, _)| i);
+    &line[n..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::source_map::{FileName, SourceMap};
+
+    #[test]
+    fn gather() {
+        let src = "\
+// a
+contract C { // b
+    /* c */ uint x;
+
+    /**
+     * d
+     */
+    uint y; /* e */
+}
+";
+        let sm = SourceMap::empty();
+        let file = sm.new_source_file(FileName::Custom("test".into()), || Ok(src.into())).unwrap();
+        let comments = gather_comments(&file)
+            .into_iter()
+            .map(|c| (c.style, c.lines.join("\n"), (c.pos - file.start_pos).0))
+            .collect::<Vec<_>>();
+        let pos = |s: &str| src.find(s).unwrap() as u32;
+        assert_eq!(
+            comments,
+            [
+                (CommentStyle::Isolated, "// a".into(), 0),
+                (CommentStyle::Trailing, "// b".into(), pos("// b")),
+                (CommentStyle::Mixed, "/* c */".into(), pos("/* c */")),
+                (CommentStyle::BlankLine, String::new(), pos("\n\n")),
+                (CommentStyle::Isolated, "/**\n * d\n */".into(), pos("/**")),
+                (CommentStyle::Trailing, "/* e */".into(), pos("/* e */")),
+            ]
+        );
+    }
+}
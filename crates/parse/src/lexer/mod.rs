@@ -8,10 +8,16 @@ use solar_interface::{
     diagnostics::DiagCtxt, source_map::SourceFile, sym, BytePos, Pos, Session, Span, Symbol,
 };
 
+mod comments;
+pub use comments::gather_comments;
+
 mod cursor;
 use cursor::token::{RawLiteralKind, RawToken, RawTokenKind};
 pub use cursor::{is_id_continue, is_id_start, is_ident, is_whitespace, token, Cursor};
 
+mod trivia;
+pub use trivia::{TokenWithTrivia, Trivia, TriviaKind};
+
 pub mod unescape;
 
 mod unicode_chars;
@@ -82,7 +88,8 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
 
     /// Consumes the lexer and collects the remaining tokens into a vector.
     ///
-    /// Note that this skips comments, as [required by the parser](crate::Parser::new).
+    /// Note that this skips comments, as [required by the parser](crate::Parser::new). Use
+    /// [`into_tokens_with_trivia`](Self::into_tokens_with_trivia) to keep them.
     ///
     /// Prefer using this method instead of manually collecting tokens using [`Iterator`].
     #[instrument(name = "lex", level = "debug", skip_all)]
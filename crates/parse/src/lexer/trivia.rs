@@ -0,0 +1,208 @@
+use super::{
+    cursor::token::{RawToken, RawTokenKind},
+    Cursor, Lexer,
+};
+use solar_ast::token::{CommentKind, Token, TokenKind};
+use solar_interface::{BytePos, Pos, Span};
+
+/// A piece of source code that is not part of the syntax: whitespace and comments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Trivia {
+    /// The kind of trivia.
+    pub kind: TriviaKind,
+    /// The span of the trivia.
+    pub span: Span,
+}
+
+/// The kind of a [`Trivia`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// A run of whitespace.
+    Whitespace,
+    /// A comment or doc-comment.
+    Comment {
+        /// Whether the comment is a doc-comment.
+        is_doc: bool,
+        /// The kind of comment.
+        kind: CommentKind,
+    },
+    /// Characters that could not be lexed into a token. An error has been emitted for them.
+    Unknown,
+}
+
+/// A token with the trivia that surrounds it, produced by
+/// [`Lexer::into_tokens_with_trivia`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenWithTrivia {
+    /// The trivia before the token, starting after the trailing trivia of the previous token.
+    pub leading: Vec<Trivia>,
+    /// The token.
+    pub token: Token,
+    /// The trivia after the token, up to and including the end of its line.
+    pub trailing: Vec<Trivia>,
+}
+
+impl TokenWithTrivia {
+    /// Returns the span of the token, including its leading and trailing trivia.
+    pub fn full_span(&self) -> Span {
+        let lo = self.leading.first().map_or(self.token.span, |trivia| trivia.span);
+        let hi = self.trailing.last().map_or(self.token.span, |trivia| trivia.span);
+        lo.to(hi)
+    }
+}
+
+impl Lexer<'_, '_> {
+    /// Consumes the lexer and collects the remaining tokens into a vector, keeping all comments
+    /// and whitespace as [`Trivia`] attached to the tokens.
+    ///
+    /// Unlike [`into_tokens`](Self::into_tokens), this is lossless: concatenating the full spans
+    /// of the returned tokens, in order, yields the original source code. The last token is always
+    /// [`TokenKind::Eof`], and it holds the trivia at the end of the source as its leading trivia.
+    ///
+    /// A token's trailing trivia is the trivia on the same line after it, including the line
+    /// break, which is where trailing comments like `a; // comment` are attached. All other trivia
+    /// is leading trivia of the next token.
+    pub fn into_tokens_with_trivia(mut self) -> Vec<TokenWithTrivia> {
+        let src = self.src;
+        let start_pos = self.start_pos;
+        let mut tokens = Vec::<TokenWithTrivia>::with_capacity(src.len() / 8);
+        let mut trivia = Vec::new();
+        // The end of the last token or comment.
+        let mut pos = start_pos;
+        loop {
+            let token = self.next_token();
+            gap_trivia(src, start_pos, pos, token.span.lo(), &mut trivia);
+            pos = token.span.hi();
+            if let TokenKind::Comment(is_doc, kind, _) = token.kind {
+                let kind = TriviaKind::Comment { is_doc, kind };
+                trivia.push(Trivia { kind, span: token.span });
+                continue;
+            }
+
+            // Attach the trivia up to the end of the previous token's line to it.
+            let mut leading = std::mem::take(&mut trivia);
+            if let Some(prev) = tokens.last_mut() {
+                let text = |span: Span| {
+                    &src[(span.lo() - start_pos).to_usize()..(span.hi() - start_pos).to_usize()]
+                };
+                let mut n = 0;
+                let mut split = None;
+                for trivia in &leading {
+                    if trivia.kind == TriviaKind::Whitespace {
+                        if let Some(i) = text(trivia.span).find('\n') {
+                            split = Some(BytePos::from_usize(i + 1));
+                            break;
+                        }
+                    }
+                    n += 1;
+                }
+                prev.trailing.extend(leading.drain(..n));
+                if let Some(i) = split {
+                    let span = leading[0].span;
+                    let line_break = span.with_hi(span.lo() + i);
+                    prev.trailing.push(Trivia { kind: TriviaKind::Whitespace, span: line_break });
+                    if line_break.hi() == span.hi() {
+                        leading.remove(0);
+                    } else {
+                        leading[0].span = span.with_lo(line_break.hi());
+                    }
+                }
+            }
+
+            let is_eof = token.is_eof();
+            tokens.push(TokenWithTrivia { leading, token, trailing: Vec::new() });
+            if is_eof {
+                break;
+            }
+        }
+        tokens
+    }
+}
+
+/// Classifies the source code between two tokens, which contains no comments, into trivia.
+fn gap_trivia(src: &str, start_pos: BytePos, lo: BytePos, hi: BytePos, out: &mut Vec<Trivia>) {
+    let gap = &src[(lo - start_pos).to_usize()..(hi - start_pos).to_usize()];
+    let mut pos = lo;
+    for RawToken { kind, len } in Cursor::new(gap) {
+        let kind = match kind {
+            RawTokenKind::Whitespace => TriviaKind::Whitespace,
+            _ => TriviaKind::Unknown,
+        };
+        let span = Span::new(pos, pos + BytePos(len));
+        pos = span.hi();
+        match out.last_mut() {
+            Some(last) if last.kind == kind && last.span.hi() == span.lo() => {
+                last.span = last.span.to(span);
+            }
+            _ => out.push(Trivia { kind, span }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::Session;
+
+    fn text(src: &str, span: Span) -> &str {
+        &src[span.lo().to_usize()..span.hi().to_usize()]
+    }
+
+    fn lex(src: &str) -> Vec<TokenWithTrivia> {
+        let sess = Session::builder().with_silent_emitter(None).build();
+        sess.enter(|| Lexer::new(&sess, src).into_tokens_with_trivia())
+    }
+
+    #[test]
+    fn attach_trivia() {
+        let src = "// a\nx /* b */ ; // c\n\n  y\n";
+        let tokens = lex(src);
+        let describe = |trivia: &[Trivia]| {
+            trivia.iter().map(|trivia| text(src, trivia.span)).collect::<Vec<_>>()
+        };
+        let tokens = tokens
+            .iter()
+            .map(|t| (describe(&t.leading), text(src, t.token.span), describe(&t.trailing)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            [
+                (vec!["// a", "\n"], "x", vec![" ", "/* b */", " "]),
+                (vec![], ";", vec![" ", "// c", "\n"]),
+                (vec!["\n  "], "y", vec!["\n"]),
+                (vec![], "", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        let src = "a §§ b";
+        let tokens = lex(src);
+        let kinds = tokens[0].trailing.iter().map(|t| t.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [TriviaKind::Whitespace, TriviaKind::Unknown, TriviaKind::Whitespace]);
+        assert_eq!(text(src, tokens[0].trailing[1].span), "§§");
+    }
+
+    #[test]
+    fn round_trip_testdata() {
+        for path in crate::testdata_files() {
+            let src = std::fs::read_to_string(&path).unwrap();
+            let tokens = lex(&src);
+            assert!(tokens.last().unwrap().token.is_eof());
+            let mut out = String::with_capacity(src.len());
+            let mut pos = BytePos(0);
+            for token in &tokens {
+                let spans = token.leading.iter().map(|t| t.span);
+                let spans =
+                    spans.chain([token.token.span]).chain(token.trailing.iter().map(|t| t.span));
+                for span in spans {
+                    assert_eq!(span.lo(), pos, "{}: gap or overlap at {span:?}", path.display());
+                    out.push_str(text(&src, span));
+                    pos = span.hi();
+                }
+            }
+            assert!(out == src, "{}: round trip failed", path.display());
+        }
+    }
+}
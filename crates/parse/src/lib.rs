@@ -26,3 +26,28 @@ pub type PErr<'a> = DiagnosticBuilder<'a, ErrorGuaranteed>;
 
 /// Parser result type. This is a shorthand for `Result<T, PErr<'a>>`.
 pub type PResult<'a, T> = Result<T, PErr<'a>>;
+
+/// Returns the paths of all the Solidity files in `testdata`, including the solc test suite if it
+/// is checked out.
+#[cfg(test)]
+fn testdata_files() -> Vec<std::path::PathBuf> {
+    fn visit(path: &std::path::Path, out: &mut Vec<std::path::PathBuf>) {
+        if path.is_dir() {
+            let mut entries = std::fs::read_dir(path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect::<Vec<_>>();
+            entries.sort();
+            for entry in entries {
+                visit(&entry, out);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "sol") {
+            out.push(path.to_path_buf());
+        }
+    }
+
+    let mut files = Vec::new();
+    visit(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../testdata"), &mut files);
+    assert!(!files.is_empty());
+    files
+}
@@ -46,11 +46,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         let mut precedence = token_precedence(&self.token);
         while precedence >= min_precedence {
             while token_precedence(&self.token) == precedence {
-                // Parse a**b**c as a**(b**c)
-                let next_precedence = if self.token.kind == TokenKind::BinOp(BinOpToken::Star) {
-                    precedence + 1
-                } else {
+                // Parse a**b**c as a**(b**c), and all other binary operators as left-associative.
+                let next_precedence = if self.token.kind == TokenKind::StarStar {
                     precedence
+                } else {
+                    precedence + 1
                 };
 
                 let token = self.token.clone();
@@ -297,7 +297,7 @@ fn token_precedence(t: &Token) -> usize {
         BinOp(Star) => 13,
         BinOp(Slash) => 13,
         BinOp(Percent) => 13,
-        StarStar => 4,
+        StarStar => 14,
         EqEq => 6,
         Ne => 6,
         Lt => 7,
@@ -325,3 +325,66 @@ unsafe fn option_boxes_unwrap_unchecked<'a, 'b, T>(
     // SAFETY: Caller must ensure that all elements are `Some`.
     unsafe { std::mem::transmute(list) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::{source_map::FileName, Result, Session};
+
+    /// Formats an expression with all binary and unary operations parenthesized.
+    fn parenthesized(expr: &Expr<'_>) -> String {
+        match &expr.kind {
+            ExprKind::Binary(lhs, op, rhs) => {
+                format!("({} {} {})", parenthesized(lhs), op.kind.to_str(), parenthesized(rhs))
+            }
+            ExprKind::Unary(op, expr) => format!("({}{})", op.kind.to_str(), parenthesized(expr)),
+            ExprKind::Ident(ident) => ident.to_string(),
+            ExprKind::Lit(lit, _) => lit.symbol.to_string(),
+            kind => panic!("unexpected expression: {kind:?}"),
+        }
+    }
+
+    #[test]
+    fn binary_operators() {
+        fn check(tests: &[(&str, &str)]) {
+            solar_interface::enter(|| -> Result {
+                let sess = Session::builder().with_test_emitter().build();
+                for (i, &(s, expected)) in tests.iter().enumerate() {
+                    let name = i.to_string();
+                    let arena = Arena::new();
+                    let mut parser =
+                        Parser::from_source_code(&sess, &arena, FileName::Custom(name), s.into())?;
+
+                    let expr = parser
+                        .parse_expr()
+                        .map_err(|e| e.emit())
+                        .unwrap_or_else(|_| panic!("src: {s:?}"));
+                    sess.dcx.has_errors().unwrap();
+                    assert_eq!(parenthesized(expr), expected, "{s:?}");
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        check(&[
+            // Left-associative.
+            ("a - b - c", "((a - b) - c)"),
+            ("a / b * c", "((a / b) * c)"),
+            ("a << b >> c", "((a << b) >> c)"),
+            ("a == b != c", "((a == b) != c)"),
+            // Right-associative.
+            ("a ** b ** c", "(a ** (b ** c))"),
+            // `**` binds tighter than all other binary operators, but not unary ones.
+            ("a * b ** c", "(a * (b ** c))"),
+            ("a ** b * c", "((a ** b) * c)"),
+            ("2 ** 3 + 1", "((2 ** 3) + 1)"),
+            ("-a ** b", "((-a) ** b)"),
+            // `^` is bitwise xor, between `&` and `|`.
+            ("a ^ b", "(a ^ b)"),
+            ("a | b ^ c & d", "(a | (b ^ (c & d)))"),
+            ("a ^ b ^ c", "((a ^ b) ^ c)"),
+            ("a + b < c && d || e", "((((a + b) < c) && d) || e)"),
+        ]);
+    }
+}
@@ -319,12 +319,40 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             _ => unreachable!("parse_contract called without contract-like keyword"),
         };
         let name = self.parse_ident()?;
-        let bases =
-            if self.eat_keyword(kw::Is) { self.parse_inheritance()? } else { Default::default() };
+
+        // The inheritance and storage layout specifiers can appear in any order.
+        let mut bases = None;
+        let mut layout = None;
+        loop {
+            if bases.is_none() && self.eat_keyword(kw::Is) {
+                bases = Some(self.parse_inheritance()?);
+            } else if self.check_keyword(sym::layout) {
+                let new_layout = self.parse_storage_layout_specifier()?;
+                if layout.is_some() {
+                    let msg = "storage layout already specified";
+                    self.dcx().err(msg).span(new_layout.span).emit();
+                } else {
+                    layout = Some(new_layout);
+                }
+            } else {
+                break;
+            }
+        }
+        let bases = bases.unwrap_or_default();
+
         self.expect(&TokenKind::OpenDelim(Delimiter::Brace))?;
         let body =
             self.in_contract(|this| this.parse_items(&TokenKind::CloseDelim(Delimiter::Brace)))?;
-        Ok(ItemContract { kind, name, bases, body })
+        Ok(ItemContract { kind, name, bases, layout, body })
+    }
+
+    /// Parses a storage layout specifier: `layout at <slot>`.
+    fn parse_storage_layout_specifier(&mut self) -> PResult<'sess, StorageLayoutSpecifier<'ast>> {
+        let lo = self.token.span;
+        self.expect_keyword(sym::layout)?;
+        self.expect_keyword(sym::at)?;
+        let slot = self.parse_expr()?;
+        Ok(StorageLayoutSpecifier { span: lo.to(self.prev_token.span), slot })
     }
 
     /// Parses an enum definition.
@@ -850,8 +878,8 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
 
     /// Parses a list of inheritance specifiers.
     fn parse_inheritance(&mut self) -> PResult<'sess, Box<'ast, [Modifier<'ast>]>> {
-        self.parse_seq_to_before_end(
-            &TokenKind::OpenDelim(Delimiter::Brace),
+        self.parse_seq_to_before_tokens(
+            &[&TokenKind::OpenDelim(Delimiter::Brace), &TokenKind::Ident(sym::layout)],
             SeqSep::trailing_disallowed(TokenKind::Comma),
             false,
             Self::parse_modifier,
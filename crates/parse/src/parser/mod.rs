@@ -999,6 +999,10 @@ fn or_list<T: fmt::Display>(list: &[T]) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solar_ast::{
+        print::{PrintConfig, Printer},
+        visit::{Visit, VisitMut},
+    };
 
     #[test]
     fn test_or_list() {
@@ -1013,4 +1017,98 @@ mod tests {
             assert_eq!(or_list(tokens), expected, "{tokens:?}");
         }
     }
+
+    /// Parses `src`, returning `None` if there are any errors.
+    fn parse<'ast>(
+        sess: &Session,
+        arena: &'ast ast::Arena,
+        name: String,
+        src: &str,
+    ) -> Option<ast::SourceUnit<'ast>> {
+        let errors = sess.dcx.err_count();
+        let mut parser = Parser::from_lazy_source_code(sess, arena, FileName::Custom(name), || {
+            Ok(src.to_string())
+        })
+        .ok()?;
+        let source_unit = parser.parse_file().map_err(|e| e.emit()).ok()?;
+        (sess.dcx.err_count() == errors).then_some(source_unit)
+    }
+
+    /// Replaces parenthesized expressions with their contents.
+    struct RemoveParens;
+
+    impl<'ast> VisitMut<'ast> for RemoveParens {
+        fn visit_expr_mut(&mut self, expr: &'ast mut ast::Expr<'ast>) {
+            while let ast::ExprKind::Tuple([Some(inner)]) = &mut expr.kind {
+                expr.kind = std::mem::replace(&mut inner.kind, ast::ExprKind::Tuple(&mut []));
+            }
+            self.walk_expr_mut(expr);
+        }
+    }
+
+    /// Collects the structure of all expressions, as a list of `(depth, kind)`.
+    #[derive(Default)]
+    struct ExprShapes {
+        depth: usize,
+        shapes: Vec<(usize, String)>,
+    }
+
+    impl<'ast> Visit<'ast> for ExprShapes {
+        fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) {
+            let kind = match &expr.kind {
+                ast::ExprKind::Assign(_, op, _) => {
+                    format!("{}=", op.map_or("", |op| op.kind.to_str()))
+                }
+                ast::ExprKind::Binary(_, op, _) => op.kind.to_str().to_string(),
+                ast::ExprKind::Unary(op, _) => op.kind.to_str().to_string(),
+                ast::ExprKind::Ident(ident) => ident.to_string(),
+                kind => format!("{:?}", std::mem::discriminant(kind)),
+            };
+            self.shapes.push((self.depth, kind));
+            self.depth += 1;
+            self.walk_expr(expr);
+            self.depth -= 1;
+        }
+    }
+
+    #[test]
+    fn print_idempotence() {
+        for path in crate::testdata_files() {
+            let src = std::fs::read_to_string(&path).unwrap();
+            let sess = Session::builder().with_silent_emitter(None).build();
+            sess.enter(|| {
+                let arena = ast::Arena::new();
+                let print = |source_unit: &ast::SourceUnit<'_>| {
+                    Printer::new(PrintConfig::default()).print_source_unit(source_unit)
+                };
+                let name = path.display().to_string();
+                // Files with syntax errors are expected in the solc test suite.
+                let Some(mut source_unit) = parse(&sess, &arena, name.clone(), &src) else {
+                    return;
+                };
+
+                let printed = print(&source_unit);
+                let reparsed = parse(&sess, &arena, format!("{name}.1"), &printed)
+                    .unwrap_or_else(|| panic!("{name}: printed source failed to parse"));
+                assert!(print(&reparsed) == printed, "{name}: printing is not idempotent");
+
+                // Without the parentheses from the source, the printer must insert them where
+                // needed to preserve the structure of the expressions.
+                RemoveParens.visit_source_unit_mut(&mut source_unit);
+                let printed = print(&source_unit);
+                let mut reparsed = parse(&sess, &arena, format!("{name}.2"), &printed)
+                    .unwrap_or_else(|| panic!("{name}: printed source failed to parse"));
+                RemoveParens.visit_source_unit_mut(&mut reparsed);
+                let shapes = |source_unit: &ast::SourceUnit<'_>| {
+                    let mut shapes = ExprShapes::default();
+                    shapes.visit_source_unit(source_unit);
+                    shapes.shapes
+                };
+                assert!(
+                    shapes(&source_unit) == shapes(&reparsed),
+                    "{name}: printing changed the structure of expressions"
+                );
+            });
+        }
+    }
 }
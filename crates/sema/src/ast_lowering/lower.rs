@@ -4,6 +4,7 @@ use crate::{
 };
 use solar_ast::ast;
 use solar_data_structures::{index::IndexVec, smallvec::SmallVec};
+use solar_interface::{BytePos, Span, Symbol};
 
 impl<'ast> super::LoweringContext<'_, 'ast, '_> {
     #[instrument(level = "debug", skip_all)]
@@ -16,15 +17,15 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
                 file: source.file.clone(),
                 imports: self.arena.alloc_slice_copy(&source.imports),
                 items: &[],
+                using_directives: &[],
             };
             if let Some(ast) = &source.ast {
                 let mut items = SmallVec::<[_; 16]>::new();
                 self.current_source_id = id;
                 for item in ast.items.iter() {
                     match &item.kind {
-                        ast::ItemKind::Pragma(_)
-                        | ast::ItemKind::Import(_)
-                        | ast::ItemKind::Using(_) => {}
+                        ast::ItemKind::Pragma(_) | ast::ItemKind::Import(_) => {}
+                        ast::ItemKind::Using(_) => self.using_directives.push((id, None, item)),
                         ast::ItemKind::Contract(_)
                         | ast::ItemKind::Function(_)
                         | ast::ItemKind::Variable(_)
@@ -56,11 +57,13 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             // Set later.
             bases: &[],
             linearized_bases: &[],
+            layout: None,
 
             ctor: None,
             fallback: None,
             receive: None,
             items: &[],
+            using_directives: &[],
         });
         let prev_contract_id = std::mem::replace(&mut self.current_contract_id, Some(id));
         debug_assert_eq!(prev_contract_id, None);
@@ -71,7 +74,10 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
                 ast::ItemKind::Pragma(_)
                 | ast::ItemKind::Import(_)
                 | ast::ItemKind::Contract(_) => unreachable!("illegal item in contract body"),
-                ast::ItemKind::Using(_) => continue,
+                ast::ItemKind::Using(_) => {
+                    self.using_directives.push((self.current_source_id, Some(id), item));
+                    continue;
+                }
                 ast::ItemKind::Variable(_) => {
                     let hir::ItemId::Variable(id) = self.lower_item(item) else { unreachable!() };
                     items.push(hir::ItemId::Variable(id));
@@ -111,6 +117,9 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             ast::ItemKind::Event(i) => hir::ItemId::Event(self.lower_event(item, i)),
         };
         self.hir_to_ast.insert(item_id, item);
+        if !item.docs.is_empty() {
+            self.hir.docs.insert(item_id, self.arena.alloc_slice_clone(item.docs));
+        }
         item_id
     }
 
@@ -173,9 +182,51 @@ impl<'ast> super::LoweringContext<'_, 'ast, '_> {
             span: item.span,
             name,
             fields: &[],
+            storage_location: self.lower_storage_location(item.docs),
         })
     }
 
+    /// Lowers the `@custom:storage-location <formula>:<id>` NatSpec tag of a struct.
+    fn lower_storage_location(&self, docs: &[ast::DocComment]) -> Option<hir::StorageLocation> {
+        const TAG: &str = "@custom:storage-location";
+
+        let mut location = None::<hir::StorageLocation>;
+        for doc in docs {
+            // The contents start after the `///` or `/**` quotes.
+            let contents_lo = doc.span.lo() + BytePos(3);
+            let contents = doc.symbol.as_str();
+            for line in contents.lines() {
+                let trimmed = line.trim_start().trim_start_matches('*').trim_start();
+                let Some(value) = trimmed.strip_prefix(TAG) else { continue };
+                let value = value.trim();
+                let offset = value.as_ptr() as usize - contents.as_ptr() as usize;
+                let lo = contents_lo + BytePos(offset as u32);
+                let span = Span::new(lo, lo + BytePos(value.len() as u32));
+
+                let Some((formula, id)) = value
+                    .split_once(':')
+                    .filter(|(formula, id)| !formula.is_empty() && !id.is_empty())
+                else {
+                    let msg = "invalid storage location; expected `<formula>:<id>`";
+                    self.dcx().err(msg).span(span).emit();
+                    continue;
+                };
+                if let Some(prev) = location {
+                    let msg = "storage location already specified";
+                    let note = "previous storage location specified here";
+                    self.dcx().err(msg).span(span).span_note(prev.span, note).emit();
+                    continue;
+                }
+                location = Some(hir::StorageLocation {
+                    span,
+                    formula: Symbol::intern(formula),
+                    id: Symbol::intern(id),
+                });
+            }
+        }
+        location
+    }
+
     fn lower_enum(&mut self, item: &ast::Item<'_>, i: &ast::ItemEnum<'_>) -> hir::EnumId {
         let ast::ItemEnum { name, ref variants } = *i;
         self.hir.enums.push(hir::Enum {
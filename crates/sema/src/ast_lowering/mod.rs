@@ -55,6 +55,8 @@ struct LoweringContext<'sess, 'ast, 'hir> {
     hir: Hir<'hir>,
     /// Mapping from Hir ItemId to AST Item. Does not include function parameters or bodies.
     hir_to_ast: FxHashMap<hir::ItemId, &'ast ast::Item<'ast>>,
+    /// The `using for` directives and the source and contract they are declared in.
+    using_directives: Vec<(hir::SourceId, Option<hir::ContractId>, &'ast ast::Item<'ast>)>,
 
     /// Current source being lowered.
     current_source_id: hir::SourceId,
@@ -73,6 +75,7 @@ impl<'sess, 'hir> LoweringContext<'sess, '_, 'hir> {
             current_source_id: hir::SourceId::MAX,
             current_contract_id: None,
             hir_to_ast: FxHashMap::default(),
+            using_directives: Vec::new(),
             resolver: SymbolResolver::new(&sess.dcx),
         }
     }
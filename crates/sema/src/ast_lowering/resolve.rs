@@ -4,10 +4,12 @@ use solar_data_structures::{
     index::{Idx, IndexVec},
     map::{FxIndexMap, IndexEntry},
     smallvec::SmallVec,
+    sync::Lock,
     BumpExt,
 };
 use solar_interface::{
     diagnostics::{DiagCtxt, ErrorGuaranteed},
+    edit_distance::find_best_match_for_name,
     sym, Ident, Session, Span, Symbol,
 };
 use std::{fmt, sync::atomic::AtomicUsize};
@@ -54,6 +56,10 @@ impl super::LoweringContext<'_, '_, '_> {
                 match import.items {
                     ast::ImportItems::Plain(alias) | ast::ImportItems::Glob(alias) => {
                         if let Some(alias) = alias {
+                            self.resolver
+                                .resolutions
+                                .lock()
+                                .push((alias.span, Res::Namespace(import_id)));
                             let _ = source_scope.declare_res(
                                 self.sess,
                                 &self.hir,
@@ -81,21 +87,26 @@ impl super::LoweringContext<'_, '_, '_> {
                                 Self::perform_alias_import(
                                     self.sess,
                                     &self.hir,
+                                    &self.resolver.resolutions,
                                     source,
                                     source_scope,
                                     name,
                                     import,
                                     import_scope.resolve(import),
+                                    || similar_declaration(import, [import_scope]),
                                 )
                             } else {
+                                let similar = similar_declaration(import, [&*source_scope]);
                                 Self::perform_alias_import(
                                     self.sess,
                                     &self.hir,
+                                    &self.resolver.resolutions,
                                     source,
                                     source_scope,
                                     name,
                                     import,
                                     source_scope.resolve_cloned(import),
+                                    || similar,
                                 )
                             }
                         }
@@ -106,19 +117,27 @@ impl super::LoweringContext<'_, '_, '_> {
     }
 
     /// Separate function to avoid cloning `resolved` when the import is not a self-import.
+    #[allow(clippy::too_many_arguments)]
     fn perform_alias_import(
         sess: &Session,
         hir: &hir::Hir<'_>,
+        resolutions: &Lock<Vec<(Span, Res)>>,
         source: &hir::Source<'_>,
         source_scope: &mut Declarations,
         name: Ident,
         import: Ident,
         resolved: Option<impl AsRef<[Declaration]>>,
+        similar: impl FnOnce() -> Option<(Symbol, Res)>,
     ) {
         if let Some(resolved) = resolved {
             let resolved = resolved.as_ref();
             debug_assert!(!resolved.is_empty());
+            let mut resolutions = resolutions.lock();
             for decl in resolved {
+                resolutions.push((import.span, decl.res));
+                if name.span != import.span {
+                    resolutions.push((name.span, decl.res));
+                }
                 // Re-span to the import name.
                 let mut decl = *decl;
                 decl.span = name.span;
@@ -129,7 +148,11 @@ impl super::LoweringContext<'_, '_, '_> {
                 "declaration `{import}` not found in {}",
                 sess.source_map().filename_for_diagnostics(&source.file.name)
             );
-            let guar = sess.dcx.err(msg).span(import.span).emit();
+            let mut diag = sess.dcx.err(msg).span(import.span);
+            if let Some((similar, res)) = similar() {
+                diag = diag.help(similar_declaration_help(similar, res));
+            }
+            let guar = diag.emit();
             let _ = source_scope.declare_res(sess, hir, name, Res::Err(guar));
         }
     }
@@ -290,6 +313,15 @@ impl super::LoweringContext<'_, '_, '_> {
             self.hir.events[id].parameters = cx.lower_variables(ast_event.parameters);
         }
 
+        for id in self.hir.contract_ids() {
+            let ast_item = self.hir_to_ast[&hir::ItemId::Contract(id)];
+            let ast::ItemKind::Contract(ast_contract) = &ast_item.kind else { unreachable!() };
+            let Some(layout) = &ast_contract.layout else { continue };
+            let c = self.hir.contract(id);
+            let mut cx = mk_resolver!(@scopes SymbolResolverScopes::new_in(c.source, Some(id)));
+            self.hir.contracts[id].layout = Some(cx.lower_expr(layout.slot));
+        }
+
         // Resolve constants and state variables.
         let normal_vars = self.hir.variables.len();
         for id in self.hir.variable_ids() {
@@ -384,6 +416,24 @@ impl super::LoweringContext<'_, '_, '_> {
         for id in self.hir.variable_ids().skip(normal_vars) {
             self.resolve_var(id, next_id);
         }
+
+        let mut source_directives = FxIndexMap::<_, Vec<_>>::default();
+        let mut contract_directives = FxIndexMap::<_, Vec<_>>::default();
+        for &(source, contract, item) in &self.using_directives {
+            let ast::ItemKind::Using(using) = &item.kind else { unreachable!() };
+            let mut cx = mk_resolver!(@scopes SymbolResolverScopes::new_in(source, contract));
+            let directive = cx.lower_using_directive(item.span, using);
+            match contract {
+                Some(contract) => contract_directives.entry(contract).or_default().push(directive),
+                None => source_directives.entry(source).or_default().push(directive),
+            }
+        }
+        for (id, directives) in source_directives {
+            self.hir.sources[id].using_directives = self.arena.alloc_vec(directives);
+        }
+        for (id, directives) in contract_directives {
+            self.hir.contracts[id].using_directives = self.arena.alloc_vec(directives);
+        }
     }
 
     fn resolve_var(&mut self, id: hir::VariableId, next_id: &AtomicUsize) {
@@ -491,7 +541,8 @@ impl super::LoweringContext<'_, '_, '_> {
             let s = self.hir.strukt(s_id);
             for &field_id in s.fields.iter() {
                 let field = self.hir.variable(field_id);
-                if !matches!(field.ty.kind, hir::TypeKind::Mapping(_)) {
+                // Mappings and arrays are omitted.
+                if !matches!(field.ty.kind, hir::TypeKind::Mapping(_) | hir::TypeKind::Array(_)) {
                     push_return(self, field.ty.clone(), field.name);
                 }
             }
@@ -646,6 +697,49 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
         self.resolver.resolve_path_as(path, &self.scopes, description)
     }
 
+    fn lower_using_directive(
+        &mut self,
+        span: Span,
+        using: &ast::UsingDirective<'_>,
+    ) -> hir::UsingDirective<'hir> {
+        let mut functions = SmallVec::<[_; 8]>::new();
+        match &using.list {
+            ast::UsingList::Single(path) => {
+                if let Ok(id) = self.resolve_path_as::<hir::ContractId>(path, "library") {
+                    let library = self.hir.contract(id);
+                    if library.kind.is_library() {
+                        functions.extend(library.functions());
+                    } else {
+                        self.resolver.report_expected(
+                            "library",
+                            library.kind.to_str(),
+                            path.span(),
+                        );
+                    }
+                }
+            }
+            ast::UsingList::Multiple(paths) => {
+                for (path, op) in paths.iter() {
+                    // Functions bound to operators are not attached as members.
+                    if op.is_some() {
+                        continue;
+                    }
+                    let Ok(decls) = self.resolve_paths(path) else { continue };
+                    functions.extend(decls.iter().filter_map(|decl| match decl.res {
+                        Res::Item(hir::ItemId::Function(id)) => Some(id),
+                        _ => None,
+                    }));
+                }
+            }
+        }
+        hir::UsingDirective {
+            span,
+            functions: self.arena.alloc_smallvec(functions),
+            ty: using.ty.as_ref().map(|ty| self.lower_type(ty)),
+            global: using.global,
+        }
+    }
+
     /// Lowers the given statements by first entering a new scope.
     fn lower_block(&mut self, block: &[ast::Stmt<'_>]) -> hir::Block<'hir> {
         self.in_scope_if(!block.is_empty(), |this| this.lower_stmts(block))
@@ -979,6 +1073,8 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
 struct ResolverError {
     name: Ident,
     kind: ResolverErrorKind,
+    /// A declaration with a name similar to the unresolved one.
+    similar: Option<(Symbol, Res)>,
 }
 
 enum ResolverErrorKind {
@@ -989,11 +1085,17 @@ enum ResolverErrorKind {
 
 impl ResolverError {
     fn new(name: Ident, kind: ResolverErrorKind) -> Self {
-        Self { name, kind }
+        Self { name, kind, similar: None }
     }
 
     fn from_path(path: &ast::PathSlice, index: usize, kind: ResolverErrorKind) -> Self {
-        Self { name: path.segments()[index], kind }
+        Self::new(path.segments()[index], kind)
+    }
+
+    /// Suggests a declaration of the given scopes with a name similar to the unresolved one.
+    fn with_similar_in<'a>(mut self, scopes: impl IntoIterator<Item = &'a Declarations>) -> Self {
+        self.similar = similar_declaration(self.name, scopes);
+        self
     }
 
     fn span(&self) -> Span {
@@ -1021,8 +1123,12 @@ pub(crate) struct SymbolResolver<'sess> {
     dcx: &'sess DiagCtxt,
     pub(crate) source_scopes: IndexVec<hir::SourceId, Declarations>,
     pub(crate) contract_scopes: IndexVec<hir::ContractId, Declarations>,
-    global_builtin_scope: Declarations,
+    pub(crate) global_builtin_scope: Declarations,
     builtin_members_scopes: Box<[Option<Declarations>; Builtin::COUNT]>,
+    /// The resolutions of the names in paths and import items, by the span of the name.
+    ///
+    /// Names that resolve to multiple declarations have one entry per declaration.
+    pub(crate) resolutions: Lock<Vec<(Span, Res)>>,
 }
 
 impl<'sess> SymbolResolver<'sess> {
@@ -1034,9 +1140,14 @@ impl<'sess> SymbolResolver<'sess> {
             contract_scopes: IndexVec::new(),
             global_builtin_scope,
             builtin_members_scopes,
+            resolutions: Lock::new(Vec::new()),
         }
     }
 
+    fn record_all(&self, name: Ident, decls: &[Declaration]) {
+        self.resolutions.lock().extend(decls.iter().map(|decl| (name.span, decl.res)));
+    }
+
     fn resolve_path_as<T: TryFrom<Res>>(
         &self,
         path: &ast::PathSlice,
@@ -1052,7 +1163,13 @@ impl<'sess> SymbolResolver<'sess> {
     }
 
     fn emit_resolver_error(&self) -> impl Fn(ResolverError) -> ErrorGuaranteed + '_ {
-        move |e| self.dcx.err(e.format()).span(e.span()).emit()
+        move |e| {
+            let mut diag = self.dcx.err(e.format()).span(e.span());
+            if let Some((name, res)) = e.similar {
+                diag = diag.help(similar_declaration_help(name, res));
+            }
+            diag.emit()
+        }
     }
 
     fn resolve_path(
@@ -1075,9 +1192,11 @@ impl<'sess> SymbolResolver<'sess> {
     ) -> Result<&'a [Declaration], ResolverError> {
         let mut segments = path.segments().iter();
         let name = *segments.next().unwrap();
-        let mut decls = self
-            .resolve_name_raw(name, scopes)
-            .ok_or_else(|| ResolverError::new(name, ResolverErrorKind::Unresolved))?;
+        let mut decls = self.resolve_name_raw(name, scopes).ok_or_else(|| {
+            ResolverError::new(name, ResolverErrorKind::Unresolved)
+                .with_similar_in(scopes.get(self))
+        })?;
+        self.record_all(name, decls);
         for (prev_i, &segment) in segments.enumerate() {
             let [decl] = decls else {
                 return Err(ResolverError::from_path(
@@ -1094,7 +1213,9 @@ impl<'sess> SymbolResolver<'sess> {
             })?;
             decls = scope.resolve(segment).ok_or_else(|| {
                 ResolverError::from_path(path, prev_i + 1, ResolverErrorKind::Unresolved)
+                    .with_similar_in([scope])
             })?;
+            self.record_all(segment, decls);
         }
         Ok(decls)
     }
@@ -1317,6 +1438,29 @@ impl PartialEq for Declaration {
 
 impl Eq for Declaration {}
 
+/// Finds a declaration in the given scopes with a name similar to `name`, to suggest it when `name`
+/// cannot be resolved. Inner scopes come first.
+fn similar_declaration<'a>(
+    name: Ident,
+    scopes: impl IntoIterator<Item = &'a Declarations>,
+) -> Option<(Symbol, Res)> {
+    let candidates = scopes
+        .into_iter()
+        .flat_map(|scope| &scope.declarations)
+        .filter_map(|(&name, decls)| Some((name, decls.first()?.res)))
+        .filter(|(_, res)| !res.is_err())
+        .collect::<Vec<_>>();
+    let names = candidates.iter().map(|&(name, _)| name).collect::<Vec<_>>();
+    let similar = find_best_match_for_name(&names, name.name, None)?;
+    candidates.into_iter().find(|&(name, _)| name == similar)
+}
+
+pub(crate) fn similar_declaration_help(name: Symbol, res: Res) -> String {
+    let description = res.description();
+    let article = if description.starts_with(['a', 'e', 'i', 'o']) { "an" } else { "a" };
+    format!("{article} {description} with a similar name exists: `{name}`")
+}
+
 pub(super) fn report_conflict(
     hir: &hir::Hir<'_>,
     sess: &Session,
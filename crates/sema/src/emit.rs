@@ -1,16 +1,34 @@
-use crate::ty::Gcx;
+use crate::{
+    hir,
+    ty::{Call, CallKind, DevDoc, Gcx, StorageLayout, UserDoc},
+};
+use alloy_primitives::{hex, keccak256};
 use serde::Serialize;
-use solar_interface::config::CompilerOutput;
+use solar_data_structures::map::{FxHashMap, FxHashSet};
+use solar_interface::{
+    config::{CompilerOutput, GraphFormat, Language, OutputLayout},
+    source_map::FileName,
+};
 use std::{
-    collections::BTreeMap,
+    collections::{hash_map::Entry, BTreeMap},
+    fmt::Write as _,
     io::{self, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 #[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct CombinedJson {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    call_graph: Option<CallGraph>,
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     contracts: BTreeMap<String, CombinedJsonContract>,
+    /// EIP-712 types of structs declared outside of contracts, keyed by their fully qualified
+    /// name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    eip712: Eip712Types,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inheritance_graph: Option<InheritanceGraph>,
     version: &'static str,
 }
 
@@ -20,45 +38,652 @@ struct CombinedJsonContract {
     abi: Option<Abi>,
     #[serde(skip_serializing_if = "Option::is_none")]
     hashes: Option<Hashes>,
+    /// The Solidity interface source code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interface: Option<String>,
+    /// The [`Metadata`] serialized as a JSON string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selectors: Option<Selectors>,
+    #[serde(rename = "storage-layout", skip_serializing_if = "Option::is_none")]
+    storage_layout: Option<StorageLayout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    eip712: Option<Eip712Types>,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Selectors {
+    functions: Hashes,
+    /// The `topic0` of non-anonymous events.
+    events: Hashes,
+    errors: Hashes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interface_id: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Eip712Type {
+    encode_type: String,
+    typehash: String,
+}
+
+/// Contract metadata.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/metadata.html>
+#[derive(Serialize)]
+struct Metadata {
+    compiler: MetadataCompiler,
+    language: &'static str,
+    output: MetadataOutput,
+    settings: MetadataSettings,
+    /// The sources the contract was compiled from, keyed by path.
+    sources: BTreeMap<String, MetadataSource>,
+    version: u32,
+}
+
+#[derive(Serialize)]
+struct MetadataCompiler {
+    version: &'static str,
+}
+
+#[derive(Serialize)]
+struct MetadataOutput {
+    abi: Abi,
+    devdoc: DevDoc,
+    userdoc: UserDoc,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MetadataSettings {
+    /// The contract, as a map from its source path to its name.
+    compilation_target: BTreeMap<String, String>,
+    evm_version: &'static str,
+    libraries: BTreeMap<String, String>,
+    remappings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct MetadataSource {
+    keccak256: String,
+    /// The SPDX license identifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+}
+
+/// A contract in the inheritance graph.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InheritanceNode {
+    kind: &'static str,
+    /// The direct bases, in declaration order.
+    bases: Vec<String>,
+    /// The C3 linearization, starting with the contract itself.
+    linearized_bases: Vec<String>,
+}
+
+/// An edge of the call graph. Functions are identified by [`function_name`].
+#[derive(Serialize)]
+struct CallGraphEdge {
+    caller: String,
+    callee: String,
+    kind: &'static str,
 }
 
 type Abi = Vec<alloy_json_abi::AbiItem<'static>>;
 type Hashes = BTreeMap<String, String>;
+type Eip712Types = BTreeMap<String, Eip712Type>;
+/// Keyed by fully qualified contract name.
+type InheritanceGraph = BTreeMap<String, InheritanceNode>;
+type CallGraph = Vec<CallGraphEdge>;
 
 pub(crate) fn emit(gcx: Gcx<'_>) {
-    let mut output =
-        CombinedJson { contracts: Default::default(), version: solar_interface::VERSION };
-    for id in gcx.hir.contract_ids() {
-        let name = gcx.contract_fully_qualified_name(id).to_string();
-        let contract_output = output.contracts.entry(name).or_default();
-        for &emit in &gcx.sess.emit {
-            match emit {
-                CompilerOutput::Abi => contract_output.abi = Some(gcx.contract_abi(id)),
-                CompilerOutput::Hashes => {
-                    let mut hashes = Hashes::default();
-                    for f in gcx.interface_functions(id) {
-                        hashes.insert(
-                            gcx.item_signature(f.id.into()).to_string(),
-                            alloy_primitives::hex::encode(f.selector),
-                        );
+    let is_graph = |emit: &CompilerOutput| {
+        matches!(emit, CompilerOutput::InheritanceGraph | CompilerOutput::CallGraph)
+    };
+    let only_graphs = gcx.sess.emit.iter().all(is_graph);
+    let contracts = if only_graphs {
+        Vec::new()
+    } else {
+        gcx.hir.contract_ids().map(|id| (id, contract_output(gcx, id))).collect::<Vec<_>>()
+    };
+    let mut eip712 = Eip712Types::default();
+    if gcx.sess.emit.contains(&CompilerOutput::Eip712) {
+        for s in gcx.hir.strukt_ids() {
+            let strukt = gcx.hir.strukt(s);
+            if strukt.contract.is_some() {
+                continue;
+            }
+            if let Some(ty) = eip712_type(gcx, s) {
+                let source = gcx.hir.source(strukt.source);
+                let name = format!("{}:{}", source.file.name.display(), strukt.name);
+                eip712.insert(name, ty);
+            }
+        }
+    }
+    let format = gcx.sess.graph_format;
+    let mut graphs = Graphs {
+        inheritance: gcx.sess.emit.contains(&CompilerOutput::InheritanceGraph).then(
+            || match format {
+                GraphFormat::Json => Graph::Json(inheritance_graph(gcx)),
+                GraphFormat::Dot => Graph::Dot(inheritance_dot(gcx)),
+            },
+        ),
+        calls: gcx.sess.emit.contains(&CompilerOutput::CallGraph).then(|| {
+            let calls = gcx.call_graph();
+            match format {
+                GraphFormat::Json => Graph::Json(call_graph(gcx, &calls)),
+                GraphFormat::Dot => Graph::Dot(call_graph_dot(gcx, &calls)),
+            }
+        }),
+    };
+
+    let pretty = gcx.sess.pretty_json;
+    let out_dir = gcx.sess.out_dir.as_deref();
+    let result = match (out_dir, gcx.sess.output_layout) {
+        (Some(dir), OutputLayout::Split) => emit_split(gcx, dir, &contracts, &eip712),
+        (Some(dir), OutputLayout::Artifacts) => emit_artifacts(gcx, dir, &contracts, &eip712),
+        (dir, _) => {
+            let graphs_in_combined = format == GraphFormat::Json;
+            if only_graphs && !graphs_in_combined {
+                Ok(())
+            } else {
+                let graphs = if graphs_in_combined { graphs.take() } else { Graphs::default() };
+                let output = CombinedJson {
+                    call_graph: graphs.calls.and_then(Graph::into_json),
+                    contracts: contracts
+                        .into_iter()
+                        .map(|(id, output)| {
+                            (gcx.contract_fully_qualified_name(id).to_string(), output)
+                        })
+                        .collect(),
+                    eip712,
+                    inheritance_graph: graphs.inheritance.and_then(Graph::into_json),
+                    version: solar_interface::VERSION,
+                };
+                let out_path = dir.map(|dir| dir.join("combined.json"));
+                write_json(out_path.as_deref(), &output, pretty)
+            }
+        }
+    }
+    .and_then(|()| emit_graphs(gcx, out_dir, &graphs));
+    let _ = result.map_err(|e| gcx.dcx().err(format!("failed to write to output: {e}")).emit());
+}
+
+fn contract_output(gcx: Gcx<'_>, id: hir::ContractId) -> CombinedJsonContract {
+    let mut contract_output = CombinedJsonContract::default();
+    for &emit in &gcx.sess.emit {
+        match emit {
+            CompilerOutput::Abi => contract_output.abi = Some(gcx.contract_abi(id)),
+            CompilerOutput::Hashes => {
+                let mut hashes = Hashes::default();
+                for f in gcx.interface_functions(id) {
+                    hashes.insert(
+                        gcx.item_signature(f.id.into()).to_string(),
+                        hex::encode(f.selector),
+                    );
+                }
+                contract_output.hashes = Some(hashes);
+            }
+            CompilerOutput::Selectors => {
+                contract_output.selectors = Some(selectors(gcx, id));
+            }
+            CompilerOutput::StorageLayout => {
+                contract_output.storage_layout = Some(gcx.storage_layout(id));
+            }
+            CompilerOutput::InheritanceGraph | CompilerOutput::CallGraph => {}
+            CompilerOutput::Metadata => {
+                contract_output.metadata = Some(metadata(gcx, id));
+            }
+            CompilerOutput::Interface => {
+                contract_output.interface = gcx.contract_interface_source(id);
+            }
+            CompilerOutput::Eip712 => {
+                let mut types = Eip712Types::default();
+                for s in gcx.hir.contract(id).structs() {
+                    if let Some(ty) = eip712_type(gcx, s) {
+                        types.insert(gcx.item_name(s).to_string(), ty);
                     }
-                    contract_output.hashes = Some(hashes);
                 }
+                contract_output.eip712 = Some(types);
+            }
+        }
+    }
+    contract_output
+}
+
+/// Writes one file per contract and type of output, with the same names as `solc -o`.
+fn emit_split(
+    gcx: Gcx<'_>,
+    dir: &Path,
+    contracts: &[(hir::ContractId, CombinedJsonContract)],
+    eip712: &Eip712Types,
+) -> io::Result<()> {
+    let pretty = gcx.sess.pretty_json;
+    let mut paths = PathChecker::new(gcx);
+    for (id, output) in contracts {
+        let name = gcx.item_name(*id);
+        if !paths.insert(dir.join(name.as_str()), *id) {
+            continue;
+        }
+        let path = |suffix: &str| dir.join(format!("{name}{suffix}"));
+        let CombinedJsonContract {
+            abi,
+            hashes,
+            interface,
+            metadata,
+            selectors,
+            storage_layout,
+            eip712: types,
+        } = output;
+        if let Some(abi) = abi {
+            write_json(Some(&path(".abi")), abi, pretty)?;
+        }
+        if let Some(hashes) = hashes {
+            let mut writer = out_writer(Some(&path(".signatures")))?;
+            for (signature, selector) in hashes {
+                writeln!(writer, "{selector}: {signature}")?;
+            }
+            writer.flush()?;
+        }
+        if let Some(interface) = interface {
+            write_file(&dir.join(format!("I{name}.sol")), interface)?;
+        }
+        if let Some(metadata) = metadata {
+            write_file(&path("_meta.json"), metadata)?;
+        }
+        if let Some(selectors) = selectors {
+            write_json(Some(&path("_selectors.json")), selectors, pretty)?;
+        }
+        if let Some(storage_layout) = storage_layout {
+            write_json(Some(&path("_storage.json")), storage_layout, pretty)?;
+        }
+        if let Some(types) = types {
+            write_json(Some(&path("_eip712.json")), types, pretty)?;
+        }
+    }
+    if !eip712.is_empty() {
+        write_json(Some(&dir.join("eip712.json")), eip712, pretty)?;
+    }
+    Ok(())
+}
+
+/// A Foundry and Hardhat-style contract artifact.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Artifact<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    abi: Option<&'a Abi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    method_identifiers: Option<&'a Hashes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selectors: Option<&'a Selectors>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage_layout: Option<&'a StorageLayout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    eip712: Option<&'a Eip712Types>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interface: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw_metadata: Option<&'a str>,
+}
+
+/// Writes one artifact per contract to `<File>.sol/<Contract>.json`.
+fn emit_artifacts(
+    gcx: Gcx<'_>,
+    dir: &Path,
+    contracts: &[(hir::ContractId, CombinedJsonContract)],
+    eip712: &Eip712Types,
+) -> io::Result<()> {
+    let pretty = gcx.sess.pretty_json;
+    let mut paths = PathChecker::new(gcx);
+    for (id, output) in contracts {
+        let source = gcx.hir.source(gcx.hir.contract(*id).source);
+        let file_name = match &source.file.name {
+            FileName::Real(path) => {
+                path.file_name().map(|name| name.to_string_lossy().into_owned())
             }
+            FileName::Stdin => Some("stdin".into()),
+            FileName::Custom(name) => Some(name.clone()),
+        };
+        let Some(file_name) = file_name else { continue };
+        let path = dir.join(file_name).join(format!("{}.json", gcx.item_name(*id)));
+        if !paths.insert(path.clone(), *id) {
+            continue;
         }
+        let artifact = Artifact {
+            abi: output.abi.as_ref(),
+            method_identifiers: output.hashes.as_ref(),
+            selectors: output.selectors.as_ref(),
+            storage_layout: output.storage_layout.as_ref(),
+            eip712: output.eip712.as_ref(),
+            interface: output.interface.as_deref(),
+            metadata: output.metadata.as_deref().and_then(|s| serde_json::from_str(s).ok()),
+            raw_metadata: output.metadata.as_deref(),
+        };
+        write_json(Some(&path), &artifact, pretty)?;
     }
-    let _ = (|| {
-        let out_path = gcx.sess.out_dir.as_deref().map(|dir| dir.join("combined.json"));
-        let mut writer = out_writer(out_path.as_deref())?;
-        to_json(&mut writer, &output, gcx.sess.pretty_json)?;
-        writer.flush()?;
-        Ok::<_, io::Error>(())
-    })()
-    .map_err(|e| gcx.dcx().err(format!("failed to write to output: {e}")).emit());
+    if !eip712.is_empty() {
+        write_json(Some(&dir.join("eip712.json")), eip712, pretty)?;
+    }
+    Ok(())
+}
+
+/// Reports contracts that would be written to the same output path.
+struct PathChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    paths: FxHashMap<PathBuf, hir::ContractId>,
+}
+
+impl<'gcx> PathChecker<'gcx> {
+    fn new(gcx: Gcx<'gcx>) -> Self {
+        Self { gcx, paths: FxHashMap::default() }
+    }
+
+    /// Returns `false` and emits an error if the path is already used by another contract.
+    fn insert(&mut self, path: PathBuf, id: hir::ContractId) -> bool {
+        let gcx = self.gcx;
+        match self.paths.entry(path) {
+            Entry::Occupied(entry) => {
+                let msg = format!(
+                    "contracts `{}` and `{}` would be written to the same output file `{}`",
+                    gcx.contract_fully_qualified_name(*entry.get()),
+                    gcx.contract_fully_qualified_name(id),
+                    entry.key().display(),
+                );
+                gcx.dcx().err(msg).help("use `--output-layout combined` instead").emit();
+                false
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(id);
+                true
+            }
+        }
+    }
+}
+
+/// The requested inheritance and call graphs.
+#[derive(Default)]
+struct Graphs {
+    inheritance: Option<Graph<InheritanceGraph>>,
+    calls: Option<Graph<CallGraph>>,
+}
+
+impl Graphs {
+    fn take(&mut self) -> Self {
+        std::mem::take(self)
+    }
+}
+
+/// A graph in the requested [`GraphFormat`].
+enum Graph<T> {
+    Json(T),
+    Dot(String),
+}
+
+impl<T> Graph<T> {
+    fn into_json(self) -> Option<T> {
+        match self {
+            Self::Json(graph) => Some(graph),
+            Self::Dot(_) => None,
+        }
+    }
+}
+
+/// Writes the graphs that are not part of `combined.json`, to `<out_dir>/<graph>.{json,dot}` or
+/// stdout.
+fn emit_graphs(gcx: Gcx<'_>, dir: Option<&Path>, graphs: &Graphs) -> io::Result<()> {
+    let format = gcx.sess.graph_format;
+    let path = |name: &str| dir.map(|dir| dir.join(format!("{name}.{format}")));
+    if let Some(graph) = &graphs.inheritance {
+        write_graph(gcx, path("inheritance-graph").as_deref(), graph)?;
+    }
+    if let Some(graph) = &graphs.calls {
+        write_graph(gcx, path("call-graph").as_deref(), graph)?;
+    }
+    Ok(())
+}
+
+fn write_graph<T: Serialize>(
+    gcx: Gcx<'_>,
+    path: Option<&Path>,
+    graph: &Graph<T>,
+) -> io::Result<()> {
+    match graph {
+        Graph::Json(graph) => write_json(path, graph, gcx.sess.pretty_json),
+        Graph::Dot(dot) => write_dot(path, dot),
+    }
+}
+
+fn inheritance_graph(gcx: Gcx<'_>) -> InheritanceGraph {
+    let name = |id| gcx.contract_fully_qualified_name(id).to_string();
+    gcx.hir
+        .contract_ids()
+        .map(|id| {
+            let c = gcx.hir.contract(id);
+            let node = InheritanceNode {
+                kind: c.kind.to_str(),
+                bases: c.bases.iter().map(|&base| name(base)).collect(),
+                linearized_bases: c.linearized_bases.iter().map(|&base| name(base)).collect(),
+            };
+            (name(id), node)
+        })
+        .collect()
+}
+
+fn call_graph(gcx: Gcx<'_>, calls: &[Call]) -> CallGraph {
+    calls
+        .iter()
+        .map(|call| CallGraphEdge {
+            caller: function_name(gcx, call.caller),
+            callee: function_name(gcx, call.callee),
+            kind: call.kind.to_str(),
+        })
+        .collect()
+}
+
+/// Returns the name of a function in the call graph: `<path>:<Contract>.<name>(<types>)`, or
+/// `<path>:<name>(<types>)` for free functions.
+fn function_name(gcx: Gcx<'_>, id: hir::FunctionId) -> String {
+    let f = gcx.hir.function(id);
+    let mut name = gcx.hir.source(f.source).file.name.display().to_string();
+    name.push(':');
+    if let Some(contract) = f.contract {
+        write!(name, "{}.", gcx.item_name(contract)).unwrap();
+    }
+    name.push_str(&gcx.function_display_name(id));
+    name
+}
+
+/// Returns the inheritance graph in Graphviz DOT format, with edges from contracts to their direct
+/// bases.
+fn inheritance_dot(gcx: Gcx<'_>) -> String {
+    let name = |id| dot_id(&gcx.contract_fully_qualified_name(id).to_string());
+    let mut out = String::from("digraph inheritance {\n");
+    for id in gcx.hir.contract_ids() {
+        let c = gcx.hir.contract(id);
+        let shape = match c.kind {
+            hir::ContractKind::Contract => "box",
+            hir::ContractKind::AbstractContract => "box, style=dashed",
+            hir::ContractKind::Interface => "ellipse",
+            hir::ContractKind::Library => "component",
+        };
+        writeln!(out, "    {} [label={}, shape={shape}];", name(id), dot_id(c.name.as_str()))
+            .unwrap();
+    }
+    for (id, base) in gcx.inheritance_graph() {
+        writeln!(out, "    {} -> {};", name(id), name(base)).unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Returns the call graph in Graphviz DOT format, with functions grouped by contract.
+///
+/// External calls are dashed, library calls are blue, and modifier invocations are dotted.
+fn call_graph_dot(gcx: Gcx<'_>, calls: &[Call]) -> String {
+    let mut functions = gcx
+        .hir
+        .function_ids()
+        .filter(|&id| gcx.hir.function(id).body.is_some())
+        .chain(calls.iter().flat_map(|call| [call.caller, call.callee]))
+        .collect::<Vec<_>>();
+    functions.sort_unstable_by_key(|&id| (gcx.hir.function(id).contract, id));
+    functions.dedup();
+
+    let name = |id| dot_id(&function_name(gcx, id));
+    let label = |id| dot_id(&gcx.function_display_name(id));
+    let mut out = String::from("digraph calls {\n");
+    let mut current_contract = None;
+    for &id in &functions {
+        let contract = gcx.hir.function(id).contract;
+        if contract != current_contract {
+            if current_contract.is_some() {
+                out.push_str("    }\n");
+            }
+            if let Some(c) = contract {
+                let cluster = format!("cluster_{}", gcx.contract_fully_qualified_name(c));
+                writeln!(out, "    subgraph {} {{", dot_id(&cluster)).unwrap();
+                writeln!(out, "        label={};", dot_id(gcx.item_name(c).as_str())).unwrap();
+            }
+            current_contract = contract;
+        }
+        let indent = if contract.is_some() { "        " } else { "    " };
+        writeln!(out, "{indent}{} [label={}];", name(id), label(id)).unwrap();
+    }
+    if current_contract.is_some() {
+        out.push_str("    }\n");
+    }
+    for call in calls {
+        write!(out, "    {} -> {}", name(call.caller), name(call.callee)).unwrap();
+        match call.kind {
+            CallKind::Internal => {}
+            CallKind::External => out.push_str(" [style=dashed]"),
+            CallKind::Library => out.push_str(" [color=blue]"),
+            CallKind::Modifier => out.push_str(" [style=dotted]"),
+        }
+        out.push_str(";\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Returns a quoted DOT identifier.
+fn dot_id(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_dot(path: Option<&Path>, dot: &str) -> io::Result<()> {
+    let mut writer = out_writer(path)?;
+    writer.write_all(dot.as_bytes())?;
+    writer.flush()
+}
+
+fn selectors(gcx: Gcx<'_>, id: hir::ContractId) -> Selectors {
+    let signature = |id: hir::ItemId| gcx.item_signature(id).to_string();
+    let mut selectors = Selectors::default();
+    for f in gcx.interface_functions(id) {
+        selectors.functions.insert(signature(f.id.into()), hex::encode(f.selector));
+    }
+    for event in gcx.interface_events(id) {
+        if !gcx.hir.event(event).anonymous {
+            selectors
+                .events
+                .insert(signature(event.into()), hex::encode(gcx.event_selector(event)));
+        }
+    }
+    for error in gcx.interface_errors(id) {
+        selectors.errors.insert(signature(error.into()), hex::encode(gcx.function_selector(error)));
+    }
+    if gcx.hir.contract(id).kind.is_interface() {
+        selectors.interface_id = Some(hex::encode(gcx.interface_id(id)));
+    }
+    selectors
+}
+
+fn metadata(gcx: Gcx<'_>, id: hir::ContractId) -> String {
+    let contract = gcx.hir.contract(id);
+    let source_name = |id: hir::SourceId| gcx.hir.source(id).file.name.display().to_string();
+
+    // Sources reachable through imports.
+    let mut sources = BTreeMap::new();
+    let mut seen = FxHashSet::default();
+    let mut stack = vec![contract.source];
+    while let Some(source_id) = stack.pop() {
+        if !seen.insert(source_id) {
+            continue;
+        }
+        let source = gcx.hir.source(source_id);
+        stack.extend(source.imports.iter().map(|&(_, import)| import));
+        sources.insert(
+            source_name(source_id),
+            MetadataSource {
+                keccak256: keccak256(source.file.src.as_str()).to_string(),
+                license: source.license().map(Into::into),
+            },
+        );
+    }
+
+    let metadata = Metadata {
+        compiler: MetadataCompiler { version: solar_interface::VERSION },
+        language: match gcx.sess.language {
+            Language::Solidity => "Solidity",
+            Language::Yul => "Yul",
+        },
+        output: MetadataOutput {
+            abi: gcx.contract_abi(id),
+            devdoc: gcx.contract_devdoc(id),
+            userdoc: gcx.contract_userdoc(id),
+        },
+        settings: MetadataSettings {
+            compilation_target: BTreeMap::from([(
+                source_name(contract.source),
+                contract.name.to_string(),
+            )]),
+            evm_version: gcx.sess.evm_version.to_str(),
+            libraries: BTreeMap::new(),
+            remappings: gcx
+                .sess
+                .import_maps
+                .iter()
+                .map(|(map, path)| format!("{}={}", map.display(), path.display()))
+                .collect(),
+        },
+        sources,
+        version: 1,
+    };
+    // Like solc, serialize compactly with sorted keys by going through `serde_json::Value`.
+    serde_json::to_value(&metadata).map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn eip712_type(gcx: Gcx<'_>, id: hir::StructId) -> Option<Eip712Type> {
+    let encode_type = gcx.eip712_encode_type(id)?;
+    let typehash = gcx.eip712_typehash(id)?.to_string();
+    Some(Eip712Type { encode_type, typehash })
+}
+
+fn write_json<T: Serialize>(path: Option<&Path>, value: &T, pretty: bool) -> io::Result<()> {
+    let mut writer = out_writer(path)?;
+    to_json(&mut writer, value, pretty)?;
+    writer.flush()
+}
+
+fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    let mut writer = out_writer(Some(path))?;
+    writer.write_all(contents.as_bytes())?;
+    writer.flush()
 }
 
 fn out_writer(path: Option<&Path>) -> io::Result<impl io::Write> {
     let out: Box<dyn io::Write> = if let Some(path) = path {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
         Box::new(std::fs::File::create(path)?)
     } else {
         Box::new(std::io::stdout())
@@ -77,3 +702,130 @@ fn to_json<W: io::Write, T: Serialize>(
         serde_json::to_writer(writer, value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{add_test_files, enter_test_session, ParsingContext};
+    use solar_interface::Session;
+
+    const FILES: &[(&str, &str)] = &[
+        (
+            "src/Counter.sol",
+            "contract Counter {
+                uint256 public count;
+
+                /// @notice Increments the counter.
+                function increment() public {
+                    count++;
+                }
+            }",
+        ),
+        ("src/Token.sol", "contract Token { function transfer(address to) external {} }"),
+    ];
+
+    /// Emits `outputs` for [`FILES`] with the given layout, and returns the written files by path
+    /// relative to the output directory.
+    fn emit_files(layout: OutputLayout, outputs: &[CompilerOutput]) -> BTreeMap<String, String> {
+        let dir = tempfile::tempdir().unwrap();
+        let sess = Session::builder()
+            .with_test_emitter()
+            .emit(outputs.iter().copied().collect())
+            .out_dir(dir.path().to_path_buf())
+            .output_layout(layout)
+            .build();
+        enter_test_session(sess, |sess| {
+            let mut pcx = ParsingContext::new(sess);
+            add_test_files(&mut pcx, FILES);
+            crate::parse_and_resolve(pcx).unwrap();
+        });
+
+        let mut files = BTreeMap::new();
+        let mut stack = vec![dir.path().to_path_buf()];
+        while let Some(path) = stack.pop() {
+            for entry in std::fs::read_dir(path).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    stack.push(path);
+                } else {
+                    let name = path.strip_prefix(dir.path()).unwrap().to_str().unwrap();
+                    files.insert(name.replace('\\', "/"), std::fs::read_to_string(&path).unwrap());
+                }
+            }
+        }
+        files
+    }
+
+    fn json(s: &str) -> serde_json::Value {
+        serde_json::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn split_layout() {
+        let outputs = [
+            CompilerOutput::Abi,
+            CompilerOutput::Hashes,
+            CompilerOutput::Interface,
+            CompilerOutput::Metadata,
+        ];
+        let files = emit_files(OutputLayout::Split, &outputs);
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            [
+                "Counter.abi",
+                "Counter.signatures",
+                "Counter_meta.json",
+                "ICounter.sol",
+                "IToken.sol",
+                "Token.abi",
+                "Token.signatures",
+                "Token_meta.json",
+            ]
+        );
+
+        assert_eq!(files["Counter.signatures"], "06661abd: count()\nd09de08a: increment()\n");
+        assert_eq!(files["Token.signatures"], "1a695230: transfer(address)\n");
+        let abi = json(&files["Counter.abi"]);
+        let names = abi.as_array().unwrap().iter().map(|item| &item["name"]).collect::<Vec<_>>();
+        assert_eq!(names, ["count", "increment"]);
+        assert!(
+            files["ICounter.sol"].contains("interface ICounter {"),
+            "{}",
+            files["ICounter.sol"]
+        );
+        assert!(files["ICounter.sol"].contains("function increment() external;"));
+        let metadata = json(&files["Counter_meta.json"]);
+        assert_eq!(metadata["settings"]["compilationTarget"]["src/Counter.sol"], "Counter");
+        assert_eq!(
+            metadata["output"]["userdoc"]["methods"]["increment()"]["notice"],
+            "Increments the counter."
+        );
+    }
+
+    #[test]
+    fn artifacts_layout() {
+        let outputs = [CompilerOutput::Abi, CompilerOutput::Hashes, CompilerOutput::Metadata];
+        let files = emit_files(OutputLayout::Artifacts, &outputs);
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["Counter.sol/Counter.json", "Token.sol/Token.json"]
+        );
+
+        let artifact = json(&files["Counter.sol/Counter.json"]);
+        let keys = artifact.as_object().unwrap().keys().collect::<Vec<_>>();
+        assert_eq!(keys, ["abi", "metadata", "methodIdentifiers", "rawMetadata"]);
+        assert_eq!(artifact["abi"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            artifact["methodIdentifiers"],
+            json(r#"{"count()": "06661abd", "increment()": "d09de08a"}"#)
+        );
+        assert_eq!(json(artifact["rawMetadata"].as_str().unwrap()), artifact["metadata"]);
+        assert_eq!(
+            artifact["metadata"]["settings"]["compilationTarget"]["src/Counter.sol"],
+            "Counter"
+        );
+
+        let artifact = json(&files["Token.sol/Token.json"]);
+        assert_eq!(artifact["methodIdentifiers"], json(r#"{"transfer(address)": "1a695230"}"#));
+    }
+}
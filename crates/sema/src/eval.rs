@@ -1,25 +1,35 @@
 use crate::{hir, ty::Gcx};
 use alloy_primitives::U256;
-use solar_ast::ast::LitKind;
-use solar_interface::{diagnostics::ErrorGuaranteed, Span};
-use std::fmt;
+use num_bigint::{BigInt, Sign};
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use solar_ast::ast::{ElementaryType, LitKind, TypeSize};
+use solar_interface::{diagnostics::ErrorGuaranteed, sym, Ident, Span};
+use std::{borrow::Cow, fmt, sync::Arc};
 
 const RECURSION_LIMIT: usize = 64;
 
-// TODO: `convertType` for truncating and extending correctly: https://github.com/ethereum/solidity/blob/de1a017ccb935d149ed6bcbdb730d89883f8ce02/libsolidity/analysis/ConstantEvaluator.cpp#L234
+/// The maximum number of bits in the numerator or the denominator of an untyped rational value.
+///
+/// Same as solc: <https://github.com/ethereum/solidity/blob/de1a017ccb935d149ed6bcbdb730d89883f8ce02/libsolidity/ast/Types.cpp#L1012>
+const MAX_RATIONAL_BITS: u64 = 4096;
 
 /// Evaluates simple constants.
 ///
-/// This only supports basic arithmetic and logical operations, and does not support more complex
-/// operations like function calls or memory allocation.
+/// Number literals are evaluated with arbitrary precision rational arithmetic, like in solc, and
+/// are converted to their target type only when they are used. Values of integer, address, boolean
+/// and fixed-bytes types are checked against the range of their type after every operation.
+///
+/// This does not support more complex operations like function calls or memory allocation, and is
+/// only supposed to be used for array sizes and other simple constants.
 ///
-/// This is only supposed to be used for array sizes and other simple constants.
+/// Reference: <https://github.com/ethereum/solidity/blob/de1a017ccb935d149ed6bcbdb730d89883f8ce02/libsolidity/analysis/ConstantEvaluator.cpp>
 pub struct ConstantEvaluator<'gcx> {
     pub gcx: Gcx<'gcx>,
     depth: usize,
 }
 
-type EvalResult<'gcx> = Result<IntScalar, EvalError>;
+type EvalResult<T = ConstValue> = Result<T, EvalError>;
 
 impl<'gcx> ConstantEvaluator<'gcx> {
     /// Creates a new constant evaluator.
@@ -27,13 +37,41 @@ impl<'gcx> ConstantEvaluator<'gcx> {
         Self { gcx, depth: 0 }
     }
 
-    /// Evaluates the given expression, emitting an error diagnostic if it fails.
+    /// Evaluates the given expression as a `uint256`, emitting an error diagnostic if it fails.
     pub fn eval(&mut self, expr: &hir::Expr<'_>) -> Result<IntScalar, ErrorGuaranteed> {
         self.try_eval(expr).map_err(|err| self.emit_eval_error(expr, err))
     }
 
+    /// Evaluates the given expression as a `uint256`, returning an error if it fails.
+    pub fn try_eval(&mut self, expr: &hir::Expr<'_>) -> EvalResult<IntScalar> {
+        let value = self.try_eval_value(expr)?;
+        value.to_int_scalar().map_err(|kind| kind.spanned(expr.span))
+    }
+
+    /// Evaluates the given expression and implicitly converts it to the given type, emitting an
+    /// error diagnostic if it fails.
+    pub fn eval_as(
+        &mut self,
+        expr: &hir::Expr<'_>,
+        ty: ElementaryType,
+    ) -> Result<ConstValue, ErrorGuaranteed> {
+        self.try_eval_as(expr, ty).map_err(|err| self.emit_eval_error(expr, err))
+    }
+
+    /// Evaluates the given expression and implicitly converts it to the given type, returning an
+    /// error if it fails.
+    pub fn try_eval_as(&mut self, expr: &hir::Expr<'_>, ty: ElementaryType) -> EvalResult {
+        let value = self.try_eval_value(expr)?;
+        value.convert(ty, false).map_err(|kind| kind.spanned(expr.span))
+    }
+
+    /// Evaluates the given expression, emitting an error diagnostic if it fails.
+    pub fn eval_value(&mut self, expr: &hir::Expr<'_>) -> Result<ConstValue, ErrorGuaranteed> {
+        self.try_eval_value(expr).map_err(|err| self.emit_eval_error(expr, err))
+    }
+
     /// Evaluates the given expression, returning an error if it fails.
-    pub fn try_eval(&mut self, expr: &hir::Expr<'_>) -> EvalResult<'gcx> {
+    pub fn try_eval_value(&mut self, expr: &hir::Expr<'_>) -> EvalResult {
         self.depth += 1;
         if self.depth > RECURSION_LIMIT {
             return Err(EE::RecursionLimitReached.spanned(expr.span));
@@ -59,41 +97,51 @@ impl<'gcx> ConstantEvaluator<'gcx> {
         }
     }
 
-    fn eval_expr(&mut self, expr: &hir::Expr<'_>) -> EvalResult<'gcx> {
+    fn eval_expr(&mut self, expr: &hir::Expr<'_>) -> EvalResult {
         let expr = expr.peel_parens();
         match expr.kind {
             // hir::ExprKind::Array(_) => todo!(),
             // hir::ExprKind::Assign(_, _, _) => todo!(),
             hir::ExprKind::Binary(l, bin_op, r) => {
-                let l = self.try_eval(l)?;
-                let r = self.try_eval(r)?;
+                let l = self.try_eval_value(l)?;
+                let r = self.try_eval_value(r)?;
                 l.binop(&r, bin_op.kind).map_err(Into::into)
             }
-            // hir::ExprKind::Call(_, _) => todo!(),
+            hir::ExprKind::Call(callee, ref args) => {
+                let hir::CallArgs::Unnamed([arg]) = *args else {
+                    return Err(EE::UnsupportedExpr.into());
+                };
+                self.eval_call(callee, arg)
+            }
             // hir::ExprKind::CallOptions(_, _) => todo!(),
             // hir::ExprKind::Delete(_) => todo!(),
-            hir::ExprKind::Ident(&[hir::Res::Item(hir::ItemId::Variable(v))]) => {
-                let v = self.gcx.hir.variable(v);
-                if v.mutability != Some(hir::VarMut::Constant) {
-                    return Err(EE::NonConstantVar.into());
+            hir::ExprKind::Ident(res) => match constant_var_res(self.gcx, res) {
+                Some(v) => self.eval_var(v),
+                None if res.iter().any(|res| matches!(res, hir::Res::Item(_))) => {
+                    Err(EE::NonConstantVar.into())
                 }
-                self.try_eval(v.initializer.expect("constant variable has no initializer"))
-            }
+                None => Err(EE::UnsupportedExpr.into()),
+            },
             // hir::ExprKind::Index(_, _) => todo!(),
             // hir::ExprKind::Slice(_, _, _) => todo!(),
             hir::ExprKind::Lit(lit) => self.eval_lit(lit),
-            // hir::ExprKind::Member(_, ident) => todo!(),
+            hir::ExprKind::Member(base, member) => self.eval_member(base, member),
             // hir::ExprKind::New(_) => todo!(),
             // hir::ExprKind::Payable(_) => todo!(),
             hir::ExprKind::Ternary(cond, t, f) => {
-                let cond = self.try_eval(cond)?;
-                Ok(if cond.to_bool() { self.try_eval(t)? } else { self.try_eval(f)? })
+                let cond = self.try_eval_value(cond)?;
+                let cond = cond.to_bool().ok_or(EE::InvalidOperands)?;
+                if cond {
+                    self.try_eval_value(t)
+                } else {
+                    self.try_eval_value(f)
+                }
             }
             // hir::ExprKind::Tuple(_) => todo!(),
             // hir::ExprKind::TypeCall(_) => todo!(),
             // hir::ExprKind::Type(_) => todo!(),
             hir::ExprKind::Unary(un_op, v) => {
-                let v = self.try_eval(v)?;
+                let v = self.try_eval_value(v)?;
                 v.unop(un_op.kind).map_err(Into::into)
             }
             hir::ExprKind::Err(guar) => Err(EE::AlreadyEmitted(guar).into()),
@@ -101,102 +149,573 @@ impl<'gcx> ConstantEvaluator<'gcx> {
         }
     }
 
-    fn eval_lit(&mut self, lit: &hir::Lit) -> EvalResult<'gcx> {
-        match lit.kind {
-            // LitKind::Str(str_kind, arc) => todo!(),
-            LitKind::Number(ref big_int) => {
-                let (_, bytes) = big_int.to_bytes_be();
-                if bytes.len() > 32 {
-                    return Err(EE::IntTooBig.into());
+    /// Evaluates a constant variable, implicitly converting its value to the declared type.
+    fn eval_var(&mut self, id: hir::VariableId) -> EvalResult {
+        let v = self.gcx.hir.variable(id);
+        let init = v.initializer.expect("constant variable has no initializer");
+        let value = self.try_eval_value(init)?;
+        match v.ty.kind {
+            hir::TypeKind::Elementary(ty) => {
+                value.convert(ty, false).map_err(|kind| kind.spanned(init.span))
+            }
+            _ => Ok(value),
+        }
+    }
+
+    /// Evaluates a single-argument call, which can be either a type conversion like `uint8(x)`, or
+    /// a UDVT `wrap`/`unwrap`.
+    fn eval_call(&mut self, callee: &hir::Expr<'_>, arg: &hir::Expr<'_>) -> EvalResult {
+        match callee.peel_parens().kind {
+            hir::ExprKind::Type(hir::Type { kind: hir::TypeKind::Elementary(ty), .. }) => {
+                let value = self.try_eval_value(arg)?;
+                value.convert(ty, true).map_err(Into::into)
+            }
+            hir::ExprKind::Member(base, member)
+                if matches!(member.name, sym::wrap | sym::unwrap) =>
+            {
+                let hir::ExprKind::Ident(&[hir::Res::Item(hir::ItemId::Udvt(id))]) =
+                    base.peel_parens().kind
+                else {
+                    return Err(EE::UnsupportedExpr.into());
+                };
+                let value = self.try_eval_value(arg)?;
+                match self.gcx.hir.udvt(id).ty.kind {
+                    hir::TypeKind::Elementary(ty) if member.name == sym::wrap => {
+                        value.convert(ty, false).map_err(Into::into)
+                    }
+                    _ => Ok(value),
                 }
-                Ok(IntScalar::from_be_bytes(&bytes))
             }
-            // LitKind::Rational(ratio) => todo!(),
-            LitKind::Address(address) => Ok(IntScalar::from_be_bytes(address.as_slice())),
-            LitKind::Bool(bool) => Ok(IntScalar::from_be_bytes(&[bool as u8])),
+            _ => Err(EE::UnsupportedExpr.into()),
+        }
+    }
+
+    /// Evaluates `type(T).min`, `type(T).max`, and constants accessed through a contract or an
+    /// import namespace, like `Lib.CONSTANT`.
+    fn eval_member(&mut self, base: &hir::Expr<'_>, member: Ident) -> EvalResult {
+        let items = match base.peel_parens().kind {
+            hir::ExprKind::TypeCall(hir::Type { kind: hir::TypeKind::Elementary(ty), .. }) => {
+                let (min, max) = int_range(ty).ok_or(EE::UnsupportedExpr)?;
+                return match member.name {
+                    sym::min if is_integer(ty) => Ok(ConstValue::Int(min, ty)),
+                    sym::max if is_integer(ty) => Ok(ConstValue::Int(max, ty)),
+                    _ => Err(EE::UnsupportedExpr.into()),
+                };
+            }
+            hir::ExprKind::Ident(&[hir::Res::Item(hir::ItemId::Contract(id))]) => {
+                self.gcx.hir.contract(id).items
+            }
+            hir::ExprKind::Ident(&[hir::Res::Namespace(id)]) => self.gcx.hir.source(id).items,
+            _ => return Err(EE::UnsupportedExpr.into()),
+        };
+        let var = items
+            .iter()
+            .filter_map(hir::ItemId::as_variable)
+            .find(|&v| self.gcx.hir.variable(v).name.is_some_and(|name| name.name == member.name))
+            .ok_or(EE::UnsupportedExpr)?;
+        if self.gcx.hir.variable(var).mutability != Some(hir::VarMut::Constant) {
+            return Err(EE::NonConstantVar.into());
+        }
+        self.eval_var(var)
+    }
+
+    fn eval_lit(&mut self, lit: &hir::Lit) -> EvalResult {
+        match lit.kind {
+            LitKind::Str(_, ref s) => Ok(ConstValue::Str(s.clone())),
+            LitKind::Number(ref int) => Ok(ConstValue::rational(int.clone().into())?),
+            LitKind::Rational(ref ratio) => Ok(ConstValue::rational(ratio.clone())?),
+            LitKind::Address(address) => Ok(ConstValue::Int(
+                BigInt::from_bytes_be(Sign::Plus, address.as_slice()),
+                ElementaryType::Address(false),
+            )),
+            LitKind::Bool(bool) => Ok(ConstValue::from_bool(bool)),
             LitKind::Err(guar) => Err(EE::AlreadyEmitted(guar).into()),
-            _ => Err(EE::UnsupportedLiteral.into()),
         }
     }
 }
 
-pub struct IntScalar {
-    pub data: U256,
+/// Returns the constant variable that the given identifier resolutions refer to, if any.
+///
+/// Public constants also resolve to their getter function, which is ignored here.
+fn constant_var_res(gcx: Gcx<'_>, res: &[hir::Res]) -> Option<hir::VariableId> {
+    let mut vars = res.iter().filter_map(|res| match *res {
+        hir::Res::Item(hir::ItemId::Variable(v)) => Some(v),
+        _ => None,
+    });
+    let var = vars.next()?;
+    let is_getter = |res: &hir::Res| matches!(*res, hir::Res::Item(hir::ItemId::Function(f)) if gcx.hir.variable(var).getter == Some(f));
+    if vars.next().is_some()
+        || !res
+            .iter()
+            .all(|res| matches!(res, hir::Res::Item(hir::ItemId::Variable(_))) || is_getter(res))
+    {
+        return None;
+    }
+    (gcx.hir.variable(var).mutability == Some(hir::VarMut::Constant)).then_some(var)
 }
 
-impl IntScalar {
-    pub fn new(data: U256) -> Self {
-        Self { data }
+/// An evaluated constant value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstValue {
+    /// An untyped rational number, such as a number literal or the result of an operation on
+    /// number literals.
+    ///
+    /// Corresponds to `RationalNumberType` in solc.
+    Rational(BigRational),
+    /// A value of the given boolean, integer, address, or fixed-bytes type.
+    ///
+    /// The value is always in the range of the type. Fixed-bytes values are stored as big-endian
+    /// unsigned integers.
+    Int(BigInt, ElementaryType),
+    /// A string literal.
+    Str(Arc<[u8]>),
+}
+
+impl ConstValue {
+    /// Creates a new untyped rational value, checking that it is not too large.
+    pub fn rational(value: BigRational) -> Result<Self, EE> {
+        if value.numer().bits() > MAX_RATIONAL_BITS || value.denom().bits() > MAX_RATIONAL_BITS {
+            return Err(EE::ArithmeticOverflow);
+        }
+        Ok(Self::Rational(value))
     }
 
-    /// Creates a new integer value from a boolean.
+    /// Creates a new boolean value.
     pub fn from_bool(value: bool) -> Self {
-        Self { data: U256::from(value as u8) }
+        Self::Int(BigInt::from(value as u8), ElementaryType::Bool)
     }
 
-    /// Creates a new integer value from big-endian bytes.
-    ///
-    /// # Panics
-    ///
-    /// Panics if `bytes` is empty or has a length greater than 32.
-    pub fn from_be_bytes(bytes: &[u8]) -> Self {
-        Self { data: U256::from_be_slice(bytes) }
+    /// Returns the boolean value, if this is a boolean.
+    pub fn to_bool(&self) -> Option<bool> {
+        match self {
+            Self::Int(value, ElementaryType::Bool) => Some(!value.is_zero()),
+            _ => None,
+        }
     }
 
-    /// Converts the integer value to a boolean.
-    pub fn to_bool(&self) -> bool {
-        !self.data.is_zero()
+    /// Returns the integer value, if this is an integer number.
+    pub fn to_integer(&self) -> Option<BigInt> {
+        match self {
+            Self::Rational(value) => value.is_integer().then(|| value.to_integer()),
+            Self::Int(value, _) => Some(value.clone()),
+            Self::Str(_) => None,
+        }
+    }
+
+    /// Returns the type of the value, or `None` if it is an untyped rational or a string literal.
+    pub fn ty(&self) -> Option<ElementaryType> {
+        match *self {
+            Self::Int(_, ty) => Some(ty),
+            Self::Rational(_) | Self::Str(_) => None,
+        }
+    }
+
+    /// Converts the value to an unsigned 256-bit integer.
+    pub fn to_int_scalar(&self) -> Result<IntScalar, EE> {
+        let Self::Int(value, _) = self.convert(ElementaryType::UInt(TypeSize::ZERO), false)? else {
+            unreachable!()
+        };
+        Ok(IntScalar::from_be_bytes(&value.to_bytes_be().1))
+    }
+
+    /// Converts the value to the given type.
+    ///
+    /// Explicit conversions truncate or extend typed integers, and reinterpret the bytes of
+    /// fixed-bytes, address, and integer values, like `uint8(x)` or `bytes32(uint256(x))` do.
+    /// Implicit conversions and conversions of literals only succeed if the value fits in the
+    /// target type.
+    pub fn convert(&self, to: ElementaryType, explicit: bool) -> Result<Self, EE> {
+        use ElementaryType::*;
+
+        let int = |value: BigInt| {
+            if fits(&value, to) {
+                Ok(Self::Int(value, to))
+            } else {
+                Err(EE::OutOfRange(to))
+            }
+        };
+        match (self, to) {
+            (Self::Rational(value), UInt(_) | Int(_) | Address(_) | FixedBytes(_)) => {
+                if !value.is_integer() {
+                    return Err(EE::NotAnInteger);
+                }
+                int(value.to_integer())
+            }
+            (Self::Rational(_), _) => Err(EE::InvalidConversion(to)),
+
+            (Self::Str(s), FixedBytes(size)) => {
+                let size = size.bytes() as usize;
+                if s.len() > size {
+                    return Err(EE::OutOfRange(to));
+                }
+                let mut bytes = [0u8; 32];
+                bytes[..s.len()].copy_from_slice(s);
+                int(BigInt::from_bytes_be(Sign::Plus, &bytes[..size]))
+            }
+            (Self::Str(_), String | Bytes) => Ok(self.clone()),
+            (Self::Str(_), _) => Err(EE::InvalidConversion(to)),
+
+            (&Self::Int(ref value, from), _) => {
+                if from == to || (!explicit && matches!((from, to), (Address(_), Address(_)))) {
+                    return Ok(Self::Int(value.clone(), to));
+                }
+                match (from, to) {
+                    (Bool, _) | (_, Bool) => Err(EE::InvalidConversion(to)),
+                    (FixedBytes(a), FixedBytes(b)) => {
+                        if !explicit && b < a {
+                            return Err(EE::InvalidConversion(to));
+                        }
+                        let (a, b) = (a.bytes() as usize * 8, b.bytes() as usize * 8);
+                        int(if a > b { value >> (a - b) } else { value << (b - a) })
+                    }
+                    _ if explicit => int(truncate(value, to)),
+                    (UInt(_) | Int(_), UInt(_) | Int(_)) => int(value.clone()),
+                    _ => Err(EE::InvalidConversion(to)),
+                }
+            }
+        }
     }
 
     /// Applies the given unary operation to this value.
     pub fn unop(&self, op: hir::UnOpKind) -> Result<Self, EE> {
-        Ok(match op {
+        use ElementaryType::*;
+
+        match op {
             hir::UnOpKind::PreInc
             | hir::UnOpKind::PreDec
             | hir::UnOpKind::PostInc
-            | hir::UnOpKind::PostDec => return Err(EE::UnsupportedUnaryOp),
-            hir::UnOpKind::Not | hir::UnOpKind::BitNot => Self::new(!self.data),
-            hir::UnOpKind::Neg => Self::new(self.data.wrapping_neg()),
-        })
+            | hir::UnOpKind::PostDec => Err(EE::UnsupportedUnaryOp),
+            hir::UnOpKind::Not => match self.to_bool() {
+                Some(value) => Ok(Self::from_bool(!value)),
+                None => Err(EE::InvalidOperands),
+            },
+            hir::UnOpKind::BitNot => match self {
+                Self::Rational(_) => Self::rational((!self.to_integer_or_err()?).into()),
+                &Self::Int(ref value, ty @ (UInt(_) | Int(_) | FixedBytes(_))) => {
+                    Ok(Self::Int(truncate(&!value, ty), ty))
+                }
+                _ => Err(EE::InvalidOperands),
+            },
+            hir::UnOpKind::Neg => match self {
+                Self::Rational(value) => Self::rational(-value),
+                &Self::Int(ref value, ty @ Int(_)) => Self::checked_int(-value, ty),
+                _ => Err(EE::InvalidOperands),
+            },
+        }
     }
 
     /// Applies the given binary operation to this value.
     pub fn binop(&self, r: &Self, op: hir::BinOpKind) -> Result<Self, EE> {
-        let l = self;
-        Ok(match op {
-            hir::BinOpKind::Lt => Self::from_bool(l.data < r.data),
-            hir::BinOpKind::Le => Self::from_bool(l.data <= r.data),
-            hir::BinOpKind::Gt => Self::from_bool(l.data > r.data),
-            hir::BinOpKind::Ge => Self::from_bool(l.data >= r.data),
-            hir::BinOpKind::Eq => Self::from_bool(l.data == r.data),
-            hir::BinOpKind::Ne => Self::from_bool(l.data != r.data),
-            hir::BinOpKind::Or | hir::BinOpKind::BitOr => Self::new(l.data | r.data),
-            hir::BinOpKind::And | hir::BinOpKind::BitAnd => Self::new(l.data & r.data),
-            hir::BinOpKind::BitXor => Self::new(l.data ^ r.data),
-            hir::BinOpKind::Shr => {
-                Self::new(l.data.wrapping_shr(r.data.try_into().unwrap_or(usize::MAX)))
+        use hir::BinOpKind::*;
+
+        match (self, r) {
+            (Self::Rational(l), Self::Rational(r)) => Self::rational_binop(l, r, op),
+            (Self::Str(_), _) | (_, Self::Str(_)) => Err(EE::InvalidOperands),
+            _ if matches!(op, Shl | Shr | Sar | Pow) => {
+                // The type of the result is the type of the left operand.
+                let ty = match (self.ty(), r.ty()) {
+                    (Some(ty), _) => ty,
+                    (None, Some(_)) => return Err(EE::UnsupportedBinaryOp),
+                    (None, None) => unreachable!(),
+                };
+                let l = self.to_integer_or_err()?;
+                let r = r.to_integer_or_err()?;
+                Self::int_binop(l, r, ty, op)
             }
-            hir::BinOpKind::Shl => {
-                Self::new(l.data.wrapping_shl(r.data.try_into().unwrap_or(usize::MAX)))
+            _ => {
+                let ty = match (self.ty(), r.ty()) {
+                    (Some(a), Some(b)) if a == b => a,
+                    (Some(ty), None) | (None, Some(ty)) => ty,
+                    // Convert to the larger of the two types, if possible.
+                    (Some(a), Some(b)) => {
+                        if self.convert(b, false).is_ok_and(|_| common_type(a, b) == Some(b)) {
+                            b
+                        } else if common_type(a, b) == Some(a) {
+                            a
+                        } else {
+                            return Err(EE::InvalidOperands);
+                        }
+                    }
+                    (None, None) => unreachable!(),
+                };
+                let l = self.convert(ty, false)?.to_integer_or_err()?;
+                let r = r.convert(ty, false)?.to_integer_or_err()?;
+                Self::int_binop(l, r, ty, op)
             }
-            hir::BinOpKind::Sar => {
-                Self::new(l.data.arithmetic_shr(r.data.try_into().unwrap_or(usize::MAX)))
+        }
+    }
+
+    fn rational_binop(l: &BigRational, r: &BigRational, op: hir::BinOpKind) -> Result<Self, EE> {
+        use hir::BinOpKind::*;
+
+        let integers = || -> Result<(BigInt, BigInt), EE> {
+            if l.is_integer() && r.is_integer() {
+                Ok((l.to_integer(), r.to_integer()))
+            } else {
+                Err(EE::NotAnInteger)
+            }
+        };
+        let value = match op {
+            Lt => return Ok(Self::from_bool(l < r)),
+            Le => return Ok(Self::from_bool(l <= r)),
+            Gt => return Ok(Self::from_bool(l > r)),
+            Ge => return Ok(Self::from_bool(l >= r)),
+            Eq => return Ok(Self::from_bool(l == r)),
+            Ne => return Ok(Self::from_bool(l != r)),
+            Or | And => return Err(EE::InvalidOperands),
+            BitOr => integers().map(|(l, r)| l | r)?.into(),
+            BitAnd => integers().map(|(l, r)| l & r)?.into(),
+            BitXor => integers().map(|(l, r)| l ^ r)?.into(),
+            Shl => {
+                let (l, r) = integers()?;
+                BigRational::from(l << shift_amount(&r, MAX_RATIONAL_BITS)?)
+            }
+            Shr | Sar => {
+                let (l, r) = integers()?;
+                if r.is_negative() {
+                    return Err(EE::InvalidOperands);
+                }
+                // Rounds towards negative infinity, like `sar`.
+                let shift =
+                    shift_amount(&r, MAX_RATIONAL_BITS).unwrap_or(MAX_RATIONAL_BITS as usize);
+                BigRational::from(l >> shift)
+            }
+            Add => l + r,
+            Sub => l - r,
+            Mul => l * r,
+            Div => {
+                if r.is_zero() {
+                    return Err(EE::DivisionByZero);
+                }
+                l / r
+            }
+            Rem => {
+                if r.is_zero() {
+                    return Err(EE::DivisionByZero);
+                }
+                l - (l / r).trunc() * r
+            }
+            Pow => {
+                if !r.is_integer() {
+                    return Err(EE::NotAnInteger);
+                }
+                let exp = r.to_integer();
+                if exp.is_zero() {
+                    BigRational::one()
+                } else if l.is_zero() || l.abs().is_one() {
+                    if exp.is_negative() && l.is_zero() {
+                        return Err(EE::DivisionByZero);
+                    }
+                    let odd = (&exp % 2u8) != BigInt::zero();
+                    if l.is_negative() && !odd {
+                        -l.clone()
+                    } else {
+                        l.clone()
+                    }
+                } else {
+                    let bits = l.numer().bits().max(l.denom().bits());
+                    let exp = exp
+                        .to_i32()
+                        .filter(|exp| exp.unsigned_abs() as u64 * (bits - 1) <= MAX_RATIONAL_BITS)
+                        .ok_or(EE::ArithmeticOverflow)?;
+                    l.pow(exp)
+                }
             }
-            hir::BinOpKind::Add => {
-                Self::new(l.data.checked_add(r.data).ok_or(EE::ArithmeticOverflow)?)
+        };
+        Self::rational(value)
+    }
+
+    fn int_binop(l: BigInt, r: BigInt, ty: ElementaryType, op: hir::BinOpKind) -> Result<Self, EE> {
+        use hir::BinOpKind::*;
+        use ElementaryType::{Bool, FixedBytes};
+
+        let is_int = is_integer(ty);
+        let value = match op {
+            Lt => return Ok(Self::from_bool(l < r)),
+            Le => return Ok(Self::from_bool(l <= r)),
+            Gt => return Ok(Self::from_bool(l > r)),
+            Ge => return Ok(Self::from_bool(l >= r)),
+            Eq => return Ok(Self::from_bool(l == r)),
+            Ne => return Ok(Self::from_bool(l != r)),
+            Or | And if ty == Bool => {
+                let value = if op == Or {
+                    !l.is_zero() || !r.is_zero()
+                } else {
+                    !l.is_zero() && !r.is_zero()
+                };
+                return Ok(Self::from_bool(value));
             }
-            hir::BinOpKind::Sub => {
-                Self::new(l.data.checked_sub(r.data).ok_or(EE::ArithmeticOverflow)?)
+            BitOr | BitAnd | BitXor if is_int || matches!(ty, FixedBytes(_)) => {
+                let value = match op {
+                    BitOr => l | r,
+                    BitAnd => l & r,
+                    _ => l ^ r,
+                };
+                return Ok(Self::Int(truncate(&value, ty), ty));
             }
-            hir::BinOpKind::Pow => {
-                Self::new(l.data.checked_pow(r.data).ok_or(EE::ArithmeticOverflow)?)
+            Shl | Shr | Sar if is_int || matches!(ty, FixedBytes(_)) => {
+                if r.is_negative() {
+                    return Err(EE::InvalidOperands);
+                }
+                let bits = int_bits(ty).unwrap() as usize;
+                let value = match op {
+                    Shl => l << shift_amount(&r, bits as u64).unwrap_or(bits),
+                    _ => l >> shift_amount(&r, bits as u64).unwrap_or(bits),
+                };
+                return Ok(Self::Int(truncate(&value, ty), ty));
             }
-            hir::BinOpKind::Mul => {
-                Self::new(l.data.checked_mul(r.data).ok_or(EE::ArithmeticOverflow)?)
+            Add if is_int => l + r,
+            Sub if is_int => l - r,
+            Mul if is_int => l * r,
+            Div | Rem if is_int => {
+                if r.is_zero() {
+                    return Err(EE::DivisionByZero);
+                }
+                // Rounds towards zero, like `sdiv` and `smod`.
+                if op == Div {
+                    l / r
+                } else {
+                    l % r
+                }
             }
-            hir::BinOpKind::Div => Self::new(l.data.checked_div(r.data).ok_or(EE::DivisionByZero)?),
-            hir::BinOpKind::Rem => Self::new(l.data.checked_rem(r.data).ok_or(EE::DivisionByZero)?),
-        })
+            Pow if is_int => {
+                if r.is_negative() {
+                    return Err(EE::InvalidOperands);
+                }
+                if l.is_zero() || l.abs().is_one() {
+                    if l.is_negative() && (&r % 2u8).is_zero() {
+                        -l
+                    } else if r.is_zero() {
+                        BigInt::one()
+                    } else {
+                        l
+                    }
+                } else {
+                    // `|l| >= 2`, so anything over 256 overflows.
+                    let exp = r.to_u32().filter(|&exp| exp <= 256).ok_or(EE::ArithmeticOverflow)?;
+                    num_traits::pow(l, exp as usize)
+                }
+            }
+            _ => return Err(EE::InvalidOperands),
+        };
+        Self::checked_int(value, ty)
+    }
+
+    fn checked_int(value: BigInt, ty: ElementaryType) -> Result<Self, EE> {
+        if fits(&value, ty) {
+            Ok(Self::Int(value, ty))
+        } else {
+            Err(EE::ArithmeticOverflow)
+        }
+    }
+
+    fn to_integer_or_err(&self) -> Result<BigInt, EE> {
+        match self {
+            Self::Str(_) => Err(EE::InvalidOperands),
+            _ => self.to_integer().ok_or(EE::NotAnInteger),
+        }
+    }
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rational(value) => value.fmt(f),
+            Self::Int(value, ElementaryType::Bool) => (!value.is_zero()).fmt(f),
+            Self::Int(value, ElementaryType::Address(_) | ElementaryType::FixedBytes(_)) => {
+                write!(f, "{value:#x}")
+            }
+            Self::Int(value, _) => value.fmt(f),
+            Self::Str(s) => write!(f, "{:?}", String::from_utf8_lossy(s)),
+        }
+    }
+}
+
+fn is_integer(ty: ElementaryType) -> bool {
+    matches!(ty, ElementaryType::Int(_) | ElementaryType::UInt(_))
+}
+
+/// Returns the number of bits of the given integer-like type.
+fn int_bits(ty: ElementaryType) -> Option<u16> {
+    match ty {
+        ElementaryType::Bool => Some(1),
+        ElementaryType::Address(_) => Some(160),
+        ElementaryType::Int(size) | ElementaryType::UInt(size) => Some(size.bits()),
+        ElementaryType::FixedBytes(size) => Some(size.bits()),
+        _ => None,
+    }
+}
+
+/// Returns the inclusive range of values of the given integer-like type.
+fn int_range(ty: ElementaryType) -> Option<(BigInt, BigInt)> {
+    let bits = int_bits(ty)?;
+    Some(if let ElementaryType::Int(_) = ty {
+        let half = BigInt::one() << (bits - 1);
+        (-half.clone(), half - 1)
+    } else {
+        (BigInt::zero(), (BigInt::one() << bits) - 1)
+    })
+}
+
+/// Returns `true` if the value is in the range of the given integer-like type.
+fn fits(value: &BigInt, ty: ElementaryType) -> bool {
+    int_range(ty).is_some_and(|(min, max)| min <= *value && *value <= max)
+}
+
+/// Truncates the value to the bits of the given type, interpreting it as two's complement for
+/// signed integer types.
+fn truncate(value: &BigInt, ty: ElementaryType) -> BigInt {
+    let bits = int_bits(ty).unwrap();
+    let modulus = BigInt::one() << bits;
+    let mut value = ((value % &modulus) + &modulus) % &modulus;
+    if matches!(ty, ElementaryType::Int(_)) && value.bits() == bits as u64 {
+        value -= modulus;
+    }
+    value
+}
+
+/// Returns the common type of two different integer types, if any.
+fn common_type(a: ElementaryType, b: ElementaryType) -> Option<ElementaryType> {
+    use ElementaryType::*;
+    match (a, b) {
+        (UInt(x), UInt(y)) | (Int(x), Int(y)) => Some(if x.bits() >= y.bits() { a } else { b }),
+        (UInt(x), Int(y)) if y.bits() > x.bits() => Some(b),
+        (Int(x), UInt(y)) if x.bits() > y.bits() => Some(a),
+        _ => None,
+    }
+}
+
+fn shift_amount(r: &BigInt, max: u64) -> Result<usize, EE> {
+    r.to_u64().filter(|&r| r <= max).map(|r| r as usize).ok_or(EE::ArithmeticOverflow)
+}
+
+/// A constant unsigned 256-bit integer.
+pub struct IntScalar {
+    pub data: U256,
+}
+
+impl IntScalar {
+    pub fn new(data: U256) -> Self {
+        Self { data }
+    }
+
+    /// Creates a new integer value from a boolean.
+    pub fn from_bool(value: bool) -> Self {
+        Self { data: U256::from(value as u8) }
+    }
+
+    /// Creates a new integer value from big-endian bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` has a length greater than 32.
+    pub fn from_be_bytes(bytes: &[u8]) -> Self {
+        Self { data: U256::from_be_slice(bytes) }
+    }
+
+    /// Converts the integer value to a boolean.
+    pub fn to_bool(&self) -> bool {
+        !self.data.is_zero()
     }
 }
 
@@ -204,10 +723,13 @@ impl IntScalar {
 pub enum EvalErrorKind {
     RecursionLimitReached,
     ArithmeticOverflow,
-    IntTooBig,
     DivisionByZero,
-    UnsupportedLiteral,
+    NotAnInteger,
+    OutOfRange(ElementaryType),
+    InvalidConversion(ElementaryType),
+    InvalidOperands,
     UnsupportedUnaryOp,
+    UnsupportedBinaryOp,
     UnsupportedExpr,
     NonConstantVar,
     AlreadyEmitted(ErrorGuaranteed),
@@ -219,21 +741,46 @@ impl EvalErrorKind {
         EvalError { kind: self, span }
     }
 
-    fn msg(&self) -> &'static str {
+    /// Returns `true` if the error is caused by an expression that the evaluator does not support,
+    /// rather than by an invalid value.
+    pub fn is_unsupported(&self) -> bool {
+        matches!(
+            self,
+            Self::RecursionLimitReached
+                | Self::UnsupportedUnaryOp
+                | Self::UnsupportedBinaryOp
+                | Self::UnsupportedExpr
+                | Self::NonConstantVar
+        )
+    }
+
+    fn msg(&self) -> Cow<'static, str> {
         match self {
-            Self::RecursionLimitReached => "recursion limit reached",
-            Self::ArithmeticOverflow => "arithmetic overflow",
-            Self::IntTooBig => "integer value is too big",
-            Self::DivisionByZero => "division by zero",
-            Self::UnsupportedLiteral => "unsupported literal",
-            Self::UnsupportedUnaryOp => "unsupported unary operation",
-            Self::UnsupportedExpr => "unsupported expression",
-            Self::NonConstantVar => "only constant variables are allowed",
-            Self::AlreadyEmitted(_) => "error already emitted",
+            Self::RecursionLimitReached => "recursion limit reached".into(),
+            Self::ArithmeticOverflow => "arithmetic overflow".into(),
+            Self::DivisionByZero => "division by zero".into(),
+            Self::NotAnInteger => "value is not an integer".into(),
+            Self::OutOfRange(ty) => format!("value does not fit in type `{}`", ty_str(*ty)).into(),
+            Self::InvalidConversion(ty) => {
+                format!("value cannot be converted to type `{}`", ty_str(*ty)).into()
+            }
+            Self::InvalidOperands => "invalid operand types".into(),
+            Self::UnsupportedUnaryOp => "unsupported unary operation".into(),
+            Self::UnsupportedBinaryOp => "unsupported binary operation".into(),
+            Self::UnsupportedExpr => "unsupported expression".into(),
+            Self::NonConstantVar => "only constant variables are allowed".into(),
+            Self::AlreadyEmitted(_) => "error already emitted".into(),
         }
     }
 }
 
+fn ty_str(ty: ElementaryType) -> Cow<'static, str> {
+    match ty {
+        ElementaryType::Address(true) => "address payable".into(),
+        ty => ty.to_abi_str(),
+    }
+}
+
 #[derive(Debug)]
 pub struct EvalError {
     pub span: Span,
@@ -0,0 +1,608 @@
+//! Source flattening.
+
+use crate::{
+    hir,
+    ty::{Gcx, GlobalCtxt},
+    ParsedSources,
+};
+use solar_ast::{
+    ast::{self, yul},
+    visit::Visit,
+};
+use solar_data_structures::{
+    map::{FxHashMap, FxHashSet, FxIndexSet},
+    trustme,
+};
+use solar_interface::{source_map::SourceFile, BytePos, Ident, Pos, Result, Session, Span, Symbol};
+use std::{fmt::Write, ops::Range};
+use thread_local::ThreadLocal;
+
+const SPDX_TAG: &str = "SPDX-License-Identifier:";
+
+/// Flattens the given sources into a single Solidity source.
+///
+/// `sources` must be sorted with [`ParsedSources::topo_sort`], so that every source comes after
+/// the sources that it imports.
+///
+/// The sources are concatenated in order, with:
+/// - all `import` directives removed;
+/// - colliding top-level names renamed to `<name>_<n>`, and references to them updated, including
+///   through import aliases and namespaces such as `import * as N from "..."`;
+/// - version pragmas merged into their intersection, and other pragmas deduplicated;
+/// - SPDX license identifiers merged into a single header.
+///
+/// References are resolved with the symbol resolver, including the references to constants in
+/// inline assembly. If a reference would be captured by a local declaration once aliases and
+/// namespaces are removed, the referenced item is given a new name instead.
+pub fn flatten(sess: &Session, sources: &ParsedSources<'_>) -> Result<String> {
+    sess.dcx.has_errors()?;
+    if let Some(source) = sources.iter().find(|source| source.ast.is_none()) {
+        let msg = format!("cannot flatten {}", source.file.name.display());
+        return Err(sess.dcx.err(msg).note("only Solidity sources can be flattened").emit());
+    }
+
+    let hir_arena = ThreadLocal::<hir::Arena>::new();
+    let (hir, symbol_resolver) = crate::lower(sess, sources, hir_arena.get_or_default())?;
+    sess.dcx.has_errors()?;
+    let global_context = GlobalCtxt::new(sess, &hir_arena, hir, symbol_resolver);
+    let gcx = Gcx::new(unsafe { trustme::decouple_lt(&global_context) });
+    let renames = rename_edits(gcx, sources);
+
+    let mut licenses = FxIndexSet::default();
+    let mut pragmas = FxIndexSet::default();
+    let mut bodies = Vec::with_capacity(sources.len());
+    for source in sources.iter() {
+        let ast = source.ast.as_ref().unwrap();
+        let src = source.file.src.as_str();
+        let range = |span: Span| {
+            let start = source.file.start_pos.to_usize();
+            span.lo().to_usize() - start..span.hi().to_usize() - start
+        };
+        let mut edits = Vec::new();
+
+        let mut offset = 0;
+        for line in src.split_inclusive('\n') {
+            let comment = line.trim_start().strip_prefix("//");
+            if let Some(license) = comment.and_then(|c| Some(c.split_once(SPDX_TAG)?.1.trim())) {
+                if !license.is_empty() {
+                    licenses.insert(license.to_string());
+                }
+                edits.push((offset..offset + line.len(), String::new()));
+            }
+            offset += line.len();
+        }
+
+        for item in ast.items.iter() {
+            match &item.kind {
+                ast::ItemKind::Import(_) => {}
+                ast::ItemKind::Pragma(pragma) => {
+                    // Version pragmas are merged in `intersect_versions`.
+                    if !matches!(pragma.tokens, ast::PragmaTokens::Version(..)) {
+                        pragmas.insert(src[range(item.span)].to_string());
+                    }
+                }
+                _ => continue,
+            }
+            edits.push((whole_lines(src, range(item.span)), String::new()));
+        }
+
+        let file = &source.file;
+        let in_file = |span: &Span| file.start_pos <= span.lo() && span.hi() <= file.end_position();
+        edits.extend(
+            renames
+                .iter()
+                .filter(|(span, _)| in_file(span))
+                .map(|(span, s)| (range(*span), s.clone())),
+        );
+
+        bodies.push((source, apply_edits(src, edits)));
+    }
+
+    let mut out = String::new();
+    if !licenses.is_empty() {
+        let licenses =
+            licenses
+                .iter()
+                .map(|l| {
+                    if licenses.len() > 1 && l.contains(' ') {
+                        format!("({l})")
+                    } else {
+                        l.clone()
+                    }
+                })
+                .collect::<Vec<_>>();
+        writeln!(out, "// {SPDX_TAG} {}", licenses.join(" AND ")).unwrap();
+    }
+    if let Some(req) = intersect_versions(sources) {
+        writeln!(out, "pragma solidity {req};").unwrap();
+    }
+    for pragma in &pragmas {
+        writeln!(out, "{pragma}").unwrap();
+    }
+    for (source, body) in bodies {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        writeln!(out, "// {}", source.file.name.display()).unwrap();
+        if !body.is_empty() {
+            writeln!(out, "{body}").unwrap();
+        }
+    }
+    Ok(out)
+}
+
+/// A reference to a top-level item.
+struct Reference {
+    /// The span of the name.
+    span: Span,
+    item: hir::ItemId,
+    /// Whether the name is qualified by a namespace, which is removed.
+    qualified: bool,
+    /// The names declared in the inline assembly block containing the reference, if any.
+    yul_names: Option<usize>,
+}
+
+/// Computes the edits that rename top-level items and remove namespace qualifiers.
+fn rename_edits(gcx: Gcx<'_>, sources: &ParsedSources<'_>) -> Vec<(Span, String)> {
+    let top_level =
+        gcx.hir.sources().flat_map(|source| source.items.iter().copied()).collect::<FxHashSet<_>>();
+    let imports = sources
+        .iter()
+        .flat_map(|source| source.ast.as_ref().unwrap().items.iter())
+        .filter(|item| matches!(item.kind, ast::ItemKind::Import(_)))
+        .map(|item| item.span)
+        .collect::<Vec<_>>();
+    let removed = |span: Span| imports.iter().any(|import| import.contains(span));
+    let text = |span: Span| gcx.sess.source_map().span_to_snippet(span).unwrap_or_default();
+
+    let mut edits = Vec::new();
+    let mut qualified = FxHashSet::default();
+    for &(span, res) in gcx.symbol_resolver.resolutions.lock().iter() {
+        if let hir::Res::Namespace(_) = res {
+            if !removed(span) {
+                let source = gcx.hir.source_at(span.lo()).unwrap();
+                let end = qualifier_end(&gcx.hir.source(source).file, span);
+                qualified.insert(end);
+                edits.push((span.with_hi(end), String::new()));
+            }
+        }
+    }
+
+    let mut references = gcx
+        .name_occurrences()
+        .into_iter()
+        .filter(|&(span, item)| top_level.contains(&item) && !removed(span))
+        .map(|(span, item)| Reference {
+            span,
+            item,
+            qualified: qualified.contains(&span.lo()),
+            yul_names: None,
+        })
+        .collect::<Vec<_>>();
+    references.dedup_by_key(|r| r.span);
+
+    let mut assembly = AssemblyReferences { names: Vec::new(), paths: Vec::new(), block: None };
+    for source in sources.iter() {
+        assembly.visit_source_unit(source.ast.as_ref().unwrap());
+    }
+    for (ident, block) in assembly.paths {
+        // Yul declarations cannot shadow Solidity ones, so the name refers to the visible one.
+        let visible = gcx.visible_names(ident.span.lo());
+        let Some(member) = visible.iter().find(|member| member.name == ident.name) else {
+            continue;
+        };
+        if let Some(hir::Res::Item(item)) = member.res {
+            if top_level.contains(&item) {
+                references.push(Reference {
+                    span: ident.span,
+                    item,
+                    qualified: false,
+                    yul_names: Some(block),
+                });
+            }
+        }
+    }
+
+    let mut taken = gcx
+        .hir
+        .item_ids()
+        .filter_map(|id| gcx.item_name_opt(id))
+        .map(|name| name.to_string())
+        .chain(assembly.names.iter().flatten().map(ToString::to_string))
+        .collect::<FxHashSet<_>>();
+    let mut names = names(gcx, &mut taken);
+    let name_of = |names: &FxHashMap<hir::ItemId, String>, item: hir::ItemId| {
+        names.get(&item).cloned().unwrap_or_else(|| gcx.item_name(item).to_string())
+    };
+
+    // Names that were shadowed in the original sources may be captured by local declarations
+    // once aliases and namespaces are removed. New names are never captured.
+    let is_captured = |reference: &Reference, name: &str| {
+        let name = Symbol::intern(name);
+        if reference.yul_names.is_some_and(|block| assembly.names[block].contains(&name)) {
+            return true;
+        }
+        // Any local variable of the enclosing function, including the one being declared.
+        let is_local = |var: &hir::Variable<'_>| var.name.is_some_and(|n| n.name == name);
+        let function = gcx.hir.functions().find(|f| f.span.contains(reference.span));
+        if function
+            .is_some_and(|f| gcx.hir.variables().any(|v| f.span.contains(v.span) && is_local(v)))
+        {
+            return true;
+        }
+        let visible = gcx.visible_names(reference.span.lo());
+        visible.iter().find(|member| member.name == name).is_some_and(
+            |member| matches!(member.res, Some(hir::Res::Item(item)) if !top_level.contains(&item)),
+        )
+    };
+    for reference in &references {
+        let name = name_of(&names, reference.item);
+        if (reference.qualified || text(reference.span) != name)
+            && !names.contains_key(&reference.item)
+            && is_captured(reference, &name)
+        {
+            let original = gcx.item_name(reference.item);
+            let new_name = fresh_name(&mut taken, original.as_str());
+            // All the overloads of a function are renamed together.
+            let source = gcx.hir.sources().find(|s| s.items.contains(&reference.item)).unwrap();
+            for &item in source.items {
+                if gcx.item_name_opt(item).is_some_and(|name| name.name == original.name) {
+                    names.insert(item, new_name.clone());
+                }
+            }
+        }
+    }
+
+    for reference in &references {
+        let name = name_of(&names, reference.item);
+        if text(reference.span) != name {
+            edits.push((reference.span, name));
+        }
+    }
+    edits
+}
+
+/// Assigns new names to top-level items whose name was already declared in a previous source.
+fn names(gcx: Gcx<'_>, taken: &mut FxHashSet<String>) -> FxHashMap<hir::ItemId, String> {
+    let mut owners = FxHashMap::default();
+    let mut by_source = FxHashMap::default();
+    let mut names = FxHashMap::default();
+    for (id, source) in gcx.hir.sources_enumerated() {
+        for &item in source.items {
+            let Some(name) = gcx.item_name_opt(item) else { continue };
+            let owner = *owners.entry(name.name).or_insert(id);
+            if owner == id {
+                continue;
+            }
+            let new_name = by_source
+                .entry((id, name.name))
+                .or_insert_with(|| fresh_name(taken, name.as_str()))
+                .clone();
+            names.insert(item, new_name);
+        }
+    }
+    names
+}
+
+/// Returns the first name of the form `<name>_<n>` that is not taken, and takes it.
+fn fresh_name(taken: &mut FxHashSet<String>, name: &str) -> String {
+    let new_name = (1..).map(|i| format!("{name}_{i}")).find(|n| !taken.contains(n)).unwrap();
+    taken.insert(new_name.clone());
+    new_name
+}
+
+/// Returns the end of the `N.` qualifier whose name is at `span`, including the dot.
+fn qualifier_end(file: &SourceFile, span: Span) -> BytePos {
+    let src = file.src.as_str();
+    let start = span.hi().to_usize() - file.start_pos.to_usize();
+    let rest = &src[start..];
+    let after_dot = rest.trim_start().strip_prefix('.').unwrap_or(rest);
+    let len = rest.len() - after_dot.trim_start().len();
+    span.hi() + BytePos::from_usize(len)
+}
+
+/// Returns the intersection of all the version requirements, as a disjunction of conjunctions.
+fn intersect_versions(sources: &ParsedSources<'_>) -> Option<String> {
+    let mut seen = FxHashSet::default();
+    let mut dis: Option<Vec<Vec<String>>> = None;
+    for source in sources.iter() {
+        for item in source.ast.as_ref().unwrap().items.iter() {
+            let ast::ItemKind::Pragma(pragma) = &item.kind else { continue };
+            let ast::PragmaTokens::Version(_, req) = &pragma.tokens else { continue };
+            if !seen.insert(req.to_string()) {
+                continue;
+            }
+            let cons = req
+                .dis
+                .iter()
+                .map(|con| con.components.iter().map(ToString::to_string).collect::<Vec<_>>());
+            dis = Some(match dis {
+                None => cons.collect(),
+                Some(prev) => {
+                    let cons = cons.collect::<Vec<_>>();
+                    prev.iter()
+                        .flat_map(|a| {
+                            cons.iter().map(move |b| {
+                                let mut con = a.clone();
+                                con.extend(b.iter().filter(|c| !a.contains(c)).cloned());
+                                con
+                            })
+                        })
+                        .collect()
+                }
+            });
+        }
+    }
+    let dis = dis?.into_iter().map(|con| con.join(" ")).collect::<FxIndexSet<_>>();
+    Some(dis.into_iter().collect::<Vec<_>>().join(" || "))
+}
+
+/// Extends `range` to whole lines if it is the only thing on its lines.
+fn whole_lines(src: &str, range: Range<usize>) -> Range<usize> {
+    let start = src[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let end = src[range.end..].find('\n').map_or(src.len(), |i| range.end + i + 1);
+    if src[start..range.start].trim().is_empty() && src[range.end..end].trim().is_empty() {
+        start..end
+    } else {
+        range
+    }
+}
+
+/// Applies the non-overlapping edits to `src`, collapsing consecutive blank lines and trimming
+/// the result.
+fn apply_edits(src: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut edited = String::with_capacity(src.len());
+    let mut pos = 0;
+    for (range, replacement) in edits {
+        edited.push_str(&src[pos..range.start]);
+        edited.push_str(&replacement);
+        pos = range.end;
+    }
+    edited.push_str(&src[pos..]);
+
+    let mut out = String::with_capacity(edited.len());
+    let mut blank = false;
+    for line in edited.trim().lines() {
+        let is_blank = line.trim().is_empty();
+        if !(blank && is_blank) {
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        blank = is_blank;
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Collects the paths and the declared names of inline assembly blocks.
+struct AssemblyReferences {
+    /// The names declared in each assembly block.
+    names: Vec<FxHashSet<Symbol>>,
+    /// The first segments of paths, with the index of their assembly block.
+    paths: Vec<(Ident, usize)>,
+    /// The index of the assembly block being visited.
+    block: Option<usize>,
+}
+
+impl<'ast> Visit<'ast> for AssemblyReferences {
+    fn visit_stmt_assembly(&mut self, assembly: &'ast ast::StmtAssembly<'ast>) {
+        self.block = Some(self.names.len());
+        self.names.push(FxHashSet::default());
+        self.walk_stmt_assembly(assembly);
+        self.block = None;
+    }
+
+    fn visit_yul_stmt(&mut self, stmt: &'ast yul::Stmt<'ast>) {
+        if let yul::StmtKind::VarDecl(idents, _) = &stmt.kind {
+            self.names.last_mut().unwrap().extend(idents.iter().map(|ident| ident.name));
+        }
+        self.walk_yul_stmt(stmt);
+    }
+
+    fn visit_yul_function(&mut self, function: &'ast yul::Function<'ast>) {
+        let names = self.names.last_mut().unwrap();
+        names.insert(function.name.name);
+        names.extend(function.parameters.iter().chain(function.returns.iter()).map(|i| i.name));
+        self.walk_yul_function(function);
+    }
+
+    fn visit_path(&mut self, path: &'ast ast::PathSlice) {
+        // Only the paths in assembly blocks are needed, like `x` and `x.slot`.
+        if let Some(block) = self.block {
+            self.paths.push((*path.first(), block));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{add_test_files, with_test_session, ParsingContext};
+
+    fn flatten_sources(files: &[(&str, &str)]) -> String {
+        with_test_session(|sess| {
+            let mut pcx = ParsingContext::new(sess);
+            add_test_files(&mut pcx, files);
+            let arenas = ThreadLocal::new();
+            let mut sources = pcx.parse(&arenas);
+            sources.topo_sort();
+            flatten(sess, &sources).unwrap()
+        })
+    }
+
+    #[test]
+    fn flatten_renames_and_merges() {
+        let a = "\
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+pragma abicoder v2;
+
+struct Point { uint x; }
+
+contract Base {}
+";
+        let b = "\
+// SPDX-License-Identifier: MIT
+pragma solidity >=0.8.4 <0.9.0;
+
+import \"a.sol\" as A;
+
+struct Point { uint y; }
+
+contract Child is A.Base {
+    function f(A.Point memory p, Point memory q) external {}
+}
+";
+        let c = "\
+// SPDX-License-Identifier: Apache-2.0
+pragma solidity ^0.8.0;
+pragma abicoder v2;
+
+import {Point as BPoint} from \"b.sol\";
+import * as B from \"b.sol\";
+import \"a.sol\";
+
+contract C is Base, B.Child {
+    BPoint p;
+    Point q;
+}
+";
+        let expected = "\
+// SPDX-License-Identifier: MIT AND Apache-2.0
+pragma solidity ^0.8.0 >=0.8.4 <0.9.0;
+pragma abicoder v2;
+
+// a.sol
+struct Point { uint x; }
+
+contract Base {}
+
+// b.sol
+struct Point_1 { uint y; }
+
+contract Child is Base {
+    function f(Point memory p, Point_1 memory q) external {}
+}
+
+// c.sol
+contract C is Base, Child {
+    Point_1 p;
+    Point q;
+}
+";
+        assert_eq!(flatten_sources(&[("c.sol", c), ("b.sol", b), ("a.sol", a)]), expected);
+    }
+
+    #[test]
+    fn flatten_aliases_and_shadowing() {
+        let b = "uint constant N = 1;\n";
+        let c = "uint constant N = 2;\n";
+        let a = "\
+import {N as M} from \"b.sol\";
+import {N} from \"c.sol\";
+import \"b.sol\" as B;
+
+contract C {
+    uint P = N;
+    function f() public pure returns (uint) {
+        uint N = M;
+        return N;
+    }
+    function g() public pure returns (uint) {
+        uint N = 3;
+        return M + B.N + N;
+    }
+}
+";
+        // `M` would be captured by the local `N`, so `N` of `b.sol` gets a new name.
+        let expected = "\
+// b.sol
+uint constant N_2 = 1;
+
+// c.sol
+uint constant N_1 = 2;
+
+// a.sol
+contract C {
+    uint P = N_1;
+    function f() public pure returns (uint) {
+        uint N = N_2;
+        return N;
+    }
+    function g() public pure returns (uint) {
+        uint N = 3;
+        return N_2 + N_2 + N;
+    }
+}
+";
+        assert_eq!(flatten_sources(&[("a.sol", a), ("b.sol", b), ("c.sol", c)]), expected);
+    }
+
+    #[test]
+    fn flatten_namespace_shadowing() {
+        let b = "uint constant N = 1;\n";
+        let a = "\
+import \"b.sol\" as B;
+
+contract C {
+    uint N;
+    function f() public view returns (uint) { return B.N + N; }
+}
+";
+        let expected = "\
+// b.sol
+uint constant N_1 = 1;
+
+// a.sol
+contract C {
+    uint N;
+    function f() public view returns (uint) { return N_1 + N; }
+}
+";
+        assert_eq!(flatten_sources(&[("a.sol", a), ("b.sol", b)]), expected);
+    }
+
+    #[test]
+    fn flatten_assembly() {
+        let b = "uint constant N = 1;\nuint constant K = 2;\n";
+        let a = "\
+import {K as M} from \"b.sol\";
+
+uint constant N = 2;
+
+contract C {
+    function f() public pure returns (uint r) {
+        assembly { r := add(N, 1) }
+    }
+    function g() public pure returns (uint r) {
+        assembly {
+            let K := 3
+            r := add(M, K)
+        }
+    }
+}
+";
+        let expected = "\
+// b.sol
+uint constant N = 1;
+uint constant K_1 = 2;
+
+// a.sol
+uint constant N_1 = 2;
+
+contract C {
+    function f() public pure returns (uint r) {
+        assembly { r := add(N_1, 1) }
+    }
+    function g() public pure returns (uint r) {
+        assembly {
+            let K := 3
+            r := add(K_1, K)
+        }
+    }
+}
+";
+        assert_eq!(flatten_sources(&[("a.sol", a), ("b.sol", b)]), expected);
+    }
+}
@@ -6,12 +6,19 @@ use rayon::prelude::*;
 use solar_ast::ast;
 use solar_data_structures::{
     index::{Idx, IndexVec},
+    map::FxHashMap,
     newtype_index, BumpExt,
 };
-use solar_interface::{diagnostics::ErrorGuaranteed, source_map::SourceFile, Ident, Span};
+use solar_interface::{diagnostics::ErrorGuaranteed, source_map::SourceFile, Ident, Span, Symbol};
 use std::{fmt, ops::ControlFlow, sync::Arc};
 use strum::EnumIs;
 
+mod node;
+pub use node::Node;
+
+mod visit;
+pub use visit::Visit;
+
 pub use ast::{
     BinOp, BinOpKind, ContractKind, DataLocation, ElementaryType, FunctionKind, Lit,
     StateMutability, UnOp, UnOpKind, VarMut, Visibility,
@@ -78,6 +85,8 @@ pub struct Hir<'hir> {
     pub(crate) errors: IndexVec<ErrorId, Error<'hir>>,
     /// All constants and variables.
     pub(crate) variables: IndexVec<VariableId, Variable<'hir>>,
+    /// The doc-comments of all documented items.
+    pub(crate) docs: FxHashMap<ItemId, &'hir [ast::DocComment]>,
 }
 
 macro_rules! indexvec_methods {
@@ -151,6 +160,7 @@ impl<'hir> Hir<'hir> {
             events: IndexVec::new(),
             errors: IndexVec::new(),
             variables: IndexVec::new(),
+            docs: FxHashMap::default(),
         }
     }
 
@@ -181,6 +191,12 @@ impl<'hir> Hir<'hir> {
         }
     }
 
+    /// Returns the doc-comments of the given item.
+    #[inline]
+    pub fn item_docs(&self, id: impl Into<ItemId>) -> &'hir [ast::DocComment] {
+        self.docs.get(&id.into()).copied().unwrap_or_default()
+    }
+
     /// Returns an iterator over all item IDs.
     pub fn item_ids(&self) -> impl DoubleEndedIterator<Item = ItemId> + Clone {
         std::iter::empty::<ItemId>()
@@ -265,6 +281,20 @@ pub struct Source<'hir> {
     pub imports: &'hir [(ast::ItemId, SourceId)],
     /// The source items.
     pub items: &'hir [ItemId],
+    /// The file-level `using for` directives.
+    pub using_directives: &'hir [UsingDirective<'hir>],
+}
+
+impl Source<'_> {
+    /// Returns the value of the first `SPDX-License-Identifier:` comment in the source.
+    pub fn license(&self) -> Option<&str> {
+        const TAG: &str = "SPDX-License-Identifier:";
+        let src = self.file.src.as_str();
+        let start = src.find(TAG)? + TAG.len();
+        let line = src[start..].lines().next()?;
+        let value = line.split("*/").next()?.trim();
+        (!value.is_empty()).then_some(value)
+    }
 }
 
 impl fmt::Debug for Source<'_> {
@@ -273,6 +303,7 @@ impl fmt::Debug for Source<'_> {
             .field("file", &self.file.name)
             .field("imports", &self.imports)
             .field("items", &self.items)
+            .field("using_directives", &self.using_directives)
             .finish()
     }
 }
@@ -475,6 +506,33 @@ impl ItemId {
             None
         }
     }
+
+    /// Returns the struct ID if this is a struct.
+    pub fn as_struct(&self) -> Option<StructId> {
+        if let Self::Struct(v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the error ID if this is an error.
+    pub fn as_error(&self) -> Option<ErrorId> {
+        if let Self::Error(v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the event ID if this is an event.
+    pub fn as_event(&self) -> Option<EventId> {
+        if let Self::Event(v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
 }
 
 /// A contract, interface, or library.
@@ -492,6 +550,8 @@ pub struct Contract<'hir> {
     pub bases: &'hir [ContractId],
     /// The linearized contract bases.
     pub linearized_bases: &'hir [ContractId],
+    /// The base slot expression of the storage layout specifier: `layout at <slot>`.
+    pub layout: Option<&'hir Expr<'hir>>,
     /// The constructor function.
     pub ctor: Option<FunctionId>,
     /// The `fallback` function.
@@ -503,6 +563,8 @@ pub struct Contract<'hir> {
     /// Note that this only includes items defined in the contract itself, not inherited items.
     /// For getting all items, use [`Hir::contract_items`].
     pub items: &'hir [ItemId],
+    /// The `using for` directives declared in the contract.
+    pub using_directives: &'hir [UsingDirective<'hir>],
 }
 
 impl Contract<'_> {
@@ -524,6 +586,11 @@ impl Contract<'_> {
         self.items.iter().filter_map(ItemId::as_variable)
     }
 
+    /// Returns an iterator over all structs declared in the contract.
+    pub fn structs(&self) -> impl Iterator<Item = StructId> + Clone + use<'_> {
+        self.items.iter().filter_map(ItemId::as_struct)
+    }
+
     /// Returns `true` if the contract can be deployed.
     pub fn can_be_deployed(&self) -> bool {
         matches!(self.kind, ContractKind::Contract | ContractKind::Library)
@@ -535,6 +602,21 @@ impl Contract<'_> {
     }
 }
 
+/// A `using for` directive.
+#[derive(Debug)]
+pub struct UsingDirective<'hir> {
+    /// The directive span.
+    pub span: Span,
+    /// The functions attached to the type as members.
+    ///
+    /// Functions bound to user-defined operators are not included.
+    pub functions: &'hir [FunctionId],
+    /// The type the functions are attached to, or `None` for `*`.
+    pub ty: Option<Type<'hir>>,
+    /// Whether the directive is marked with the `global` keyword.
+    pub global: bool,
+}
+
 /// A function.
 #[derive(Debug)]
 pub struct Function<'hir> {
@@ -609,6 +691,28 @@ pub struct Struct<'hir> {
     /// The struct name.
     pub name: Ident,
     pub fields: &'hir [VariableId],
+    /// The namespaced storage location, from the `@custom:storage-location` NatSpec tag.
+    pub storage_location: Option<StorageLocation>,
+}
+
+/// A namespaced storage location: `@custom:storage-location <formula>:<id>`.
+///
+/// Reference: <https://eips.ethereum.org/EIPS/eip-7201>
+#[derive(Clone, Copy, Debug)]
+pub struct StorageLocation {
+    /// The span of the tag's value.
+    pub span: Span,
+    /// The formula used to compute the root slot of the namespace, e.g. `erc7201`.
+    pub formula: Symbol,
+    /// The namespace ID.
+    pub id: Symbol,
+}
+
+impl StorageLocation {
+    /// Returns `true` if the location uses the ERC-7201 formula.
+    pub fn is_erc7201(&self) -> bool {
+        self.formula.as_str() == "erc7201"
+    }
 }
 
 /// An enum.
@@ -1028,6 +1132,19 @@ impl CallArgs<'_> {
     pub fn empty() -> Self {
         Self::Unnamed(Default::default())
     }
+
+    /// Returns the number of arguments.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Unnamed(exprs) => exprs.len(),
+            Self::Named(args) => args.len(),
+        }
+    }
+
+    /// Returns `true` if there are no arguments.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 /// A type name.
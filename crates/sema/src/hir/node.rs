@@ -0,0 +1,141 @@
+//! Finding HIR nodes by position.
+
+use super::*;
+use solar_data_structures::Never;
+use solar_interface::BytePos;
+
+/// A HIR node, returned by [`Hir::node_at`].
+#[derive(Clone, Copy, Debug)]
+pub enum Node<'hir> {
+    /// An item, including local variables, parameters and struct fields.
+    Item(ItemId),
+    /// A statement.
+    Stmt(&'hir Stmt<'hir>),
+    /// An expression.
+    Expr(&'hir Expr<'hir>),
+}
+
+impl<'hir> Hir<'hir> {
+    /// Returns the source whose file contains the given position.
+    pub fn source_at(&self, pos: BytePos) -> Option<SourceId> {
+        self.sources_enumerated()
+            .find(|(_, source)| source.file.start_pos <= pos && pos <= source.file.end_position())
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the innermost node whose span contains the given position: an expression, a
+    /// statement, or an item.
+    ///
+    /// Spans are inclusive of their end, so a position right after a name is still in it.
+    /// Generated getter functions are skipped in favor of their variables.
+    pub fn node_at(&'hir self, pos: BytePos) -> Option<Node<'hir>> {
+        let mut finder = NodeFinder { hir: self, pos, node: None };
+        let _ = finder.visit_nested_source(self.source_at(pos)?);
+        finder.node
+    }
+}
+
+struct NodeFinder<'hir> {
+    hir: &'hir Hir<'hir>,
+    pos: BytePos,
+    node: Option<Node<'hir>>,
+}
+
+impl NodeFinder<'_> {
+    fn contains(&self, span: Span) -> bool {
+        span.lo() <= self.pos && self.pos <= span.hi()
+    }
+}
+
+impl<'hir> Visit<'hir> for NodeFinder<'hir> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'hir Hir<'hir> {
+        self.hir
+    }
+
+    fn visit_nested_item(&mut self, id: ItemId) -> ControlFlow<Self::BreakValue> {
+        let item = self.hir.item(id);
+        let is_getter = matches!(item, Item::Function(f) if f.is_getter());
+        if is_getter || !self.contains(item.span()) {
+            return ControlFlow::Continue(());
+        }
+        self.node = Some(Node::Item(id));
+        match id {
+            ItemId::Contract(id) => self.visit_nested_contract(id),
+            ItemId::Function(id) => self.visit_nested_function(id),
+            ItemId::Variable(id) => self.visit_nested_var(id),
+            ItemId::Struct(id) => self.visit_nested_struct(id),
+            ItemId::Enum(_) => ControlFlow::Continue(()),
+            ItemId::Udvt(id) => self.visit_ty(&self.hir.udvt(id).ty),
+            ItemId::Error(id) => self.visit_nested_vars(self.hir.error(id).parameters),
+            ItemId::Event(id) => self.visit_nested_vars(self.hir.event(id).parameters),
+        }
+    }
+
+    fn visit_nested_var(&mut self, id: VariableId) -> ControlFlow<Self::BreakValue> {
+        let var = self.hir.variable(id);
+        if !self.contains(var.span) {
+            return ControlFlow::Continue(());
+        }
+        self.node = Some(Node::Item(id.into()));
+        self.walk_var(var)
+    }
+
+    fn visit_stmt(&mut self, stmt: &'hir Stmt<'hir>) -> ControlFlow<Self::BreakValue> {
+        if !self.contains(stmt.span) {
+            return ControlFlow::Continue(());
+        }
+        self.node = Some(Node::Stmt(stmt));
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'hir Expr<'hir>) -> ControlFlow<Self::BreakValue> {
+        if !self.contains(expr.span) {
+            return ControlFlow::Continue(());
+        }
+        self.node = Some(Node::Expr(expr));
+        self.walk_expr(expr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze_test_files;
+    use solar_interface::Pos;
+
+    #[test]
+    fn node_at() {
+        let src = "\
+contract C {
+    uint public x;
+    function f(uint a) public returns (uint) { return a + x; }
+}
+";
+        analyze_test_files(&[("a.sol", src)], |_, gcx| {
+            let hir = &gcx.hir;
+            let node_at = |pattern: &str| {
+                hir.node_at(BytePos::from_usize(src.find(pattern).unwrap())).unwrap()
+            };
+            let item_name = |node: Node<'_>| match node {
+                Node::Item(id) => hir.item(id).name().unwrap().to_string(),
+                _ => panic!("expected an item, got {node:?}"),
+            };
+
+            assert_eq!(item_name(node_at("contract")), "C");
+            assert_eq!(item_name(node_at("public x")), "x");
+            assert_eq!(item_name(node_at("function")), "f");
+            assert_eq!(item_name(node_at("uint a")), "a");
+            assert!(matches!(
+                node_at("return a"),
+                Node::Stmt(Stmt { kind: StmtKind::Return(_), .. })
+            ));
+            let Node::Expr(expr) = node_at("a + x") else { panic!() };
+            assert!(matches!(expr.kind, ExprKind::Ident([Res::Item(ItemId::Variable(_))])));
+            let Node::Expr(expr) = node_at("+ x") else { panic!() };
+            assert!(matches!(expr.kind, ExprKind::Binary(..)));
+            assert!(hir.node_at(BytePos::from_usize(src.len() + 1)).is_none());
+        });
+    }
+}
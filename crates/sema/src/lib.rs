@@ -27,6 +27,9 @@ mod ast_passes;
 mod parse;
 pub use parse::{ParsedSource, ParsedSources, ParsingContext};
 
+mod flatten;
+pub use flatten::flatten;
+
 pub mod builtins;
 pub mod eval;
 pub mod hir;
@@ -38,6 +41,14 @@ mod emit;
 
 /// Parses and semantically analyzes all the loaded sources, recursing into imports.
 pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
+    parse_and_resolve_with(pcx, |_| {})
+}
+
+/// Parses and semantically analyzes all the loaded sources like [`parse_and_resolve`], then calls
+/// `f` with the global context.
+///
+/// `f` is called once the sources have been lowered, even if later analysis emitted errors.
+pub fn parse_and_resolve_with(pcx: ParsingContext<'_>, f: impl FnOnce(Gcx<'_>)) -> Result<()> {
     let sess = pcx.sess;
 
     if pcx.sources.is_empty() {
@@ -82,14 +93,45 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
         }
     });
 
+    analyze_with(sess, &hir_arena, hir, symbol_resolver, f)
+}
+
+/// Semantically analyzes already parsed sources like [`parse_and_resolve_with`], then calls `f`
+/// with the global context.
+///
+/// This can be used to analyze ASTs that were not parsed from source code, such as the ones built
+/// with the [`ast::Arena`] builder methods. The imports of each source must already be filled in
+/// [`ParsedSource::imports`].
+pub fn resolve_with(
+    sess: &Session,
+    mut sources: ParsedSources<'_>,
+    f: impl FnOnce(Gcx<'_>),
+) -> Result<()> {
+    sources.topo_sort();
+
+    let hir_arena = ThreadLocal::<hir::Arena>::new();
+    let (hir, symbol_resolver) = lower(sess, &sources, hir_arena.get_or_default())?;
+    drop(sources);
+
+    analyze_with(sess, &hir_arena, hir, symbol_resolver, f)
+}
+
+/// Creates the global context from the lowered HIR, runs analysis, then calls `f`.
+fn analyze_with<'sess, 'hir>(
+    sess: &'sess Session,
+    hir_arena: &'hir ThreadLocal<hir::Arena>,
+    hir: hir::Hir<'hir>,
+    symbol_resolver: ast_lowering::SymbolResolver<'sess>,
+    f: impl FnOnce(Gcx<'_>),
+) -> Result<()> {
     let global_context =
-        OnDrop::new(ty::GlobalCtxt::new(sess, &hir_arena, hir, symbol_resolver), |gcx| {
+        OnDrop::new(ty::GlobalCtxt::new(sess, hir_arena, hir, symbol_resolver), |gcx| {
             debug_span!("drop_gcx").in_scope(|| drop(gcx));
         });
     let gcx = ty::Gcx::new(unsafe { trustme::decouple_lt(&global_context) });
-    analysis(gcx)?;
-
-    Ok(())
+    let result = analysis(gcx);
+    f(gcx);
+    result
 }
 
 /// Lowers the parsed ASTs into the HIR.
@@ -184,3 +226,106 @@ fn match_file_name(name: &solar_interface::source_map::FileName, path: &str) ->
         solar_interface::source_map::FileName::Custom(name) => path == name,
     }
 }
+
+/// Analyzes the given files and calls `f` with the session and the global context.
+#[cfg(test)]
+pub(crate) fn analyze_test_files(files: &[(&str, &str)], f: impl FnOnce(&Session, Gcx<'_>) + Send) {
+    with_test_session(|sess| {
+        let mut pcx = ParsingContext::new(sess);
+        add_test_files(&mut pcx, files);
+        parse_and_resolve_with(pcx, |gcx| f(sess, gcx)).unwrap();
+    });
+}
+
+/// Adds the given files to the parsing context, with real file names.
+#[cfg(test)]
+pub(crate) fn add_test_files(pcx: &mut ParsingContext<'_>, files: &[(&str, &str)]) {
+    for &(name, src) in files {
+        let file = pcx
+            .sess
+            .source_map()
+            .new_source_file(solar_interface::source_map::FileName::Real(name.into()), || {
+                Ok(src.to_string())
+            })
+            .unwrap();
+        pcx.add_file(file);
+    }
+}
+
+/// Calls `f` with a test session entered on a single-threaded thread pool.
+#[cfg(test)]
+pub(crate) fn with_test_session<R: Send>(f: impl FnOnce(&Session) -> R + Send) -> R {
+    enter_test_session(Session::builder().with_test_emitter().build(), f)
+}
+
+/// Calls `f` with the given session entered on a single-threaded thread pool.
+#[cfg(test)]
+pub(crate) fn enter_test_session<R: Send>(
+    sess: Session,
+    f: impl FnOnce(&Session) -> R + Send,
+) -> R {
+    // Run parallel iterators on the thread that has the session globals. A new thread is used
+    // since a thread can only be added to a thread pool once.
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(1).use_current_thread();
+            pool.build().unwrap().install(|| sess.enter(|| f(&sess)))
+        })
+        .join()
+        .unwrap()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::{BinOpKind, ContractKind, ElementaryType, FunctionKind, TypeSize, Visibility};
+    use solar_interface::source_map::FileName;
+
+    /// Builds `contract C { function f(uint256 x) public returns (uint256) { return x + <rhs>; } }`
+    /// and analyzes it, returning the names of the HIR items.
+    fn resolve_generated(rhs: &str) -> Result<Vec<String>> {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| {
+            let arena = ast::Arena::new();
+            let uint = || arena.elementary_ty(ElementaryType::UInt(TypeSize::new(32).unwrap()));
+            let mut header = arena.function_header(
+                "f",
+                vec![arena.var(uint(), Some("x"))],
+                vec![arena.var(uint(), None)],
+            );
+            header.visibility = Some(Visibility::Public);
+            let sum =
+                arena.binary_expr(arena.ident_expr("x"), BinOpKind::Add, arena.ident_expr(rhs));
+            let body = vec![arena.return_stmt(Some(sum))];
+            let f = arena.function(FunctionKind::Function, header, Some(body));
+            let c = arena.contract(ContractKind::Contract, "C", &[], vec![f]);
+
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::Custom("generated".into()), || Ok(String::new()))
+                .unwrap();
+            let mut sources = ParsedSources::new();
+            let mut source = ParsedSource::new(file);
+            source.ast = Some(arena.source_unit(vec![c]));
+            sources.push(source);
+
+            let mut names = Vec::new();
+            resolve_with(&sess, sources, |gcx| {
+                names = gcx
+                    .hir
+                    .item_ids()
+                    .filter_map(|id| gcx.hir.item(id).name())
+                    .map(|name| name.to_string())
+                    .collect();
+            })?;
+            Ok(names)
+        })
+    }
+
+    #[test]
+    fn resolve_generated_ast() {
+        assert_eq!(resolve_generated("x").unwrap(), ["C", "f", "x"]);
+        assert!(resolve_generated("y").is_err());
+    }
+}
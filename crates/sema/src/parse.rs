@@ -6,12 +6,18 @@ use solar_data_structures::{
     map::FxHashSet,
 };
 use solar_interface::{
-    diagnostics::DiagCtxt,
-    source_map::{FileName, FileResolver, SourceFile},
-    Result, Session,
+    diagnostics::{DiagCtxt, ErrorGuaranteed},
+    edit_distance::find_best_match,
+    source_map::{FileName, FileResolver, ResolveError, SourceFile},
+    Result, Session, Span,
 };
 use solar_parse::{unescape, Lexer, Parser};
-use std::{borrow::Cow, fmt, path::Path, sync::Arc};
+use std::{
+    borrow::Cow,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use thread_local::ThreadLocal;
 
 pub struct ParsingContext<'sess> {
@@ -189,6 +195,97 @@ impl<'sess> ParsingContext<'sess> {
         trace!(allocated = arena.allocated_bytes(), used = arena.used_bytes(), "AST arena stats");
         r
     }
+
+    /// Reports an import that could not be resolved. If the file was not found, suggests files with
+    /// similar names and import remappings.
+    fn report_import_error(
+        &self,
+        error: ResolveError,
+        path: &Path,
+        parent: Option<&Path>,
+        span: Span,
+    ) -> ErrorGuaranteed {
+        let mut diag = self.dcx().err(error.to_string()).span(span);
+        if let ResolveError::NotFound(_) = error {
+            for help in self.import_suggestions(path, parent) {
+                diag = diag.help(help);
+            }
+        }
+        diag.emit()
+    }
+
+    /// Returns suggestions for an import path that was not found.
+    fn import_suggestions(&self, path: &Path, parent: Option<&Path>) -> Vec<String> {
+        let resolver = &self.file_resolver;
+        let mut suggestions = Vec::new();
+
+        // The directories that were searched, like in `FileResolver::resolve_file`.
+        let is_relative = path.starts_with("./") || path.starts_with("../");
+        let remapped = resolver.remap_path(path);
+        let (searched, bases) = if is_relative {
+            let base = parent.map(|parent| parent.parent().unwrap_or(Path::new(".")));
+            (path, base.map(Path::to_path_buf).into_iter().collect())
+        } else {
+            let import_paths = resolver.get_import_paths().iter();
+            let mut bases = import_paths
+                .filter(|(map, _)| map.is_none())
+                .map(|(_, path)| path.clone())
+                .collect::<Vec<_>>();
+            if bases.is_empty() {
+                bases.push(PathBuf::new());
+            }
+            (&*remapped, bases)
+        };
+
+        // Files with a similar name in the same directories.
+        if let Some(file_name) = path.file_name().and_then(|name| name.to_str()) {
+            let mut names = Vec::new();
+            for base in &bases {
+                let dir = base.join(searched);
+                let dir = dir.parent().filter(|dir| !dir.as_os_str().is_empty());
+                let Ok(entries) = std::fs::read_dir(dir.unwrap_or(Path::new("."))) else {
+                    continue;
+                };
+                names.extend(
+                    entries
+                        .flatten()
+                        .filter(|entry| entry.file_type().is_ok_and(|ty| ty.is_file()))
+                        .filter_map(|entry| entry.file_name().into_string().ok()),
+                );
+            }
+            names.sort();
+            if let Some(similar) =
+                find_best_match(names.iter().map(String::as_str), file_name, None)
+            {
+                let similar = path.with_file_name(similar);
+                suggestions
+                    .push(format!("a file with a similar name exists: `{}`", similar.display()));
+            }
+        }
+
+        // Remappings, for imports of libraries that were not remapped.
+        let first = path.components().next().and_then(|first| first.as_os_str().to_str());
+        if let (false, Cow::Borrowed(_), Some(first)) = (is_relative, &remapped, first) {
+            let maps = resolver
+                .get_import_paths()
+                .iter()
+                .filter_map(|(map, _)| map.as_deref()?.to_str())
+                .map(|map| map.trim_end_matches('/'));
+            if let Some(similar) = find_best_match(maps, first, None) {
+                suggestions.push(format!(
+                    "an import remapping with a similar prefix exists: `{similar}/`"
+                ));
+            } else if path.components().count() > 1
+                && !bases.iter().any(|base| base.join(first).is_dir())
+            {
+                suggestions.push(format!(
+                    "if `{first}` is a library, add an import remapping for it: `{first}/=<path>/`"
+                ));
+            }
+        }
+
+        suggestions
+    }
 }
 
 /// Resolves the imports of the given file, returning an iterator over all the imported files.
@@ -225,7 +322,7 @@ macro_rules! resolve_imports {
                 };
                 this.file_resolver
                     .resolve_file(path, parent.as_deref())
-                    .map_err(|e| this.dcx().err(e.to_string()).span(span).emit())
+                    .map_err(|e| this.report_import_error(e, path, parent.as_deref(), span))
                     .ok()
                     .map(|file| (id, file))
             })
@@ -295,6 +392,34 @@ impl ParsedSources<'_> {
         self.sources.push(ParsedSource::new(file))
     }
 
+    /// Returns the import cycles.
+    ///
+    /// Each cycle is a strongly connected component of the import graph with more than one
+    /// source, or a single source that imports itself. Sources in a cycle are in ID order.
+    #[instrument(level = "debug", skip_all)]
+    pub fn import_cycles(&self) -> Vec<Vec<SourceId>> {
+        let mut tarjan = Tarjan {
+            sources: self,
+            index: 0,
+            indices: IndexVec::from_vec(vec![None; self.len()]),
+            low_links: IndexVec::from_vec(vec![0; self.len()]),
+            stack: Vec::new(),
+            on_stack: FxHashSet::default(),
+            cycles: Vec::new(),
+        };
+        for id in self.sources.indices() {
+            if tarjan.indices[id].is_none() {
+                tarjan.connect(id);
+            }
+        }
+        let mut cycles = tarjan.cycles;
+        for cycle in &mut cycles {
+            cycle.sort_unstable();
+        }
+        cycles.sort_unstable();
+        cycles
+    }
+
     /// Asserts that all sources are unique.
     fn assert_unique(&self) {
         if self.sources.len() <= 1 {
@@ -405,6 +530,52 @@ impl ParsedSource<'_> {
     }
 }
 
+/// Tarjan's strongly connected components algorithm, used to find import cycles.
+struct Tarjan<'a, 'ast> {
+    sources: &'a ParsedSources<'ast>,
+    index: usize,
+    indices: IndexVec<SourceId, Option<usize>>,
+    low_links: IndexVec<SourceId, usize>,
+    stack: Vec<SourceId>,
+    on_stack: FxHashSet<SourceId>,
+    cycles: Vec<Vec<SourceId>>,
+}
+
+impl Tarjan<'_, '_> {
+    fn connect(&mut self, id: SourceId) {
+        self.indices[id] = Some(self.index);
+        self.low_links[id] = self.index;
+        self.index += 1;
+        self.stack.push(id);
+        self.on_stack.insert(id);
+
+        for &(_, import_id) in &self.sources[id].imports {
+            match self.indices[import_id] {
+                None => {
+                    self.connect(import_id);
+                    self.low_links[id] = self.low_links[id].min(self.low_links[import_id]);
+                }
+                Some(index) if self.on_stack.contains(&import_id) => {
+                    self.low_links[id] = self.low_links[id].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_links[id]) == self.indices[id] {
+            let start = self.stack.iter().rposition(|&s| s == id).unwrap();
+            let component = self.stack.split_off(start);
+            for s in &component {
+                self.on_stack.remove(s);
+            }
+            let imports_itself = || self.sources[id].imports.iter().any(|&(_, i)| i == id);
+            if component.len() > 1 || imports_itself() {
+                self.cycles.push(component);
+            }
+        }
+    }
+}
+
 /// Sorts `data` according to `indices`.
 ///
 /// Adapted from: <https://stackoverflow.com/a/69774341>
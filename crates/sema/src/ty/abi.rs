@@ -1,8 +1,10 @@
-use super::{Gcx, Ty, TyKind};
+use super::{Gcx, Ty, TyFnPtr, TyKind};
 use crate::hir;
 use alloy_json_abi as json;
+use alloy_primitives::{keccak256, B256};
 use solar_ast::ast::ElementaryType;
-use std::{fmt, ops::ControlFlow};
+use solar_data_structures::map::FxHashSet;
+use std::{collections::BTreeSet, fmt, ops::ControlFlow};
 
 impl<'gcx> Gcx<'gcx> {
     /// Formats the ABI signature of a function in the form `{name}({tys},*)`.
@@ -17,6 +19,59 @@ impl<'gcx> Gcx<'gcx> {
         s
     }
 
+    /// Formats the given type as it would appear in solc diagnostics, without data locations.
+    pub(crate) fn ty_solc_name(self, ty: Ty<'gcx>) -> String {
+        let mut s = String::new();
+        TyPrinter::new(self, &mut s).solc(true).print(ty).unwrap();
+        s
+    }
+
+    /// Returns the EIP-712 `encodeType` of the given struct, e.g.
+    /// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+    ///
+    /// Referenced struct types are appended sorted by name. Returns `None` if the struct contains
+    /// types that cannot be encoded, such as mappings, function pointers or fixed-point numbers.
+    ///
+    /// Reference: <https://eips.ethereum.org/EIPS/eip-712#definition-of-encodetype>
+    pub fn eip712_encode_type(self, id: hir::StructId) -> Option<String> {
+        let mut referenced = BTreeSet::new();
+        let mut stack = vec![id];
+        while let Some(next) = stack.pop() {
+            for &ty in self.struct_field_types(next) {
+                let res = ty.visit(&mut |ty| match ty.kind {
+                    TyKind::Struct(s) => {
+                        if s != id && referenced.insert((self.item_name(s).to_string(), s)) {
+                            stack.push(s);
+                        }
+                        ControlFlow::Continue(())
+                    }
+                    TyKind::Elementary(ElementaryType::Fixed(..) | ElementaryType::UFixed(..))
+                    | TyKind::Mapping(..)
+                    | TyKind::FnPtr(_)
+                    | TyKind::Err(_) => ControlFlow::Break(()),
+                    _ => ControlFlow::Continue(()),
+                });
+                if res.is_break() {
+                    return None;
+                }
+            }
+        }
+
+        let mut s = String::new();
+        let mut printer = TyPrinter::new(self, &mut s).eip712(true);
+        printer.print_eip712_struct(id).unwrap();
+        for (_, s) in referenced {
+            printer.print_eip712_struct(s).unwrap();
+        }
+        Some(s)
+    }
+
+    /// Returns the EIP-712 type hash of the given struct, which is the Keccak-256 hash of its
+    /// [`encodeType`](Self::eip712_encode_type).
+    pub fn eip712_typehash(self, id: hir::StructId) -> Option<B256> {
+        self.eip712_encode_type(id).map(keccak256)
+    }
+
     /// Returns the ABI of the given contract.
     ///
     /// Reference: <https://docs.soliditylang.org/en/develop/abi-spec.html>
@@ -41,26 +96,50 @@ impl<'gcx> Gcx<'gcx> {
         for f in self.interface_functions(id) {
             items.push(self.function_abi(f.id).into());
         }
-        // TODO: Does not include referenced items.
-        // See solc `interfaceEvents` and `interfaceErrors`.
-        for item in self.hir.contract_item_ids(id) {
-            match item {
-                hir::ItemId::Event(id) => items.push(self.event_abi(id).into()),
-                hir::ItemId::Error(id) => items.push(self.error_abi(id).into()),
-                _ => {}
-            }
+        for event in self.interface_events(id) {
+            items.push(self.event_abi(event).into());
+        }
+        for error in self.interface_errors(id) {
+            items.push(self.error_abi(error).into());
         }
 
         // https://github.com/ethereum/solidity/blob/87d86bfba64d8b88537a4a85c1d71f521986b614/libsolidity/interface/ABI.cpp#L43-L47
         fn cmp_key<'a>(item: &'a json::AbiItem<'_>) -> impl Ord + use<'a> {
-            // TODO: Use `json_type` instead of `debug_name`.
-            (item.debug_name(), item.name())
+            (item.json_type(), item.name())
         }
         items.sort_by(|a, b| cmp_key(a).cmp(&cmp_key(b)));
 
         items
     }
 
+    /// Returns the events of the given contract's interface: the ones declared in the contract and
+    /// its bases, and the ones emitted by its code, deduplicated by signature.
+    ///
+    /// See solc `ContractDefinition::interfaceEvents`.
+    pub fn interface_events(self, id: hir::ContractId) -> Vec<hir::EventId> {
+        let declared = self.hir.contract_item_ids(id).filter_map(|item| item.as_event());
+        let used = self.contract_used_items(id).events.iter().copied();
+        self.dedup_by_signature(declared.chain(used))
+    }
+
+    /// Returns the errors of the given contract's interface: the ones declared in the contract and
+    /// its bases, and the ones used by its code, deduplicated by signature.
+    ///
+    /// See solc `ContractDefinition::interfaceErrors`.
+    pub fn interface_errors(self, id: hir::ContractId) -> Vec<hir::ErrorId> {
+        let declared = self.hir.contract_item_ids(id).filter_map(|item| item.as_error());
+        let used = self.contract_used_items(id).errors.iter().copied();
+        self.dedup_by_signature(declared.chain(used))
+    }
+
+    fn dedup_by_signature<T: Into<hir::ItemId> + Copy>(
+        self,
+        items: impl Iterator<Item = T>,
+    ) -> Vec<T> {
+        let mut seen = FxHashSet::default();
+        items.filter(|&item| seen.insert(self.item_signature(item.into()))).collect()
+    }
+
     fn function_abi(self, id: hir::FunctionId) -> json::Function {
         let f = self.hir.function(id);
         json::Function {
@@ -136,7 +215,7 @@ fn json_state_mutability(s: hir::StateMutability) -> json::StateMutability {
     }
 }
 
-struct TyPrinter<'gcx, W: fmt::Write> {
+pub(super) struct TyPrinter<'gcx, W: fmt::Write> {
     gcx: Gcx<'gcx>,
     buf: W,
     /// If `true`, prints the type as it would appear in solc, otherwise as ABI.
@@ -149,14 +228,31 @@ struct TyPrinter<'gcx, W: fmt::Write> {
     ///
     /// Only has effect when printing as solc.
     data_locations: bool,
+    /// If `true`, prints structs by name, as in EIP-712 `encodeType`.
+    ///
+    /// Only has effect when printing as ABI.
+    eip712: bool,
+    /// If `true`, prints the type as it would appear in a standalone source file: user-defined
+    /// types by name, contracts as `address` and function pointers with their signature.
+    ///
+    /// Only has effect when printing as solc.
+    source: bool,
 }
 
 impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
-    fn new(gcx: Gcx<'gcx>, buf: W) -> Self {
-        Self { gcx, buf, recurse: true, solc: false, data_locations: false }
+    pub(super) fn new(gcx: Gcx<'gcx>, buf: W) -> Self {
+        Self {
+            gcx,
+            buf,
+            recurse: true,
+            solc: false,
+            data_locations: false,
+            eip712: false,
+            source: false,
+        }
     }
 
-    fn solc(mut self, yes: bool) -> Self {
+    pub(super) fn solc(mut self, yes: bool) -> Self {
         self.solc = yes;
         self
     }
@@ -166,13 +262,40 @@ impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
         self
     }
 
-    #[allow(dead_code)]
-    fn data_locations(mut self, yes: bool) -> Self {
+    pub(super) fn data_locations(mut self, yes: bool) -> Self {
         self.data_locations = yes;
         self
     }
 
-    fn print(&mut self, ty: Ty<'gcx>) -> fmt::Result {
+    fn eip712(mut self, yes: bool) -> Self {
+        self.eip712 = yes;
+        self
+    }
+
+    pub(super) fn source(mut self, yes: bool) -> Self {
+        self.source = yes;
+        self
+    }
+
+    pub(super) fn into_inner(self) -> W {
+        self.buf
+    }
+
+    /// Prints the struct as `Name(type1 name1,type2 name2,...)`.
+    fn print_eip712_struct(&mut self, id: hir::StructId) -> fmt::Result {
+        self.buf.write_str(self.gcx.item_name(id).as_str())?;
+        self.buf.write_str("(")?;
+        for (i, (ty, field)) in self.gcx.item_fields(id).enumerate() {
+            if i > 0 {
+                self.buf.write_str(",")?;
+            }
+            self.print(ty)?;
+            write!(self.buf, " {}", self.gcx.item_name(field))?;
+        }
+        self.buf.write_str(")")
+    }
+
+    pub(super) fn print(&mut self, ty: Ty<'gcx>) -> fmt::Result {
         if self.solc {
             self.print_solc(ty)
         } else {
@@ -186,7 +309,9 @@ impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
             TyKind::Contract(_) => self.buf.write_str("address"),
             TyKind::FnPtr(_) => self.buf.write_str("function"),
             TyKind::Struct(id) => {
-                if self.recurse {
+                if self.eip712 {
+                    self.buf.write_str(self.gcx.item_name(id).as_str())
+                } else if self.recurse {
                     self.print_tuple(self.gcx.struct_field_types(id).iter().copied())
                 } else {
                     self.buf.write_str("tuple")
@@ -216,18 +341,23 @@ impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
                 }
                 Ok(())
             }
+            TyKind::Contract(_) if self.source => self.buf.write_str("address"),
             TyKind::Contract(id) => {
                 write!(self.buf, "contract {}", self.gcx.item_canonical_name(id))
             }
+            TyKind::FnPtr(ptr) if self.source => self.print_fn_ptr(ptr),
             TyKind::FnPtr(_) => self.buf.write_str("function"),
+            TyKind::Struct(id) if self.source => write!(self.buf, "{}", self.gcx.item_name(id)),
             TyKind::Struct(id) => {
                 write!(self.buf, "struct {}", self.gcx.item_canonical_name(id))
             }
+            TyKind::Enum(id) if self.source => write!(self.buf, "{}", self.gcx.item_name(id)),
             TyKind::Enum(id) => write!(self.buf, "enum {}", self.gcx.item_canonical_name(id)),
+            TyKind::Udvt(_, id) if self.source => write!(self.buf, "{}", self.gcx.item_name(id)),
             TyKind::Udvt(_, id) => write!(self.buf, "{}", self.gcx.item_canonical_name(id)),
             TyKind::Ref(ty, loc) => {
                 self.print_solc(ty)?;
-                if self.data_locations {
+                if self.data_locations && !(self.source && ty.is_value_type()) {
                     write!(self.buf, " {loc}")?;
                 }
                 Ok(())
@@ -240,10 +370,44 @@ impl<'gcx, W: fmt::Write> TyPrinter<'gcx, W> {
                 self.print_solc(ty)?;
                 write!(self.buf, "[{len}]")
             }
+            TyKind::Mapping(key, value) => {
+                self.buf.write_str("mapping(")?;
+                self.print_solc(key)?;
+                self.buf.write_str(" => ")?;
+                self.print_solc(value)?;
+                self.buf.write_str(")")
+            }
             _ => panic!("printing invalid solc type: {ty:?}"),
         }
     }
 
+    /// Prints a function pointer type as `function (T1, T2) external view returns (T3)`.
+    fn print_fn_ptr(&mut self, ptr: &TyFnPtr<'gcx>) -> fmt::Result {
+        self.buf.write_str("function ")?;
+        self.print_solc_list(ptr.parameters)?;
+        write!(self.buf, " {}", ptr.visibility)?;
+        if !ptr.state_mutability.is_non_payable() {
+            write!(self.buf, " {}", ptr.state_mutability)?;
+        }
+        if !ptr.returns.is_empty() {
+            self.buf.write_str(" returns ")?;
+            self.print_solc_list(ptr.returns)?;
+        }
+        Ok(())
+    }
+
+    /// Prints the types as `(T1, T2)`.
+    fn print_solc_list(&mut self, tys: &[Ty<'gcx>]) -> fmt::Result {
+        self.buf.write_str("(")?;
+        for (i, &ty) in tys.iter().enumerate() {
+            if i > 0 {
+                self.buf.write_str(", ")?;
+            }
+            self.print_solc(ty)?;
+        }
+        self.buf.write_str(")")
+    }
+
     fn print_tuple(&mut self, tys: impl IntoIterator<Item = Ty<'gcx>>) -> fmt::Result {
         self.buf.write_str("(")?;
         for (i, ty) in tys.into_iter().enumerate() {
@@ -0,0 +1,170 @@
+use super::{abi::TyPrinter, resolver::ExprResolver, Gcx};
+use crate::hir::{self, Visit};
+use solar_data_structures::{map::FxIndexSet, Never};
+use std::ops::ControlFlow;
+
+/// A call from a function to another function.
+///
+/// Element of [`Gcx::call_graph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Call {
+    /// The calling function. This may be a modifier.
+    pub caller: hir::FunctionId,
+    /// The called function.
+    pub callee: hir::FunctionId,
+    /// The kind of call.
+    pub kind: CallKind,
+}
+
+/// The kind of a [`Call`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CallKind {
+    /// A call to a function of the same contract, a base contract, or a free function, including
+    /// explicit base constructor calls.
+    Internal,
+    /// An external call through a contract or interface type, or `this`, or a contract creation.
+    External,
+    /// A call to a library function.
+    Library,
+    /// A modifier invocation.
+    Modifier,
+}
+
+impl CallKind {
+    /// Returns the string representation of the call kind.
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::Internal => "internal",
+            Self::External => "external",
+            Self::Library => "library",
+            Self::Modifier => "modifier",
+        }
+    }
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the direct inheritance edges `(contract, base)` of all contracts, with bases in
+    /// declaration order.
+    ///
+    /// See also [`hir::Contract::linearized_bases`].
+    pub fn inheritance_graph(
+        self,
+    ) -> impl Iterator<Item = (hir::ContractId, hir::ContractId)> + use<'gcx> {
+        self.hir
+            .contract_ids()
+            .flat_map(move |id| self.hir.contract(id).bases.iter().map(move |&base| (id, base)))
+    }
+
+    /// Returns the function-level call graph of all functions and modifiers, in visiting order.
+    ///
+    /// Callees are resolved statically: calls to virtual functions point to the function that is
+    /// visible at the call site, and `super` calls are resolved in the linearization of the
+    /// calling contract. Overloads are filtered by the number of arguments only. Calls through
+    /// `using for` directives are resolved for receivers whose type is known without type
+    /// checking, like variables and their fields; calls through function pointers are not.
+    pub fn call_graph(self) -> Vec<Call> {
+        let mut collector = CallCollector { gcx: self, caller: None, calls: FxIndexSet::default() };
+        for id in self.hir.function_ids() {
+            collector.caller = Some(id);
+            let _ = collector.visit_nested_function(id);
+        }
+        collector.calls.into_iter().collect()
+    }
+
+    /// Returns a readable name of a function that is unique in its contract or source, like
+    /// `f(uint256,S)` or `constructor()`.
+    pub fn function_display_name(self, id: hir::FunctionId) -> String {
+        let f = self.hir.function(id);
+        let mut s = String::new();
+        match f.name {
+            Some(name) => s.push_str(name.as_str()),
+            None => s.push_str(f.kind.to_str()),
+        }
+        s.push('(');
+        let tys = self.item_parameter_types(id.into());
+        for (i, &ty) in tys.iter().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            let mut printer = TyPrinter::new(self, String::new()).solc(true).source(true);
+            printer.print(ty).unwrap();
+            s.push_str(&printer.into_inner());
+        }
+        s.push(')');
+        s
+    }
+}
+
+/// Collects the calls made by functions.
+struct CallCollector<'gcx> {
+    gcx: Gcx<'gcx>,
+    caller: Option<hir::FunctionId>,
+    calls: FxIndexSet<Call>,
+}
+
+impl<'gcx> CallCollector<'gcx> {
+    fn caller(&self) -> hir::FunctionId {
+        self.caller.unwrap()
+    }
+
+    fn add(&mut self, callee: hir::FunctionId, kind: CallKind) {
+        self.calls.insert(Call { caller: self.caller(), callee, kind });
+    }
+
+    /// Resolves a callee expression and records the calls to the functions it may refer to.
+    fn add_callees(&mut self, callee: &'gcx hir::Expr<'gcx>, n_args: usize) {
+        let caller = self.gcx.hir.function(self.caller());
+        let Some(callee) = ExprResolver::for_function(self.gcx, caller).callee(callee, n_args)
+        else {
+            return;
+        };
+        // Attached functions take the receiver as their first argument.
+        let n_params = n_args + callee.attached as usize;
+        for f in callee.items.iter().filter_map(|item| item.as_function()) {
+            let func = self.gcx.hir.function(f);
+            if func.parameters.len() != n_params {
+                continue;
+            }
+            let kind = match func.contract {
+                _ if callee.external => CallKind::External,
+                Some(c)
+                    if caller.contract != Some(c) && self.gcx.hir.contract(c).kind.is_library() =>
+                {
+                    CallKind::Library
+                }
+                _ => CallKind::Internal,
+            };
+            self.add(f, kind);
+        }
+    }
+}
+
+impl<'gcx> Visit<'gcx> for CallCollector<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_function(&mut self, func: &'gcx hir::Function<'gcx>) -> ControlFlow<Self::BreakValue> {
+        for &modifier in func.modifiers {
+            match modifier {
+                hir::ItemId::Function(modifier) => self.add(modifier, CallKind::Modifier),
+                hir::ItemId::Contract(base) => {
+                    if let Some(ctor) = self.gcx.hir.contract(base).ctor {
+                        self.add(ctor, CallKind::Internal);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.walk_function(func)
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let hir::ExprKind::Call(callee, ref args) = expr.kind {
+            self.add_callees(callee, args.len());
+        }
+        self.walk_expr(expr)
+    }
+}
@@ -0,0 +1,340 @@
+use super::{abi::TyPrinter, Gcx, InterfaceFunction, Ty, TyKind};
+use crate::hir;
+use solar_ast::ast::StateMutability;
+use solar_data_structures::map::FxIndexSet;
+use std::fmt::{self, Write};
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the source code of a Solidity interface `I<Name>` with the external functions,
+    /// including getters, events and errors of the given contract, and the user-defined types they
+    /// reference.
+    ///
+    /// Returns `None` for libraries.
+    pub fn contract_interface_source(self, id: hir::ContractId) -> Option<String> {
+        let contract = self.hir.contract(id);
+        if contract.kind.is_library() {
+            return None;
+        }
+
+        let functions = self.interface_functions(id);
+        let events = self.interface_events(id);
+        let errors = self.interface_errors(id);
+
+        let mut types = FxIndexSet::default();
+        for f in functions.iter() {
+            collect_user_types(self, f.ty, &mut types);
+        }
+        for item in events.iter().map(|&e| e.into()).chain(errors.iter().map(|&e| e.into())) {
+            for &ty in self.item_parameter_types(item) {
+                collect_user_types(self, ty, &mut types);
+            }
+        }
+
+        let mut printer = InterfacePrinter { gcx: self, buf: String::new() };
+        printer.print(id, &types, &events, &errors, functions.all()).unwrap();
+        Some(printer.buf)
+    }
+
+    /// Returns the declaration of an item without its body or initializer, like
+    /// `function f(uint256 x) external view returns (bool)` or `uint256 public x`.
+    ///
+    /// Types are printed as they appear in solc diagnostics.
+    pub fn item_declaration(self, id: hir::ItemId) -> String {
+        let mut printer = InterfacePrinter { gcx: self, buf: String::new() };
+        printer.print_declaration(id).unwrap();
+        printer.buf
+    }
+}
+
+/// Collects the structs, enums and UDVTs referenced by a type, including in struct fields.
+fn collect_user_types<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>, types: &mut FxIndexSet<hir::ItemId>) {
+    match ty.kind {
+        TyKind::Struct(id) => {
+            if !types.insert(id.into()) {
+                return;
+            }
+            for &field in gcx.struct_field_types(id) {
+                collect_user_types(gcx, field, types);
+            }
+        }
+        TyKind::Enum(id) => {
+            types.insert(id.into());
+        }
+        TyKind::Udvt(_, id) => {
+            types.insert(id.into());
+        }
+        TyKind::Ref(ty, _) | TyKind::DynArray(ty) | TyKind::Array(ty, _) => {
+            collect_user_types(gcx, ty, types)
+        }
+        TyKind::FnPtr(ptr) => {
+            for ty in ptr.tys() {
+                collect_user_types(gcx, ty, types);
+            }
+        }
+        _ => {}
+    }
+}
+
+struct InterfacePrinter<'gcx> {
+    gcx: Gcx<'gcx>,
+    buf: String,
+}
+
+impl<'gcx> InterfacePrinter<'gcx> {
+    fn print(
+        &mut self,
+        id: hir::ContractId,
+        types: &FxIndexSet<hir::ItemId>,
+        events: &[hir::EventId],
+        errors: &[hir::ErrorId],
+        functions: &[InterfaceFunction<'gcx>],
+    ) -> fmt::Result {
+        let gcx = self.gcx;
+        let contract = gcx.hir.contract(id);
+        let license = gcx.hir.source(contract.source).license().unwrap_or("UNLICENSED");
+        // Custom errors require 0.8.4, and user-defined value types require 0.8.8.
+        let version = if types.iter().any(|ty| ty.is_udvt()) { "0.8.8" } else { "0.8.4" };
+        writeln!(self.buf, "// SPDX-License-Identifier: {license}")?;
+        writeln!(self.buf, "pragma solidity ^{version};")?;
+        writeln!(self.buf)?;
+        writeln!(self.buf, "interface I{} {{", contract.name)?;
+
+        let mut first = true;
+        let mut separate = |this: &mut Self| {
+            if !std::mem::take(&mut first) {
+                this.buf.push('\n');
+            }
+        };
+        for &ty in types {
+            separate(self);
+            self.print_user_type(ty)?;
+        }
+        if !events.is_empty() {
+            separate(self);
+            for &event in events {
+                self.print_event(event)?;
+            }
+        }
+        if !errors.is_empty() {
+            separate(self);
+            for &error in errors {
+                self.print_error(error)?;
+            }
+        }
+        if !functions.is_empty() {
+            separate(self);
+            for f in functions {
+                self.print_function(f.id, f.ty)?;
+            }
+        }
+        writeln!(self.buf, "}}")
+    }
+
+    fn print_declaration(&mut self, id: hir::ItemId) -> fmt::Result {
+        let gcx = self.gcx;
+        match id {
+            hir::ItemId::Contract(id) => {
+                let contract = gcx.hir.contract(id);
+                write!(self.buf, "{} {}", contract.kind, contract.name)?;
+                for (i, &base) in contract.bases.iter().enumerate() {
+                    self.buf.push_str(if i == 0 { " is " } else { ", " });
+                    write!(self.buf, "{}", gcx.item_name(base))?;
+                }
+                Ok(())
+            }
+            hir::ItemId::Function(id) => {
+                let f = gcx.hir.function(id);
+                self.buf.push_str(f.kind.to_str());
+                if let Some(name) = f.name {
+                    write!(self.buf, " {name}")?;
+                }
+                let tys = gcx.item_parameter_types(id.into());
+                self.print_parameters(f.parameters, tys, true)?;
+                if !(f.kind.is_modifier() || f.kind.is_constructor()) {
+                    write!(self.buf, " {}", f.visibility)?;
+                }
+                if f.state_mutability != StateMutability::NonPayable {
+                    write!(self.buf, " {}", f.state_mutability)?;
+                }
+                if !f.returns.is_empty() {
+                    self.buf.push_str(" returns ");
+                    let TyKind::FnPtr(ptr) = gcx.type_of_item(id.into()).kind else {
+                        unreachable!()
+                    };
+                    self.print_parameters(f.returns, ptr.returns, true)?;
+                }
+                Ok(())
+            }
+            hir::ItemId::Variable(id) => {
+                let var = gcx.hir.variable(id);
+                let ty = gcx.type_of_item(id.into());
+                // State variables are implicitly in storage.
+                let ty = match ty.kind {
+                    TyKind::Ref(inner, _) if var.is_state_variable() => inner,
+                    _ => ty,
+                };
+                self.buf.push_str(&self.ty(ty, true));
+                if let Some(visibility) = var.visibility {
+                    write!(self.buf, " {visibility}")?;
+                }
+                if let Some(mutability) = var.mutability {
+                    write!(self.buf, " {mutability}")?;
+                }
+                if var.indexed {
+                    self.buf.push_str(" indexed");
+                }
+                if let Some(name) = var.name {
+                    write!(self.buf, " {name}")?;
+                }
+                Ok(())
+            }
+            hir::ItemId::Struct(id) => write!(self.buf, "struct {}", gcx.item_name(id)),
+            hir::ItemId::Enum(id) => write!(self.buf, "enum {}", gcx.item_name(id)),
+            hir::ItemId::Udvt(id) => {
+                write!(self.buf, "type {}", gcx.item_name(id))?;
+                if let TyKind::Udvt(ty, _) = gcx.type_of_item(id.into()).kind {
+                    write!(self.buf, " is {}", self.ty(ty, false))?;
+                }
+                Ok(())
+            }
+            hir::ItemId::Event(id) => {
+                let event = gcx.hir.event(id);
+                write!(self.buf, "event {}", event.name)?;
+                self.print_parameters(
+                    event.parameters,
+                    gcx.item_parameter_types(id.into()),
+                    false,
+                )?;
+                if event.anonymous {
+                    self.buf.push_str(" anonymous");
+                }
+                Ok(())
+            }
+            hir::ItemId::Error(id) => {
+                let error = gcx.hir.error(id);
+                write!(self.buf, "error {}", error.name)?;
+                self.print_parameters(error.parameters, gcx.item_parameter_types(id.into()), false)
+            }
+        }
+    }
+
+    fn print_user_type(&mut self, id: hir::ItemId) -> fmt::Result {
+        let gcx = self.gcx;
+        match id {
+            hir::ItemId::Struct(id) => {
+                writeln!(self.buf, "    struct {} {{", gcx.item_name(id))?;
+                for (ty, field) in gcx.item_fields(id) {
+                    writeln!(self.buf, "        {} {};", self.ty(ty, false), gcx.item_name(field))?;
+                }
+                writeln!(self.buf, "    }}")
+            }
+            hir::ItemId::Enum(id) => {
+                let enumm = gcx.hir.enumm(id);
+                write!(self.buf, "    enum {} {{ ", enumm.name)?;
+                for (i, variant) in enumm.variants.iter().enumerate() {
+                    if i > 0 {
+                        self.buf.push_str(", ");
+                    }
+                    write!(self.buf, "{variant}")?;
+                }
+                writeln!(self.buf, " }}")
+            }
+            hir::ItemId::Udvt(id) => {
+                let TyKind::Udvt(ty, _) = gcx.type_of_item(id.into()).kind else { unreachable!() };
+                writeln!(self.buf, "    type {} is {};", gcx.item_name(id), self.ty(ty, false))
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn print_event(&mut self, id: hir::EventId) -> fmt::Result {
+        let event = self.gcx.hir.event(id);
+        write!(self.buf, "    event {}", event.name)?;
+        self.print_parameters(event.parameters, self.gcx.item_parameter_types(id.into()), false)?;
+        if event.anonymous {
+            self.buf.push_str(" anonymous");
+        }
+        writeln!(self.buf, ";")
+    }
+
+    fn print_error(&mut self, id: hir::ErrorId) -> fmt::Result {
+        let error = self.gcx.hir.error(id);
+        write!(self.buf, "    error {}", error.name)?;
+        self.print_parameters(error.parameters, self.gcx.item_parameter_types(id.into()), false)?;
+        writeln!(self.buf, ";")
+    }
+
+    fn print_function(&mut self, id: hir::FunctionId, ty: Ty<'gcx>) -> fmt::Result {
+        let TyKind::FnPtr(ptr) = ty.kind else { unreachable!() };
+        let f = self.gcx.hir.function(id);
+        write!(self.buf, "    function {}", f.name.unwrap())?;
+        self.print_parameters(f.parameters, ptr.parameters, true)?;
+        self.buf.push_str(" external");
+        if ptr.state_mutability != StateMutability::NonPayable {
+            write!(self.buf, " {}", ptr.state_mutability)?;
+        }
+        if !ptr.returns.is_empty() {
+            self.buf.push_str(" returns ");
+            self.print_parameters(f.returns, ptr.returns, true)?;
+        }
+        writeln!(self.buf, ";")
+    }
+
+    fn print_parameters(
+        &mut self,
+        vars: &[hir::VariableId],
+        tys: &[Ty<'gcx>],
+        data_locations: bool,
+    ) -> fmt::Result {
+        self.buf.push('(');
+        for (i, (&var, &ty)) in vars.iter().zip(tys).enumerate() {
+            if i > 0 {
+                self.buf.push_str(", ");
+            }
+            let var = self.gcx.hir.variable(var);
+            self.buf.push_str(&self.ty(ty, data_locations));
+            if var.indexed {
+                self.buf.push_str(" indexed");
+            }
+            if let Some(name) = var.name {
+                write!(self.buf, " {name}")?;
+            }
+        }
+        self.buf.push(')');
+        Ok(())
+    }
+
+    fn ty(&self, ty: Ty<'gcx>, data_locations: bool) -> String {
+        let mut printer = TyPrinter::new(self.gcx, String::new())
+            .solc(true)
+            .source(true)
+            .data_locations(data_locations);
+        printer.print(ty).unwrap();
+        printer.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyze_test_files;
+
+    #[test]
+    fn interfaces_compile() {
+        let src = include_str!("../../../../tests/ui/interface/interface.sol");
+        let mut interfaces = Vec::new();
+        analyze_test_files(&[("interface.sol", src)], |_, gcx| {
+            interfaces = gcx
+                .hir
+                .contract_ids()
+                .filter_map(|id| gcx.contract_interface_source(id))
+                .collect::<Vec<_>>();
+        });
+        assert_eq!(interfaces.len(), 3);
+        for interface in &interfaces {
+            analyze_test_files(&[("interface.sol", interface)], |sess, gcx| {
+                assert!(sess.dcx.has_errors().is_ok(), "{interface}");
+                assert_eq!(gcx.hir.contracts().count(), 1);
+            });
+        }
+    }
+}
@@ -22,12 +22,38 @@ use thread_local::ThreadLocal;
 
 mod abi;
 
+mod call_graph;
+pub use call_graph::{Call, CallKind};
+
 mod common;
 pub use common::{CommonTypes, EachDataLoc};
 
+mod interface;
+
 mod interner;
 use interner::Interner;
 
+mod natspec;
+pub use natspec::{DevDoc, DevDocItem, NatSpec, UserDoc, UserDocItem};
+
+mod references;
+
+mod rename;
+pub use rename::RenameError;
+
+mod resolver;
+
+mod scope;
+
+mod selector;
+pub use selector::SelectorCollision;
+
+mod storage;
+pub use storage::{erc7201_slot, StorageLayout, StorageLayoutEntry, StorageLayoutType};
+
+mod used;
+pub use used::UsedItems;
+
 #[allow(clippy::module_inception)]
 mod ty;
 pub use ty::{Ty, TyData, TyFlags, TyFnPtr, TyKind};
@@ -598,6 +624,13 @@ pub fn struct_field_types(gcx: _, id: hir::StructId) -> &'gcx [Ty<'gcx>] {
     gcx.mk_ty_iter(gcx.hir.strukt(id).fields.iter().map(|&f| gcx.type_of_item(f.into())))
 }
 
+/// Returns the events and errors used by the code of the given contract and its bases.
+///
+/// This includes the code reachable through calls to internal, library and free functions.
+pub fn contract_used_items(gcx: _, id: hir::ContractId) -> UsedItems<'gcx> {
+    used::used_items(gcx, id)
+}
+
 /// Returns the members of the given type.
 pub fn members_of(gcx: _, ty: Ty<'gcx>) -> members::MemberList<'gcx> {
     members::members_of(gcx, ty)
@@ -0,0 +1,395 @@
+//! NatSpec documentation.
+//!
+//! Reference: <https://docs.soliditylang.org/en/latest/natspec-format.html>
+
+use super::Gcx;
+use crate::hir;
+use serde::Serialize;
+use solar_ast::ast::{self, CommentKind};
+use std::collections::BTreeMap;
+
+/// The NatSpec tags of an item.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NatSpec {
+    /// `@title`.
+    pub title: Option<String>,
+    /// `@author`.
+    pub author: Option<String>,
+    /// `@notice`, or untagged text.
+    pub notice: Option<String>,
+    /// `@dev`.
+    pub dev: Option<String>,
+    /// `@param` tags, as `(name, description)` pairs.
+    pub params: Vec<(String, String)>,
+    /// `@return` tags, in order.
+    pub returns: Vec<String>,
+    /// `@inheritdoc` contract name.
+    pub inheritdoc: Option<String>,
+    /// `@custom:...` tags, as `(name, description)` pairs.
+    pub custom: Vec<(String, String)>,
+}
+
+impl NatSpec {
+    /// Parses the NatSpec tags of the given doc-comments.
+    ///
+    /// Lines without a tag continue the previous tag, or start a `@notice` if there is none.
+    /// Unknown tags are ignored.
+    pub fn parse(docs: &[ast::DocComment]) -> Self {
+        let mut tags = Vec::<(&str, String)>::new();
+        for doc in docs {
+            for line in doc.symbol.as_str().lines() {
+                let line = match doc.kind {
+                    CommentKind::Block => line.trim_start().trim_start_matches('*'),
+                    CommentKind::Line => line,
+                };
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                if let Some(tag) = line.strip_prefix('@') {
+                    let (tag, content) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+                    tags.push((tag, content.trim().to_string()));
+                } else if let Some((_, content)) = tags.last_mut() {
+                    push_line(content, line);
+                } else {
+                    tags.push(("notice", line.to_string()));
+                }
+            }
+        }
+
+        let mut natspec = Self::default();
+        for (tag, content) in tags {
+            match tag {
+                "title" => push_opt(&mut natspec.title, content),
+                "author" => push_opt(&mut natspec.author, content),
+                "notice" => push_opt(&mut natspec.notice, content),
+                "dev" => push_opt(&mut natspec.dev, content),
+                "param" => {
+                    let (name, description) =
+                        content.split_once(char::is_whitespace).unwrap_or((&content, ""));
+                    natspec.params.push((name.to_string(), description.trim().to_string()));
+                }
+                "return" => natspec.returns.push(content),
+                "inheritdoc" => natspec.inheritdoc = Some(content),
+                _ => {
+                    if let Some(name) = tag.strip_prefix("custom:") {
+                        natspec.custom.push((name.to_string(), content));
+                    }
+                }
+            }
+        }
+        natspec
+    }
+
+    /// Returns `true` if there are no tags.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Fills the missing tags with the ones of `base`.
+    fn inherit(&mut self, base: Self) {
+        let Self { title, author, notice, dev, params, returns, inheritdoc: _, custom } = base;
+        self.title = self.title.take().or(title);
+        self.author = self.author.take().or(author);
+        self.notice = self.notice.take().or(notice);
+        self.dev = self.dev.take().or(dev);
+        if self.params.is_empty() {
+            self.params = params;
+        }
+        if self.returns.is_empty() {
+            self.returns = returns;
+        }
+        if self.custom.is_empty() {
+            self.custom = custom;
+        }
+    }
+
+    fn custom_map(&self) -> BTreeMap<String, String> {
+        self.custom.iter().map(|(name, value)| (format!("custom:{name}"), value.clone())).collect()
+    }
+}
+
+fn push_line(s: &mut String, line: &str) {
+    if !s.is_empty() {
+        s.push(' ');
+    }
+    s.push_str(line);
+}
+
+fn push_opt(s: &mut Option<String>, content: String) {
+    match s {
+        Some(s) => push_line(s, &content),
+        None => *s = Some(content),
+    }
+}
+
+/// The user documentation of a contract.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/natspec-format.html#user-documentation>
+#[derive(Clone, Debug, Serialize)]
+pub struct UserDoc {
+    /// The custom errors, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<UserDocItem>>,
+    /// The events, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, UserDocItem>,
+    /// Always `user`.
+    pub kind: &'static str,
+    /// The functions, keyed by signature, and the constructor, keyed by `constructor`.
+    pub methods: BTreeMap<String, UserDocItem>,
+    /// The contract `@notice`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+    /// The format version.
+    pub version: u32,
+}
+
+/// An item in a [`UserDoc`].
+#[derive(Clone, Debug, Serialize)]
+pub struct UserDocItem {
+    /// The `@notice`.
+    pub notice: String,
+}
+
+/// The developer documentation of a contract.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/natspec-format.html#developer-documentation>
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevDoc {
+    /// The contract `@author`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// The contract `@custom:...` tags, keyed by `custom:<name>`.
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+    /// The contract `@dev`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The custom errors, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<DevDocItem>>,
+    /// The events, keyed by signature.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub events: BTreeMap<String, DevDocItem>,
+    /// Always `dev`.
+    pub kind: &'static str,
+    /// The functions, keyed by signature, and the constructor, keyed by `constructor`.
+    pub methods: BTreeMap<String, DevDocItem>,
+    /// The state variables, keyed by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub state_variables: BTreeMap<String, DevDocItem>,
+    /// The contract `@title`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The format version.
+    pub version: u32,
+}
+
+/// An item in a [`DevDoc`].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct DevDocItem {
+    /// The `@custom:...` tags, keyed by `custom:<name>`.
+    #[serde(flatten)]
+    pub custom: BTreeMap<String, String>,
+    /// The `@dev`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// The `@param` tags, keyed by parameter name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+    /// The `@return` of a public state variable.
+    #[serde(rename = "return", skip_serializing_if = "Option::is_none")]
+    pub return_: Option<String>,
+    /// The `@return` tags, keyed by return variable name, or `_<index>` if it is unnamed.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub returns: BTreeMap<String, String>,
+}
+
+impl DevDocItem {
+    fn is_empty(&self) -> bool {
+        self.custom.is_empty()
+            && self.details.is_none()
+            && self.params.is_empty()
+            && self.return_.is_none()
+            && self.returns.is_empty()
+    }
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the NatSpec documentation of the given item.
+    ///
+    /// Functions inherit the documentation of the base function named by `@inheritdoc`, or of the
+    /// function they override if they are not documented. Getters use the documentation of their
+    /// state variable.
+    pub fn natspec(self, id: impl Into<hir::ItemId>) -> NatSpec {
+        let id = id.into();
+        let hir::ItemId::Function(f_id) = id else {
+            return NatSpec::parse(self.hir.item_docs(id));
+        };
+        let f = self.hir.function(f_id);
+        if let Some(var) = f.gettee {
+            return NatSpec::parse(self.hir.item_docs(var));
+        }
+        let mut natspec = NatSpec::parse(self.hir.item_docs(id));
+        let Some(contract) = f.contract else { return natspec };
+        if !f.is_part_of_external_interface() {
+            return natspec;
+        }
+
+        let selector = self.function_selector(f_id);
+        let base_function = |base: hir::ContractId| {
+            self.hir.contract(base).functions().find(|&g| {
+                self.hir.function(g).is_part_of_external_interface()
+                    && self.function_selector(g) == selector
+            })
+        };
+        let bases = &self.hir.contract(contract).linearized_bases[1..];
+        let base = if let Some(name) = &natspec.inheritdoc {
+            bases
+                .iter()
+                .find(|&&base| self.item_name(base).as_str() == name)
+                .and_then(|&base| base_function(base))
+        } else if natspec.is_empty() {
+            bases.iter().find_map(|&base| base_function(base))
+        } else {
+            None
+        };
+        if let Some(base) = base {
+            natspec.inherit(self.natspec(base));
+        }
+        natspec
+    }
+
+    /// Returns the user documentation of the given contract.
+    pub fn contract_userdoc(self, id: hir::ContractId) -> UserDoc {
+        let mut doc = UserDoc {
+            errors: BTreeMap::new(),
+            events: BTreeMap::new(),
+            kind: "user",
+            methods: BTreeMap::new(),
+            notice: self.natspec(id).notice,
+            version: 1,
+        };
+        let notice = |id: hir::ItemId| self.natspec(id).notice.map(|notice| UserDocItem { notice });
+        let signature = |id: hir::ItemId| self.item_signature(id).to_string();
+
+        if let Some(ctor) = self.hir.contract(id).ctor {
+            if let Some(item) = notice(ctor.into()) {
+                doc.methods.insert("constructor".into(), item);
+            }
+        }
+        for f in self.interface_functions(id) {
+            if let Some(item) = notice(f.id.into()) {
+                doc.methods.insert(signature(f.id.into()), item);
+            }
+        }
+        for event in self.interface_events(id) {
+            if let Some(item) = notice(event.into()) {
+                doc.events.insert(signature(event.into()), item);
+            }
+        }
+        for error in self.interface_errors(id) {
+            if let Some(item) = notice(error.into()) {
+                doc.errors.insert(signature(error.into()), vec![item]);
+            }
+        }
+        doc
+    }
+
+    /// Returns the developer documentation of the given contract.
+    pub fn contract_devdoc(self, id: hir::ContractId) -> DevDoc {
+        let natspec = self.natspec(id);
+        let custom = natspec.custom_map();
+        let NatSpec { title, author, dev, .. } = natspec;
+        let mut doc = DevDoc {
+            author,
+            custom,
+            details: dev,
+            errors: BTreeMap::new(),
+            events: BTreeMap::new(),
+            kind: "dev",
+            methods: BTreeMap::new(),
+            state_variables: BTreeMap::new(),
+            title,
+            version: 1,
+        };
+        let signature = |id: hir::ItemId| self.item_signature(id).to_string();
+
+        let contract = self.hir.contract(id);
+        if let Some(ctor) = contract.ctor {
+            let item = self.devdoc_item(ctor.into());
+            if !item.is_empty() {
+                doc.methods.insert("constructor".into(), item);
+            }
+        }
+        for f in self.interface_functions(id) {
+            if self.hir.function(f.id).is_getter() {
+                continue;
+            }
+            let item = self.devdoc_item(f.id.into());
+            if !item.is_empty() {
+                doc.methods.insert(signature(f.id.into()), item);
+            }
+        }
+        for event in self.interface_events(id) {
+            let item = self.devdoc_item(event.into());
+            if !item.is_empty() {
+                doc.events.insert(signature(event.into()), item);
+            }
+        }
+        for error in self.interface_errors(id) {
+            let item = self.devdoc_item(error.into());
+            if !item.is_empty() {
+                doc.errors.insert(signature(error.into()), vec![item]);
+            }
+        }
+        for var_id in contract.variables() {
+            let var = self.hir.variable(var_id);
+            let natspec = self.natspec(var_id);
+            let item = DevDocItem {
+                custom: natspec.custom_map(),
+                details: natspec.dev,
+                return_: natspec.returns.into_iter().next().filter(|_| var.is_public()),
+                ..Default::default()
+            };
+            if !item.is_empty() {
+                doc.state_variables.insert(self.item_name(var_id).to_string(), item);
+            }
+        }
+        doc
+    }
+
+    fn devdoc_item(self, id: hir::ItemId) -> DevDocItem {
+        let natspec = self.natspec(id);
+        let returns = match id {
+            hir::ItemId::Function(f) => self.hir.function(f).returns,
+            _ => &[],
+        };
+        DevDocItem {
+            custom: natspec.custom_map(),
+            details: natspec.dev,
+            params: natspec.params.into_iter().collect(),
+            return_: None,
+            returns: natspec
+                .returns
+                .into_iter()
+                .enumerate()
+                .map(|(i, content)| {
+                    let name = returns.get(i).and_then(|&ret| self.hir.variable(ret).name);
+                    match name {
+                        Some(name) => {
+                            let description = content
+                                .strip_prefix(name.as_str())
+                                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+                                .map_or(content.as_str(), str::trim);
+                            (name.to_string(), description.to_string())
+                        }
+                        None => (format!("_{i}"), content),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use super::{
+    resolver::{items_of, ExprResolver, Items},
+    Gcx,
+};
+use crate::hir::{self, Visit};
+use solar_data_structures::{map::FxIndexMap, smallvec::SmallVec, Never};
+use solar_interface::Span;
+use std::ops::ControlFlow;
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the spans of the names of all references to the given item in all sources, sorted
+    /// by span. The declaration itself is not included.
+    ///
+    /// References to a public state variable include the calls to its getter, like `c.x()` and
+    /// `this.x()`, and references to a getter include the uses of its variable. References are
+    /// resolved like in [`Gcx::rename`].
+    pub fn references(self, id: impl Into<hir::ItemId>) -> Vec<Span> {
+        let id = id.into();
+        let mut targets = Items::from_elem(id, 1);
+        match id {
+            hir::ItemId::Variable(var) => {
+                targets.extend(self.hir.variable(var).getter.map(Into::into))
+            }
+            hir::ItemId::Function(f) => targets.extend(self.hir.function(f).gettee.map(Into::into)),
+            _ => {}
+        }
+        let declarations = targets
+            .iter()
+            .filter_map(|&id| self.item_name_opt(id))
+            .map(|name| name.span)
+            .collect::<SmallVec<[Span; 2]>>();
+
+        let mut references = self
+            .name_occurrences()
+            .into_iter()
+            .filter(|(span, id)| targets.contains(id) && !declarations.contains(span))
+            .map(|(span, _)| span)
+            .collect::<Vec<_>>();
+        references.dedup();
+        references
+    }
+
+    /// Returns the occurrences of the names of items in all sources, sorted by span.
+    ///
+    /// This includes declarations, names in paths and import items, identifiers, member accesses
+    /// and named arguments. A name that may refer to multiple items, like the name of a public
+    /// state variable and its getter, has one entry per item.
+    ///
+    /// Member accesses are resolved with the declared types of variables and return types of
+    /// functions, since expressions are not type checked, including members attached with
+    /// `using for`. Overloaded functions are filtered by the number of arguments only.
+    pub(crate) fn name_occurrences(self) -> Vec<(Span, hir::ItemId)> {
+        let mut occurrences = Occurrences::default();
+
+        for id in self.hir.item_ids() {
+            if let Some(name) = self.item_name_opt(id) {
+                add_occurrence(&mut occurrences, name.span, id);
+            }
+        }
+        for &(span, res) in self.symbol_resolver.resolutions.lock().iter() {
+            if let hir::Res::Item(id) = res {
+                add_occurrence(&mut occurrences, span, id);
+            }
+        }
+        for id in self.hir.source_ids() {
+            let resolver = ExprResolver::new(self, id, None);
+            let mut collector = OccurrenceCollector { resolver, occurrences: &mut occurrences };
+            let _ = collector.visit_nested_source(id);
+        }
+
+        let mut occurrences = occurrences
+            .into_iter()
+            .filter(|(span, _)| !span.is_dummy())
+            .flat_map(|(span, items)| items.into_iter().map(move |id| (span, id)))
+            .collect::<Vec<_>>();
+        occurrences.sort_by_key(|&(span, _)| (span.lo(), span.hi()));
+        occurrences
+    }
+}
+
+type Occurrences = FxIndexMap<Span, Items>;
+
+fn add_occurrence(occurrences: &mut Occurrences, span: Span, id: hir::ItemId) {
+    let items = occurrences.entry(span).or_default();
+    if !items.contains(&id) {
+        items.push(id);
+    }
+}
+
+/// Collects the occurrences of item names in expressions and statements.
+struct OccurrenceCollector<'a, 'gcx> {
+    resolver: ExprResolver<'gcx>,
+    occurrences: &'a mut Occurrences,
+}
+
+impl<'gcx> OccurrenceCollector<'_, 'gcx> {
+    /// Overwrites the items of an occurrence with a more precise resolution.
+    fn set(&mut self, span: Span, items: Items) {
+        if !items.is_empty() {
+            self.occurrences.insert(span, items);
+        }
+    }
+
+    /// Records named arguments as references to the parameters or fields they name.
+    fn add_named_args(&mut self, items: &[hir::ItemId], args: &hir::CallArgs<'_>) {
+        let hir::CallArgs::Named(args) = *args else { return };
+        let gcx = self.resolver.gcx;
+        for &id in items {
+            let Some(params) = gcx.item_parameters_opt(id) else { continue };
+            for arg in args {
+                let param = params.iter().copied().find(|&param| {
+                    gcx.item_name_opt(param).is_some_and(|name| name.name == arg.name.name)
+                });
+                if let Some(param) = param {
+                    add_occurrence(self.occurrences, arg.name.span, param.into());
+                }
+            }
+        }
+    }
+}
+
+impl<'gcx> Visit<'gcx> for OccurrenceCollector<'_, 'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.resolver.gcx.hir
+    }
+
+    fn visit_nested_contract(&mut self, id: hir::ContractId) -> ControlFlow<Self::BreakValue> {
+        let contract = self.resolver.gcx.hir.contract(id);
+        let resolver = ExprResolver::new(self.resolver.gcx, contract.source, Some(id));
+        let prev = std::mem::replace(&mut self.resolver, resolver);
+        let r = self.visit_contract(contract);
+        self.resolver = prev;
+        r
+    }
+
+    fn visit_function(&mut self, func: &'gcx hir::Function<'gcx>) -> ControlFlow<Self::BreakValue> {
+        let resolver = ExprResolver::for_function(self.resolver.gcx, func);
+        let prev = std::mem::replace(&mut self.resolver, resolver);
+        let r = self.walk_function(func);
+        self.resolver = prev;
+        r
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        self.walk_stmt(stmt)?;
+        if let hir::StmtKind::Emit(res, ref args) | hir::StmtKind::Revert(res, ref args) = stmt.kind
+        {
+            let mut items = items_of(res);
+            self.resolver.filter_arity(&mut items, args.len());
+            self.add_named_args(&items, args);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        // Walk first so that the resolution of callees can overwrite the one of member accesses.
+        self.walk_expr(expr)?;
+        match expr.kind {
+            hir::ExprKind::Member(base, member) => {
+                let items = self.resolver.members(base, member);
+                self.set(member.span, items);
+            }
+            hir::ExprKind::Call(callee, ref args) => {
+                let Some(callee) = self.resolver.callee(callee, args.len()) else {
+                    return ControlFlow::Continue(());
+                };
+                if let Some(span) = callee.span {
+                    self.set(span, callee.items.clone());
+                }
+                self.add_named_args(&callee.items, args);
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{analyze_test_files, hir};
+    use solar_interface::Span;
+
+    #[test]
+    fn getters_and_this() {
+        let src = "\
+contract C {
+    uint public x;
+    function f(uint a) public returns (uint) { return a + x; }
+    function g() public { this.f(this.x()); f(x); }
+}
+contract D { function h(C c) public { c.f(c.x()); } }
+";
+        // The byte offsets of the last character of each pattern.
+        let offsets = |patterns: &[&str]| -> Vec<usize> {
+            patterns.iter().map(|p| src.find(p).unwrap() + p.len() - 1).collect()
+        };
+        analyze_test_files(&[("a.sol", src)], |_, gcx| {
+            let references = |id: hir::ItemId| {
+                gcx.references(id)
+                    .iter()
+                    .map(|span: &Span| span.lo().0 as usize)
+                    .collect::<Vec<_>>()
+            };
+            let name = |name: &str| {
+                gcx.hir
+                    .item_ids()
+                    .find(|&id| gcx.item_name_opt(id).is_some_and(|n| n.as_str() == name))
+                    .unwrap()
+            };
+
+            let getter = name("x").as_function().unwrap();
+            let x = gcx.hir.function(getter).gettee.unwrap();
+            let expected = offsets(&["a + x", "this.x", "f(x", "c.x"]);
+            assert_eq!(references(x.into()), expected);
+            assert_eq!(references(getter.into()), expected);
+
+            assert_eq!(references(name("f")), offsets(&["this.f", "; f", "c.f"]));
+            assert_eq!(references(name("a")), offsets(&["return a"]));
+            assert_eq!(references(name("C")), offsets(&["(C"]));
+        });
+    }
+
+    #[test]
+    fn using_for() {
+        let src = "\
+struct S { uint a; }
+library L {
+    function inc(S storage s) internal returns (S storage) { s.a++; return s; }
+    function get(uint x) internal pure returns (uint) { return x; }
+}
+using {L.get} for uint;
+contract C {
+    using L for S;
+    S s;
+    function f(uint x) public returns (uint) { return s.inc().inc().a + x.get(); }
+}
+";
+        analyze_test_files(&[("a.sol", src)], |_, gcx| {
+            let references = |name: &str| {
+                let id = gcx
+                    .hir
+                    .item_ids()
+                    .find(|&id| gcx.item_name_opt(id).is_some_and(|n| n.as_str() == name))
+                    .unwrap();
+                gcx.references(id)
+                    .iter()
+                    .map(|span: &Span| gcx.sess.source_map().span_to_snippet(*span).unwrap())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(references("inc"), ["inc", "inc"]);
+            assert_eq!(references("get"), ["get", "get"]);
+            assert_eq!(references("a"), ["a", "a"]);
+        });
+    }
+}
@@ -0,0 +1,270 @@
+use super::Gcx;
+use crate::hir;
+use solar_data_structures::map::FxIndexSet;
+use solar_interface::{source_map::TextEdit, BytePos, Span, Symbol};
+use std::fmt;
+
+/// An error returned by [`Gcx::rename`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RenameError {
+    /// There is no name of a renameable declaration at the given position.
+    NotFound,
+    /// The new name is not a valid identifier, or it is a reserved keyword.
+    InvalidName(String),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("no symbol to rename at the given position"),
+            Self::InvalidName(name) => write!(f, "`{name}` is not a valid identifier"),
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Renames the declaration whose name is at `pos` in all sources, returning the edits to
+    /// apply.
+    ///
+    /// `pos` may be at the declaration itself or at any reference to it, including import items,
+    /// member accesses and named arguments. Renaming a function also renames all the functions and
+    /// public state variables in its override chain.
+    ///
+    /// If `pos` is at an alias introduced by `import {X as Y}`, only the alias `Y` is renamed.
+    ///
+    /// Edits are sorted by span. Member accesses are resolved like in [`Gcx::references`],
+    /// including the calls to functions attached with `using for` directives.
+    pub fn rename(self, pos: BytePos, new_name: &str) -> Result<Vec<TextEdit>, RenameError> {
+        if !solar_parse::lexer::is_ident(new_name) || Symbol::intern(new_name).is_reserved(false) {
+            return Err(RenameError::InvalidName(new_name.to_string()));
+        }
+
+        let occurrences = self.name_occurrences();
+        let span = occurrences
+            .iter()
+            .map(|&(span, _)| span)
+            .filter(|span| span.lo() <= pos && pos <= span.hi())
+            .min_by_key(|span| span.hi() - span.lo())
+            .ok_or(RenameError::NotFound)?;
+        let items = occurrences.iter().filter(|&&(s, _)| s == span).map(|&(_, id)| id);
+        let text = |span: Span| self.sess.source_map().span_to_snippet(span).ok();
+        let old_name = text(span).ok_or(RenameError::NotFound)?;
+
+        // Import aliases are renamed only where the alias is used.
+        let is_alias = items
+            .clone()
+            .all(|id| self.item_name_opt(id).is_some_and(|name| name.as_str() != old_name));
+        let targets = if is_alias { items.collect() } else { self.override_family(items) };
+
+        let mut edits = occurrences
+            .iter()
+            .filter(|(_, id)| targets.contains(id))
+            .map(|&(span, _)| span)
+            .filter(|&span| text(span).is_some_and(|text| text == old_name))
+            .map(|span| TextEdit { span, text: new_name.to_string() })
+            .collect::<Vec<_>>();
+        edits.dedup();
+        Ok(edits)
+    }
+
+    /// Returns the given items along with the functions and public state variables that override
+    /// or are overridden by them, transitively.
+    fn override_family(self, items: impl Iterator<Item = hir::ItemId>) -> FxIndexSet<hir::ItemId> {
+        let mut family = items.collect::<FxIndexSet<_>>();
+        let mut i = 0;
+        while let Some(&id) = family.get_index(i) {
+            i += 1;
+            let f = match id {
+                hir::ItemId::Function(f) => f,
+                hir::ItemId::Variable(var) => {
+                    if let Some(getter) = self.hir.variable(var).getter {
+                        family.insert(getter.into());
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let func = self.hir.function(f);
+            if let Some(var) = func.gettee {
+                family.insert(var.into());
+            }
+            let (Some(name), Some(contract)) = (func.name, func.contract) else { continue };
+            let bases = self.hir.contract(contract).linearized_bases;
+            // Data locations may differ when overriding external functions.
+            let same_params = |g: hir::FunctionId| {
+                let a = self.item_parameter_types(f.into());
+                let b = self.item_parameter_types(g.into());
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.peel_refs() == b.peel_refs())
+            };
+            for g in self.hir.function_ids() {
+                let other = self.hir.function(g);
+                let Some(other_contract) = other.contract else { continue };
+                if g == f
+                    || other.kind != func.kind
+                    || other.name.is_none_or(|other| other.name != name.name)
+                {
+                    continue;
+                }
+                let related = bases.contains(&other_contract)
+                    || self.hir.contract(other_contract).linearized_bases.contains(&contract);
+                if related && same_params(g) {
+                    family.insert(g.into());
+                }
+            }
+        }
+        family
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze_test_files as analyze;
+    use solar_interface::{source_map::Rewriter, Pos};
+
+    /// Renames the name at the first occurrence of `at` in the first file, and returns the changed
+    /// files.
+    fn rename(files: &[(&str, &str)], at: &str, new_name: &str) -> Vec<(String, String)> {
+        let mut renamed = Vec::new();
+        analyze(files, |sess, gcx| {
+            let file = sess.source_map().files()[0].clone();
+            let pos = file.start_pos + BytePos::from_usize(file.src.find(at).unwrap());
+            let mut rewriter = Rewriter::new(sess.source_map());
+            for edit in gcx.rename(pos, new_name).unwrap() {
+                rewriter.edit(edit);
+            }
+            renamed = rewriter
+                .apply()
+                .unwrap()
+                .into_iter()
+                .filter(|patched| patched.is_changed())
+                .map(|patched| (patched.file.name.display().to_string(), patched.src))
+                .collect();
+        });
+        renamed
+    }
+
+    #[test]
+    fn locals_and_named_args() {
+        let src = "\
+contract C {
+    struct S { uint a; }
+    function f(uint a, uint b) internal returns (uint) { return a + b; }
+    function g() public returns (uint) {
+        S memory s = S({a: 1});
+        return f({a: s.a, b: 2});
+    }
+}
+";
+        let renamed = rename(&[("a.sol", src)], "a, uint b", "x");
+        assert_eq!(
+            renamed[0].1,
+            src.replace("uint a, uint b", "uint x, uint b")
+                .replace("return a + b", "return x + b")
+                .replace("f({a: s.a", "f({x: s.a")
+        );
+
+        let renamed = rename(&[("a.sol", src)], "a; }", "y");
+        assert_eq!(
+            renamed[0].1,
+            src.replace("uint a; }", "uint y; }")
+                .replace("S({a: 1})", "S({y: 1})")
+                .replace("s.a", "s.y")
+        );
+    }
+
+    #[test]
+    fn imports() {
+        let a = "\
+import {Token as T} from \"b.sol\";
+import * as B from \"b.sol\";
+contract C is T {
+    function f(B.Token t) public { t.transfer(1); }
+}
+";
+        let b = "\
+contract Token {
+    function transfer(uint amount) public {}
+}
+";
+        let renamed = rename(&[("a.sol", a), ("b.sol", b)], "Token as", "Coin");
+        assert_eq!(
+            renamed,
+            [
+                ("a.sol".to_string(), a.replace("Token", "Coin")),
+                ("b.sol".to_string(), b.replace("Token", "Coin")),
+            ]
+        );
+
+        // Only the alias is renamed.
+        let renamed = rename(&[("a.sol", a), ("b.sol", b)], "T {", "Base");
+        let expected = a.replace("as T}", "as Base}").replace("is T {", "is Base {");
+        assert_eq!(renamed, [("a.sol".to_string(), expected)]);
+
+        let renamed = rename(&[("a.sol", a), ("b.sol", b)], "transfer", "send");
+        assert_eq!(
+            renamed,
+            [
+                ("a.sol".to_string(), a.replace("transfer", "send")),
+                ("b.sol".to_string(), b.replace("transfer", "send")),
+            ]
+        );
+    }
+
+    #[test]
+    fn using_for() {
+        let src = "\
+library SafeLib {
+    function safeDo(address target) internal {}
+    function safeDo(address target, uint value) internal {}
+}
+contract C {
+    using SafeLib for address;
+    function f(address t) external {
+        t.safeDo();
+        t.safeDo(1);
+        SafeLib.safeDo(t);
+    }
+}
+";
+        let renamed = rename(&[("a.sol", src)], "safeDo(address target)", "run");
+        let expected = src
+            .replace("safeDo(address target)", "run(address target)")
+            .replace("t.safeDo();", "t.run();")
+            .replace("SafeLib.safeDo(t)", "SafeLib.run(t)");
+        assert_eq!(renamed[0].1, expected);
+    }
+
+    #[test]
+    fn override_chain() {
+        let src = "\
+interface I { function f() external returns (uint); }
+contract A is I { function f() public virtual returns (uint) { return 1; } }
+contract B is A { function f() public override returns (uint) { return super.f(); } }
+contract D is I { uint public override f; }
+contract E { function f() public {} function g(B b) public { this.f(); b.f(); } }
+";
+        let renamed = rename(&[("a.sol", src)], "f() public override", "h");
+        let expected = "\
+interface I { function h() external returns (uint); }
+contract A is I { function h() public virtual returns (uint) { return 1; } }
+contract B is A { function h() public override returns (uint) { return super.h(); } }
+contract D is I { uint public override h; }
+contract E { function f() public {} function g(B b) public { this.f(); b.h(); } }
+";
+        assert_eq!(renamed[0].1, expected);
+    }
+
+    #[test]
+    fn errors() {
+        analyze(&[("a.sol", "contract C {}")], |_, gcx| {
+            assert_eq!(gcx.rename(BytePos(0), "D"), Err(RenameError::NotFound));
+            let invalid = |name: &str| Err(RenameError::InvalidName(name.to_string()));
+            assert_eq!(gcx.rename(BytePos(9), "1x"), invalid("1x"));
+            assert_eq!(gcx.rename(BytePos(9), "uint"), invalid("uint"));
+            assert_eq!(gcx.rename(BytePos(9), "D").unwrap().len(), 1);
+        });
+    }
+}
@@ -0,0 +1,346 @@
+use super::{Gcx, Ty, TyKind};
+use crate::{builtins::Builtin, hir};
+use solar_data_structures::smallvec::SmallVec;
+use solar_interface::{Ident, Span};
+
+pub(super) type Items = SmallVec<[hir::ItemId; 2]>;
+
+/// Resolves identifiers, member accesses and callees in the code of a source or contract, without
+/// type checking.
+///
+/// Member accesses are resolved with the declared types of variables and return types of
+/// functions, including the functions attached with the `using for` directives that apply to the
+/// code. Overloaded functions are filtered by the number of arguments only.
+#[derive(Clone, Copy)]
+pub(super) struct ExprResolver<'gcx> {
+    pub(super) gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+    contract: Option<hir::ContractId>,
+}
+
+/// A resolved callee. Return type of [`ExprResolver::callee`].
+pub(super) struct Callee {
+    /// The span of the name of the callee, if it has one.
+    pub(super) span: Option<Span>,
+    /// The items the callee may refer to.
+    pub(super) items: Items,
+    /// Whether the items are functions attached with `using for`, which take the receiver as
+    /// their first argument.
+    pub(super) attached: bool,
+    /// Whether this is an external call, through a contract type or `this`, or a contract
+    /// creation.
+    pub(super) external: bool,
+}
+
+/// The items that may be accessed as members of an expression.
+#[derive(Clone, Copy)]
+enum Members {
+    /// The members of a contract, including the inherited ones.
+    Contract(hir::ContractId),
+    /// The members of the bases of a contract, for `super`.
+    Super(hir::ContractId),
+    /// The declarations of a source, for import namespaces.
+    Source(hir::SourceId),
+    /// The fields of a struct.
+    Struct(hir::StructId),
+    /// A value of another type, which only has attached members.
+    Value,
+}
+
+impl<'gcx> ExprResolver<'gcx> {
+    /// Creates a resolver for the code of `contract`, or for the free code of `source` if
+    /// `contract` is `None`.
+    pub(super) fn new(
+        gcx: Gcx<'gcx>,
+        source: hir::SourceId,
+        contract: Option<hir::ContractId>,
+    ) -> Self {
+        Self { gcx, source, contract }
+    }
+
+    /// Creates a resolver for the code of a function.
+    pub(super) fn for_function(gcx: Gcx<'gcx>, func: &hir::Function<'_>) -> Self {
+        Self::new(gcx, func.source, func.contract)
+    }
+
+    /// Resolves a callee, filtering overloads by the number of arguments.
+    pub(super) fn callee(&self, callee: &'gcx hir::Expr<'gcx>, n_args: usize) -> Option<Callee> {
+        let mut callee = callee.peel_parens();
+        let mut external = false;
+        while let hir::ExprKind::CallOptions(inner, _) = callee.kind {
+            callee = inner.peel_parens();
+            external = true;
+        }
+        let (span, mut items, attached) = match callee.kind {
+            hir::ExprKind::Ident(res) => (Some(callee.span), items_of(res), false),
+            hir::ExprKind::Member(base, member) => {
+                let (mut items, attached) = self.member_items(base, member);
+                external |= !attached && self.is_external_base(base);
+                if external {
+                    // Only the functions of the external interface can be called externally.
+                    items.retain(|id| {
+                        id.as_function().is_none_or(|f| {
+                            self.gcx.hir.function(f).is_part_of_external_interface()
+                        })
+                    });
+                }
+                (Some(member.span), items, attached)
+            }
+            hir::ExprKind::New(ref ty) => {
+                let hir::TypeKind::Custom(hir::ItemId::Contract(c)) = ty.kind else { return None };
+                let ctor = self.gcx.hir.contract(c).ctor.map(Into::into).into_iter().collect();
+                return Some(Callee { span: None, items: ctor, attached: false, external: true });
+            }
+            _ => return None,
+        };
+        self.filter_arity(&mut items, n_args + attached as usize);
+        Some(Callee { span, items, attached, external })
+    }
+
+    /// Removes the overloads that do not accept `n_args` arguments, if any overload does.
+    pub(super) fn filter_arity(&self, items: &mut Items, n_args: usize) {
+        if items.len() < 2 {
+            return;
+        }
+        let accepts =
+            |id: hir::ItemId| self.gcx.item_parameters_opt(id).is_none_or(|p| p.len() == n_args);
+        if items.iter().any(|&id| accepts(id)) {
+            items.retain(|id| accepts(*id));
+        }
+    }
+
+    /// Returns the items that `base.member` may refer to.
+    pub(super) fn members(&self, base: &'gcx hir::Expr<'gcx>, member: Ident) -> Items {
+        self.member_items(base, member).0
+    }
+
+    /// Returns the items that `base.member` may refer to, and whether they are attached
+    /// functions.
+    fn member_items(&self, base: &'gcx hir::Expr<'gcx>, member: Ident) -> (Items, bool) {
+        let gcx = self.gcx;
+        let resolver = &gcx.symbol_resolver;
+        let named = |id: hir::ItemId| gcx.item_name_opt(id).is_some_and(|n| n.name == member.name);
+        let items = match self.members_of(base) {
+            Some(Members::Contract(c)) => {
+                items_of_decls(resolver.contract_scopes[c].resolve(member).unwrap_or_default())
+            }
+            Some(Members::Super(c)) => {
+                // The first base in the linearization that declares the member.
+                let bases = gcx.hir.contract(c).linearized_bases;
+                bases
+                    .iter()
+                    .skip(1)
+                    .find_map(|&base| resolver.contract_scopes[base].resolve(member))
+                    .map(items_of_decls)
+                    .unwrap_or_default()
+            }
+            Some(Members::Source(s)) => {
+                return (
+                    items_of_decls(resolver.source_scopes[s].resolve(member).unwrap_or_default()),
+                    false,
+                );
+            }
+            Some(Members::Struct(s)) => {
+                gcx.hir.strukt(s).fields.iter().map(|&f| f.into()).filter(|&f| named(f)).collect()
+            }
+            Some(Members::Value) => Items::new(),
+            None => return (Items::new(), false),
+        };
+        if !items.is_empty() {
+            return (items, false);
+        }
+
+        // Members that are not declared by the type may be attached to it.
+        match self.type_of(base) {
+            Some(ty) => (self.attached_functions(ty, member), true),
+            None => (Items::new(), false),
+        }
+    }
+
+    /// Returns the members that can be accessed on an expression.
+    fn members_of(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<Members> {
+        match self.resolve(expr) {
+            Some(hir::Res::Item(hir::ItemId::Contract(c))) => return Some(Members::Contract(c)),
+            Some(hir::Res::Namespace(s)) => return Some(Members::Source(s)),
+            Some(hir::Res::Builtin(Builtin::This)) => {
+                return Some(Members::Contract(self.contract?))
+            }
+            Some(hir::Res::Builtin(Builtin::Super)) => return Some(Members::Super(self.contract?)),
+            _ => {}
+        }
+        match self.type_of(expr)?.peel_refs().kind {
+            TyKind::Contract(c) => Some(Members::Contract(c)),
+            TyKind::Struct(s) => Some(Members::Struct(s)),
+            _ => Some(Members::Value),
+        }
+    }
+
+    /// Returns `true` if calling a member of `base` is an external call.
+    fn is_external_base(&self, base: &'gcx hir::Expr<'gcx>) -> bool {
+        match self.resolve(base) {
+            Some(hir::Res::Builtin(Builtin::This)) => true,
+            Some(hir::Res::Item(hir::ItemId::Contract(_)) | hir::Res::Namespace(_)) => false,
+            Some(hir::Res::Builtin(_)) => false,
+            _ => self
+                .type_of(base)
+                .is_some_and(|ty| matches!(ty.peel_refs().kind, TyKind::Contract(_))),
+        }
+    }
+
+    /// Returns the functions named `member` attached to `ty` with the `using for` directives that
+    /// apply to the code.
+    pub(super) fn attached_functions(&self, ty: Ty<'gcx>, member: Ident) -> Items {
+        let gcx = self.gcx;
+        let ty = ty.peel_refs();
+        let contract_directives = self.contract.map(|c| gcx.hir.contract(c).using_directives);
+        let source_directives = gcx.hir.source(self.source).using_directives;
+        // `global` directives apply wherever the type is used.
+        let global_directives = type_source(gcx, ty)
+            .map(|source| gcx.hir.source(source).using_directives)
+            .unwrap_or_default()
+            .iter()
+            .filter(|directive| directive.global);
+        let directives = contract_directives
+            .unwrap_or_default()
+            .iter()
+            .chain(source_directives)
+            .chain(global_directives);
+
+        let mut functions = Items::new();
+        for directive in directives {
+            if directive.ty.as_ref().is_some_and(|t| gcx.type_of_hir_ty(t).peel_refs() != ty) {
+                continue;
+            }
+            for &f in directive.functions {
+                let matches = gcx.hir.function(f).name.is_some_and(|n| n.name == member.name)
+                    && gcx
+                        .item_parameter_types(f.into())
+                        .first()
+                        .is_some_and(|p| p.peel_refs() == ty);
+                if matches && !functions.contains(&f.into()) {
+                    functions.push(f.into());
+                }
+            }
+        }
+        functions
+    }
+
+    /// Returns `true` if a function named `member` is attached to any type with the `using for`
+    /// directives that may apply to the code.
+    ///
+    /// Unlike [`attached_functions`](Self::attached_functions), this does not compare types, so
+    /// it also covers the functions whose first parameter the receiver implicitly converts to.
+    pub(super) fn has_attached_function(&self, member: Ident) -> bool {
+        let gcx = self.gcx;
+        let contract_directives = self.contract.map(|c| gcx.hir.contract(c).using_directives);
+        let source_directives = gcx.hir.source(self.source).using_directives;
+        let global_directives = gcx
+            .hir
+            .sources()
+            .flat_map(|source| source.using_directives)
+            .filter(|directive| directive.global);
+        contract_directives
+            .unwrap_or_default()
+            .iter()
+            .chain(source_directives)
+            .chain(global_directives)
+            .flat_map(|directive| directive.functions)
+            .any(|&f| gcx.hir.function(f).name.is_some_and(|n| n.name == member.name))
+    }
+
+    /// Resolves an identifier or a member access to a single declaration.
+    pub(super) fn resolve(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<hir::Res> {
+        match expr.peel_parens().kind {
+            hir::ExprKind::Ident(&[res]) => Some(res),
+            // A public state variable and its getter.
+            hir::ExprKind::Ident(
+                &[res @ hir::Res::Item(hir::ItemId::Variable(var)), hir::Res::Item(hir::ItemId::Function(f))],
+            ) if self.gcx.hir.variable(var).getter == Some(f) => Some(res),
+            hir::ExprKind::Member(base, member) => match self.members(base, member)[..] {
+                [id] => Some(hir::Res::Item(id)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the type of an expression, if it can be determined from declarations only.
+    pub(super) fn type_of(&self, expr: &'gcx hir::Expr<'gcx>) -> Option<Ty<'gcx>> {
+        let gcx = self.gcx;
+        let expr = expr.peel_parens();
+        match expr.kind {
+            hir::ExprKind::Ident(_) | hir::ExprKind::Member(..) => match self.resolve(expr)? {
+                hir::Res::Item(hir::ItemId::Variable(var)) => Some(gcx.type_of_item(var.into())),
+                _ => None,
+            },
+            hir::ExprKind::Index(base, _) => {
+                let base = self.type_of(base)?;
+                let elem = match base.peel_refs().kind {
+                    TyKind::Mapping(_, value) => value,
+                    TyKind::Array(elem, _) | TyKind::DynArray(elem) => elem,
+                    _ => return None,
+                };
+                // Elements are in the same location as the indexed value.
+                match base.kind {
+                    TyKind::Ref(_, loc) if !elem.is_value_type() => Some(elem.with_loc(gcx, loc)),
+                    _ => Some(elem),
+                }
+            }
+            hir::ExprKind::Call(callee, _) => {
+                if let hir::ExprKind::New(ref ty) = callee.peel_parens().kind {
+                    return Some(gcx.type_of_hir_ty(ty));
+                }
+                match self.resolve(callee)? {
+                    hir::Res::Item(hir::ItemId::Function(f)) => match gcx.hir.function(f).returns {
+                        &[ret] => Some(gcx.type_of_item(ret.into())),
+                        _ => None,
+                    },
+                    // Getters of value types.
+                    hir::Res::Item(hir::ItemId::Variable(var)) => {
+                        let ty = gcx.type_of_item(var.into());
+                        match ty.peel_refs().kind {
+                            TyKind::Mapping(..) | TyKind::Array(..) | TyKind::DynArray(_) => None,
+                            _ => Some(ty),
+                        }
+                    }
+                    // Contract conversions and struct constructors.
+                    hir::Res::Item(hir::ItemId::Contract(c)) => {
+                        Some(gcx.mk_ty(TyKind::Contract(c)))
+                    }
+                    hir::Res::Item(hir::ItemId::Struct(s)) => Some(gcx.mk_ty(TyKind::Struct(s))),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the source a user-defined type is declared in.
+fn type_source(gcx: Gcx<'_>, ty: Ty<'_>) -> Option<hir::SourceId> {
+    match ty.kind {
+        TyKind::Struct(id) => Some(gcx.hir.strukt(id).source),
+        TyKind::Enum(id) => Some(gcx.hir.enumm(id).source),
+        TyKind::Udvt(_, id) => Some(gcx.hir.udvt(id).source),
+        _ => None,
+    }
+}
+
+pub(super) fn items_of(res: &[hir::Res]) -> Items {
+    res.iter()
+        .filter_map(|res| match *res {
+            hir::Res::Item(id) => Some(id),
+            _ => None,
+        })
+        .collect()
+}
+
+fn items_of_decls(decls: &[crate::ast_lowering::resolve::Declaration]) -> Items {
+    decls
+        .iter()
+        .filter_map(|decl| match decl.res {
+            hir::Res::Item(id) => Some(id),
+            _ => None,
+        })
+        .collect()
+}
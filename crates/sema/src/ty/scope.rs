@@ -0,0 +1,363 @@
+use super::{resolver::ExprResolver, Gcx, Ty, TyKind};
+use crate::{
+    ast_lowering::resolve::Declarations,
+    builtins::{Builtin, Member},
+    hir,
+};
+use solar_ast::ast::{DataLocation, ElementaryType};
+use solar_data_structures::map::FxHashSet;
+use solar_interface::{BytePos, Ident};
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the declarations that are visible at the given position, innermost first.
+    ///
+    /// These are, in order: the local variables and parameters of the enclosing function, the
+    /// declarations of the enclosing contract including the inherited ones, the declarations of the
+    /// source including the imported ones, and the global builtins. Names shadowed by an inner
+    /// scope are not included, but all the overloads of a name are.
+    ///
+    /// Local variables are visible after the statement that declares them, until the end of the
+    /// enclosing block.
+    pub fn visible_names(self, pos: BytePos) -> Vec<Member<'gcx>> {
+        let Some(source) = self.hir.source_at(pos) else { return Vec::new() };
+        let contract = self.contract_at(source, pos);
+
+        let mut names = Vec::new();
+        let mut shadowed = FxHashSet::default();
+        let mut outer = FxHashSet::default();
+        if let Some(func) = self.function_at(source, pos) {
+            for var in self.visible_locals(func, pos) {
+                let Some(name) = self.item_name_opt(var) else { continue };
+                if shadowed.insert(name.name) {
+                    let ty = self.type_of_item(var.into());
+                    names.push(Member::with_res(name.name, ty, hir::ItemId::from(var)));
+                }
+            }
+        }
+        let resolver = &self.symbol_resolver;
+        let scopes = contract
+            .map(|c| &resolver.contract_scopes[c])
+            .into_iter()
+            .chain([&resolver.source_scopes[source], &resolver.global_builtin_scope]);
+        for scope in scopes {
+            for member in self.scope_members(scope, contract) {
+                if !shadowed.contains(&member.name) {
+                    outer.insert(member.name);
+                    names.push(member);
+                }
+            }
+            shadowed.extend(outer.drain());
+        }
+        names
+    }
+
+    /// Returns the completions at the given position.
+    ///
+    /// If `pos` is in the member name of a member access `x.name`, these are the members of `x`;
+    /// otherwise they are the [visible names](Self::visible_names). Since the sources must parse,
+    /// editors should complete a member access that has no name yet, like `x.`, by analyzing the
+    /// source with a placeholder name inserted at `pos`.
+    pub fn completions(self, pos: BytePos) -> Vec<Member<'gcx>> {
+        if let Some(hir::Node::Expr(expr)) = self.hir.node_at(pos) {
+            if let hir::ExprKind::Member(base, _) = expr.kind {
+                if let Some(source) = self.hir.source_at(pos).filter(|_| pos > base.span.hi()) {
+                    return self.expr_members(base, source, self.contract_at(source, pos));
+                }
+            }
+        }
+        self.visible_names(pos)
+    }
+
+    /// Returns the members of `base` if `member` is not one of them.
+    ///
+    /// Returns `None` if `member` is a member of `base`, may be a function attached to it with a
+    /// `using for` directive, or if the members of `base` cannot be determined from declarations.
+    pub(crate) fn missing_member(
+        self,
+        base: &'gcx hir::Expr<'gcx>,
+        member: Ident,
+        source: hir::SourceId,
+        contract: Option<hir::ContractId>,
+    ) -> Option<Vec<Member<'gcx>>> {
+        let members = self.expr_members(base, source, contract);
+        if members.is_empty() || members.iter().any(|m| m.name == member.name) {
+            return None;
+        }
+        let resolver = ExprResolver::new(self, source, contract);
+        if !resolver.members(base, member).is_empty() || resolver.has_attached_function(member) {
+            return None;
+        }
+        Some(members)
+    }
+
+    /// Returns the members that can be accessed on an expression.
+    ///
+    /// The type of the expression is determined from declarations only, like when resolving
+    /// references.
+    fn expr_members(
+        self,
+        expr: &'gcx hir::Expr<'gcx>,
+        source: hir::SourceId,
+        contract: Option<hir::ContractId>,
+    ) -> Vec<Member<'gcx>> {
+        let expr_resolver = ExprResolver::new(self, source, contract);
+        let resolver = &self.symbol_resolver;
+        let ty = match expr_resolver.resolve(expr) {
+            // `C.f`, including internal functions of base contracts and library functions.
+            Some(hir::Res::Item(hir::ItemId::Contract(c))) => {
+                return self.scope_members(&resolver.contract_scopes[c], None);
+            }
+            Some(hir::Res::Builtin(Builtin::Super)) => {
+                let Some(contract) = contract else { return Vec::new() };
+                // The members of all the bases, the first base in the linearization that declares
+                // a name shadowing the others.
+                let mut members = Vec::new();
+                let mut seen = FxHashSet::default();
+                for &base in self.hir.contract(contract).linearized_bases.iter().skip(1) {
+                    let base_members = self.scope_members(&resolver.contract_scopes[base], None);
+                    let new = base_members.iter().map(|m| m.name).collect::<Vec<_>>();
+                    members.extend(base_members.into_iter().filter(|m| !seen.contains(&m.name)));
+                    seen.extend(new);
+                }
+                return members;
+            }
+            Some(hir::Res::Builtin(Builtin::This)) => {
+                let Some(contract) = contract else { return Vec::new() };
+                self.mk_ty(TyKind::Contract(contract))
+            }
+            Some(hir::Res::Item(hir::ItemId::Variable(_))) | None => {
+                let Some(ty) = expr_resolver.type_of(expr) else { return Vec::new() };
+                ty
+            }
+            // `E.Variant`, `U.wrap`.
+            Some(res @ hir::Res::Item(hir::ItemId::Enum(_) | hir::ItemId::Udvt(_))) => {
+                self.type_of_res(res).make_type_type(self)
+            }
+            Some(res) => self.type_of_res(res),
+        };
+        self.ty_members(ty)
+    }
+
+    /// Returns the members of a type, like [`members_of`](Self::members_of), but does not panic on
+    /// types without a data location.
+    fn ty_members(self, ty: Ty<'gcx>) -> Vec<Member<'gcx>> {
+        let ty = match ty.kind {
+            // Members of function types are not implemented yet.
+            TyKind::FnPtr(_) => return Vec::new(),
+            TyKind::Struct(_)
+            | TyKind::Array(..)
+            | TyKind::DynArray(_)
+            | TyKind::Elementary(ElementaryType::Bytes) => ty.make_ref(self, DataLocation::Storage),
+            _ => ty,
+        };
+        self.members_of(ty).to_vec()
+    }
+
+    /// Returns the declarations of a scope as members, without the getters of public state
+    /// variables.
+    ///
+    /// `this` and `super` have the type of `contract`, or are skipped if it is `None`.
+    fn scope_members(
+        self,
+        scope: &Declarations,
+        contract: Option<hir::ContractId>,
+    ) -> Vec<Member<'gcx>> {
+        let mut members = Vec::new();
+        for (&name, decls) in &scope.declarations {
+            for decl in decls.iter().filter(|decl| !self.is_getter(decl.res)) {
+                let ty = match decl.res {
+                    hir::Res::Builtin(Builtin::This | Builtin::Super) => {
+                        let Some(contract) = contract else { continue };
+                        self.mk_ty(TyKind::Contract(contract))
+                    }
+                    res => self.type_of_res(res),
+                };
+                members.push(Member::with_res(name, ty, decl.res));
+            }
+        }
+        members
+    }
+
+    fn is_getter(self, res: hir::Res) -> bool {
+        matches!(res, hir::Res::Item(hir::ItemId::Function(f)) if self.hir.function(f).is_getter())
+    }
+
+    /// Returns the contract in the given source whose span contains `pos`.
+    fn contract_at(self, source: hir::SourceId, pos: BytePos) -> Option<hir::ContractId> {
+        self.hir
+            .contracts_enumerated()
+            .find(|(_, c)| c.source == source && c.span.lo() <= pos && pos <= c.span.hi())
+            .map(|(id, _)| id)
+    }
+
+    /// Returns the function in the given source whose span contains `pos`.
+    fn function_at(self, source: hir::SourceId, pos: BytePos) -> Option<&'gcx hir::Function<'gcx>> {
+        self.hir.functions().find(|f| {
+            f.source == source && !f.is_getter() && f.span.lo() <= pos && pos <= f.span.hi()
+        })
+    }
+
+    /// Returns the local variables and parameters of a function that are visible at `pos`,
+    /// innermost first.
+    fn visible_locals(self, func: &'gcx hir::Function<'gcx>, pos: BytePos) -> Vec<hir::VariableId> {
+        let mut locals = LocalsCollector { hir: &self.hir, pos, locals: Vec::new() };
+        locals.locals.extend(func.parameters.iter().chain(func.returns));
+        if let Some(body) = func.body {
+            locals.block(body);
+        }
+        let mut locals = locals.locals;
+        locals.reverse();
+        locals
+    }
+}
+
+/// Collects the local variables that are visible at a position.
+struct LocalsCollector<'a> {
+    hir: &'a hir::Hir<'a>,
+    pos: BytePos,
+    locals: Vec<hir::VariableId>,
+}
+
+impl LocalsCollector<'_> {
+    fn contains(&self, stmt: &hir::Stmt<'_>) -> bool {
+        stmt.span.lo() <= self.pos && self.pos <= stmt.span.hi()
+    }
+
+    fn block(&mut self, block: hir::Block<'_>) {
+        for stmt in block {
+            if stmt.span.hi() <= self.pos {
+                match stmt.kind {
+                    hir::StmtKind::DeclSingle(var) => self.locals.push(var),
+                    hir::StmtKind::DeclMulti(vars, _) => self.locals.extend(vars.iter().flatten()),
+                    _ => {}
+                }
+            } else {
+                if self.contains(stmt) {
+                    self.stmt(stmt);
+                }
+                break;
+            }
+        }
+    }
+
+    /// Collects the locals of a statement that contains the position.
+    fn stmt(&mut self, stmt: &hir::Stmt<'_>) {
+        match stmt.kind {
+            hir::StmtKind::Block(block)
+            | hir::StmtKind::UncheckedBlock(block)
+            | hir::StmtKind::Loop(block, _) => self.block(block),
+            hir::StmtKind::If(_, then, els) => {
+                for stmt in std::iter::once(then).chain(els) {
+                    if self.contains(stmt) {
+                        self.stmt(stmt);
+                    }
+                }
+            }
+            hir::StmtKind::Try(try_) => {
+                // Blocks and clauses don't have spans, so find the last one that starts before
+                // the position.
+                let clauses = std::iter::once((try_.returns, try_.block))
+                    .chain(try_.catch.iter().map(|clause| (clause.args, clause.block)));
+                let mut current = None;
+                for (vars, block) in clauses {
+                    let start = match vars.first() {
+                        Some(&var) => Some(self.hir.variable(var).span.lo()),
+                        None => block.first().map(|stmt| stmt.span.lo()),
+                    };
+                    if start.is_some_and(|start| start <= self.pos) {
+                        current = Some((vars, block));
+                    }
+                }
+                if let Some((vars, block)) = current {
+                    self.locals.extend(vars);
+                    self.block(block);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze_test_files;
+    use solar_interface::Pos;
+
+    const SRC: &str = "\
+contract Base { uint internal b; function g() internal {} }
+contract C is Base {
+    struct S { uint a; uint[] arr; }
+    enum E { X, Y }
+    uint public x;
+    S s;
+    mapping(uint => S) m;
+    function f(uint p) public returns (uint r) {
+        uint a = 1;
+        {
+            uint inner = 2;
+        }
+        for (uint i = 0; i < 10; i++) {
+            uint x = i;
+            /*A*/
+        }
+        /*B*/
+        s.arr;
+        m[1].a;
+        this.f;
+        super.g;
+        E.X;
+        msg.sender;
+    }
+}
+";
+
+    /// Returns the names returned by `f` at the end of the first occurrence of `at`.
+    fn names(at: &str, f: impl FnOnce(Gcx<'_>, BytePos) -> Vec<Member<'_>> + Send) -> Vec<String> {
+        let mut names = Vec::new();
+        analyze_test_files(&[("a.sol", SRC)], |_, gcx| {
+            let pos = BytePos::from_usize(SRC.find(at).unwrap() + at.len());
+            names = f(gcx, pos).iter().map(|member| member.name.to_string()).collect();
+        });
+        names
+    }
+
+    #[test]
+    fn visible_names() {
+        let names_at = |at| names(at, |gcx, pos| gcx.visible_names(pos));
+
+        let a = names_at("/*A*/");
+        assert_eq!(a[..5], ["x", "i", "a", "r", "p"]);
+        assert_eq!(a.iter().filter(|name| *name == "x").count(), 1);
+        assert!(!a.contains(&"inner".to_string()));
+        for name in ["S", "E", "s", "m", "f", "b", "g", "C", "Base", "msg", "this"] {
+            assert!(a.contains(&name.to_string()), "{name}");
+        }
+
+        let b = names_at("/*B*/");
+        assert_eq!(b[..3], ["a", "r", "p"]);
+        assert!(!b.contains(&"i".to_string()));
+        assert!(b.contains(&"x".to_string()));
+
+        let contract = names_at("contract C is Base {");
+        assert!(!contract.contains(&"p".to_string()));
+        assert!(contract.contains(&"f".to_string()));
+    }
+
+    #[test]
+    fn member_completions() {
+        let completions_at = |at| {
+            let mut names = names(at, |gcx, pos| gcx.completions(pos));
+            names.sort();
+            names
+        };
+        assert_eq!(completions_at("s.ar"), ["a", "arr"]);
+        assert_eq!(completions_at("m[1].a"), ["a", "arr"]);
+        assert_eq!(completions_at("this.f"), ["f", "x"]);
+        assert_eq!(completions_at("super.g"), ["b", "g"]);
+        assert_eq!(completions_at("E.X"), ["X", "Y"]);
+        assert!(completions_at("msg.sender").contains(&"value".to_string()));
+        // Not in a member name.
+        assert!(completions_at("/*B*/\n        s").contains(&"a".to_string()));
+    }
+}
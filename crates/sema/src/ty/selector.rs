@@ -0,0 +1,63 @@
+use super::{Gcx, InterfaceFunction};
+use crate::hir;
+use alloy_primitives::Selector;
+use solar_data_structures::map::FxHashMap;
+
+/// A pair of functions with the same 4-byte selector.
+///
+/// Return type of [`Gcx::selector_collisions`] and [`Gcx::proxy_selector_collisions`].
+#[derive(Clone, Copy, Debug)]
+pub struct SelectorCollision {
+    /// The shared selector.
+    pub selector: Selector,
+    /// The first function.
+    pub first: hir::FunctionId,
+    /// The second function.
+    pub second: hir::FunctionId,
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the pairs of functions in the interface of the given contract, including inherited
+    /// functions, that have different signatures but the same selector.
+    ///
+    /// These are reported as errors when computing [`Gcx::interface_functions`].
+    pub fn selector_collisions(self, id: hir::ContractId) -> Vec<SelectorCollision> {
+        let functions = self.interface_functions(id);
+        let mut collisions = Vec::new();
+        let mut seen = FxHashMap::<Selector, &InterfaceFunction<'_>>::default();
+        for f in functions.iter() {
+            if let Some(prev) = seen.insert(f.selector, f) {
+                collisions.push(SelectorCollision {
+                    selector: f.selector,
+                    first: prev.id,
+                    second: f.id,
+                });
+            }
+        }
+        collisions
+    }
+
+    /// Returns the pairs of external functions of a proxy and an implementation contract that have
+    /// the same selector, as `(proxy function, implementation function)`.
+    ///
+    /// Calls to such implementation functions through the proxy are handled by the proxy instead
+    /// of being forwarded, which includes functions with identical signatures.
+    pub fn proxy_selector_collisions(
+        self,
+        proxy: hir::ContractId,
+        implementation: hir::ContractId,
+    ) -> Vec<SelectorCollision> {
+        let proxy_functions = self
+            .interface_functions(proxy)
+            .iter()
+            .map(|f| (f.selector, f.id))
+            .collect::<FxHashMap<_, _>>();
+        self.interface_functions(implementation)
+            .iter()
+            .filter_map(|f| {
+                let &first = proxy_functions.get(&f.selector)?;
+                Some(SelectorCollision { selector: f.selector, first, second: f.id })
+            })
+            .collect()
+    }
+}
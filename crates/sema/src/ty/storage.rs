@@ -0,0 +1,394 @@
+use super::{Gcx, Ty, TyKind};
+use crate::{eval::ConstantEvaluator, hir};
+use alloy_primitives::{keccak256, B256, U256};
+use serde::Serialize;
+use solar_ast::ast::{ElementaryType, StateMutability, Visibility};
+use std::collections::BTreeMap;
+
+/// The storage layout of a contract.
+///
+/// Reference: <https://docs.soliditylang.org/en/latest/internals/layout_in_storage.html#json-output>
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StorageLayout {
+    /// The state variables, in storage order.
+    pub storage: Vec<StorageLayoutEntry>,
+    /// The types referenced by `storage` and `namespaces`, keyed by their identifier.
+    pub types: BTreeMap<String, StorageLayoutType>,
+    /// The namespaced storage structs declared in the contract and its bases, labeled by their
+    /// storage location.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub namespaces: Vec<StorageLayoutEntry>,
+}
+
+/// A variable in a [`StorageLayout`], or a member of a struct in [`StorageLayoutType::members`].
+#[derive(Clone, Debug, Serialize)]
+pub struct StorageLayoutEntry {
+    /// The fully qualified name of the contract.
+    pub contract: String,
+    /// The name of the variable.
+    pub label: String,
+    /// The offset in bytes within the storage slot.
+    pub offset: u8,
+    /// The storage slot, as a decimal string.
+    pub slot: String,
+    /// The type identifier, which is a key in [`StorageLayout::types`].
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// A type in a [`StorageLayout`].
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageLayoutType {
+    /// The element type of arrays.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// How the data is encoded: `inplace`, `mapping`, `dynamic_array` or `bytes`.
+    pub encoding: &'static str,
+    /// The key type of mappings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// The canonical type name.
+    pub label: String,
+    /// The members of structs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<StorageLayoutEntry>>,
+    /// The number of used bytes, as a decimal string.
+    pub number_of_bytes: String,
+    /// The value type of mappings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl<'gcx> Gcx<'gcx> {
+    /// Returns the storage slot at which the storage of the given contract starts.
+    ///
+    /// This is the value of the `layout at <slot>` specifier, or zero if there is none or it could
+    /// not be evaluated.
+    pub fn storage_base_slot(self, id: hir::ContractId) -> U256 {
+        let Some(slot) = self.hir.contract(id).layout else { return U256::ZERO };
+        ConstantEvaluator::new(self).try_eval(slot).map_or(U256::ZERO, |int| int.data)
+    }
+
+    /// Returns the storage state variables of the given contract, including inherited ones, in
+    /// storage order.
+    ///
+    /// Constants, immutables and transient variables are excluded.
+    pub fn storage_variables(
+        self,
+        id: hir::ContractId,
+    ) -> impl Iterator<Item = hir::VariableId> + use<'gcx> {
+        let bases = self.hir.contract(id).linearized_bases;
+        bases.iter().rev().flat_map(move |&base| {
+            self.hir.contract(base).variables().filter(move |&var| {
+                let var = self.hir.variable(var);
+                var.mutability.is_none() && var.data_location.is_none()
+            })
+        })
+    }
+
+    /// Returns the number of storage slots used by the state variables of the given contract,
+    /// not including the base slot.
+    pub fn contract_storage_size(self, id: hir::ContractId) -> U256 {
+        let tys = self.storage_variables(id).map(|var| self.type_of_item(var.into()));
+        storage_offsets(self, tys).1
+    }
+
+    /// Returns the storage layout of the given contract.
+    pub fn storage_layout(self, id: hir::ContractId) -> StorageLayout {
+        let contract = self.contract_fully_qualified_name(id).to_string();
+        let base_slot = self.storage_base_slot(id);
+        let mut layout = StorageLayout::default();
+        let vars = self.storage_variables(id).collect::<Vec<_>>();
+        let tys = vars.iter().map(|&var| self.type_of_item(var.into()));
+        let (offsets, _) = storage_offsets(self, tys.clone());
+        for ((&var, ty), (slot, offset)) in vars.iter().zip(tys).zip(offsets) {
+            let ty = self.storage_layout_type(&contract, ty, &mut layout.types);
+            layout.storage.push(StorageLayoutEntry {
+                contract: contract.clone(),
+                label: self.item_name(var).to_string(),
+                offset,
+                slot: base_slot.saturating_add(slot).to_string(),
+                ty,
+            });
+        }
+        for (id, slot) in self.namespaced_structs(id) {
+            let location = self.hir.strukt(id).storage_location.unwrap();
+            let ty = self.type_of_item(id.into());
+            let ty = self.storage_layout_type(&contract, ty, &mut layout.types);
+            layout.namespaces.push(StorageLayoutEntry {
+                contract: contract.clone(),
+                label: format!("{}:{}", location.formula, location.id),
+                offset: 0,
+                slot: slot.to_string(),
+                ty,
+            });
+        }
+        layout
+    }
+
+    /// Returns the root storage slot of the given struct if it is annotated with an ERC-7201
+    /// storage location.
+    pub fn struct_storage_slot(self, id: hir::StructId) -> Option<U256> {
+        let location = self.hir.strukt(id).storage_location?;
+        location.is_erc7201().then(|| erc7201_slot(location.id.as_str()).into())
+    }
+
+    /// Returns the structs with an ERC-7201 storage location declared in the given contract and
+    /// its bases, along with their root slots.
+    pub fn namespaced_structs(
+        self,
+        id: hir::ContractId,
+    ) -> impl Iterator<Item = (hir::StructId, U256)> + use<'gcx> {
+        let bases = self.hir.contract(id).linearized_bases;
+        bases.iter().rev().flat_map(move |&base| {
+            self.hir
+                .contract(base)
+                .structs()
+                .filter_map(move |s| Some((s, self.struct_storage_slot(s)?)))
+        })
+    }
+
+    /// Returns the number of bytes the type occupies in a storage slot. This is at most 32.
+    pub fn type_storage_bytes(self, ty: Ty<'gcx>) -> u8 {
+        match ty.kind {
+            TyKind::Elementary(ElementaryType::Address(_)) | TyKind::Contract(_) => 20,
+            TyKind::Elementary(ElementaryType::Bool) | TyKind::Enum(_) => 1,
+            TyKind::Elementary(
+                ElementaryType::Int(size)
+                | ElementaryType::UInt(size)
+                | ElementaryType::FixedBytes(size)
+                | ElementaryType::Fixed(size, _)
+                | ElementaryType::UFixed(size, _),
+            ) => size.bytes(),
+            TyKind::Udvt(ty, _) => self.type_storage_bytes(ty),
+            TyKind::FnPtr(f) => {
+                if f.visibility == Visibility::External {
+                    24
+                } else {
+                    8
+                }
+            }
+            TyKind::Ref(ty, _) => self.type_storage_bytes(ty),
+            _ => 32,
+        }
+    }
+
+    /// Returns the number of storage slots the type occupies.
+    pub fn type_storage_size(self, ty: Ty<'gcx>) -> U256 {
+        match ty.kind {
+            TyKind::Ref(ty, _) => self.type_storage_size(ty),
+            TyKind::Array(elem, len) => {
+                let elem_bytes = self.type_storage_bytes(elem);
+                if elem_bytes <= 16 {
+                    let per_slot = U256::from(32 / elem_bytes);
+                    len.div_ceil(per_slot)
+                } else {
+                    len.saturating_mul(self.type_storage_size(elem))
+                }
+            }
+            TyKind::Struct(id) => {
+                let tys = self.struct_field_types(id).iter().copied();
+                storage_offsets(self, tys).1
+            }
+            _ => U256::from(1),
+        }
+    }
+
+    /// Returns the storage layout identifier of the type, inserting it and its subtypes into
+    /// `types`.
+    fn storage_layout_type(
+        self,
+        contract: &str,
+        ty: Ty<'gcx>,
+        types: &mut BTreeMap<String, StorageLayoutType>,
+    ) -> String {
+        let ty = ty.peel_refs();
+        let id = self.storage_type_identifier(ty, false);
+        if types.contains_key(&id) {
+            return id;
+        }
+
+        let mut base = None;
+        let mut key = None;
+        let mut value = None;
+        let mut members = None;
+        let encoding = match ty.kind {
+            TyKind::Elementary(ElementaryType::String | ElementaryType::Bytes) => "bytes",
+            TyKind::Mapping(k, v) => {
+                key = Some(self.storage_layout_key_type(k, types));
+                value = Some(self.storage_layout_type(contract, v, types));
+                "mapping"
+            }
+            TyKind::DynArray(elem) => {
+                base = Some(self.storage_layout_type(contract, elem, types));
+                "dynamic_array"
+            }
+            TyKind::Array(elem, _) => {
+                base = Some(self.storage_layout_type(contract, elem, types));
+                "inplace"
+            }
+            TyKind::Struct(s) => {
+                // Insert a placeholder first to handle recursive structs.
+                types.insert(id.clone(), self.storage_layout_type_simple(ty, "inplace"));
+                let fields = self.hir.strukt(s).fields;
+                let tys = self.struct_field_types(s);
+                let (offsets, _) = storage_offsets(self, tys.iter().copied());
+                let entries = fields
+                    .iter()
+                    .zip(tys)
+                    .zip(offsets)
+                    .map(|((&field, &ty), (slot, offset))| StorageLayoutEntry {
+                        contract: contract.to_string(),
+                        label: self.item_name(field).to_string(),
+                        offset,
+                        slot: slot.to_string(),
+                        ty: self.storage_layout_type(contract, ty, types),
+                    })
+                    .collect();
+                members = Some(entries);
+                "inplace"
+            }
+            _ => "inplace",
+        };
+        let entry = StorageLayoutType {
+            base,
+            key,
+            value,
+            members,
+            ..self.storage_layout_type_simple(ty, encoding)
+        };
+        types.insert(id.clone(), entry);
+        id
+    }
+
+    /// Returns the storage layout identifier of a mapping key type.
+    ///
+    /// Dynamic mapping keys are not stored, so they are referenced as memory pointers.
+    fn storage_layout_key_type(
+        self,
+        ty: Ty<'gcx>,
+        types: &mut BTreeMap<String, StorageLayoutType>,
+    ) -> String {
+        let id = self.storage_type_identifier(ty, true);
+        if !types.contains_key(&id) {
+            let encoding = if matches!(
+                ty.kind,
+                TyKind::Elementary(ElementaryType::String | ElementaryType::Bytes)
+            ) {
+                "bytes"
+            } else {
+                "inplace"
+            };
+            types.insert(id.clone(), self.storage_layout_type_simple(ty, encoding));
+        }
+        id
+    }
+
+    fn storage_layout_type_simple(self, ty: Ty<'gcx>, encoding: &'static str) -> StorageLayoutType {
+        let number_of_bytes = if self.type_storage_size(ty) == U256::from(1) {
+            U256::from(self.type_storage_bytes(ty))
+        } else {
+            self.type_storage_size(ty).saturating_mul(U256::from(32))
+        };
+        StorageLayoutType {
+            base: None,
+            encoding,
+            key: None,
+            label: self.ty_solc_name(ty),
+            members: None,
+            number_of_bytes: number_of_bytes.to_string(),
+            value: None,
+        }
+    }
+
+    /// Returns the solc type identifier of the given type, e.g. `t_uint256`.
+    ///
+    /// Item IDs are used in place of solc's AST IDs.
+    fn storage_type_identifier(self, ty: Ty<'gcx>, is_key: bool) -> String {
+        let loc = if is_key { "memory_ptr" } else { "storage" };
+        match ty.kind {
+            TyKind::Elementary(ElementaryType::Address(true)) => "t_address_payable".into(),
+            TyKind::Elementary(ElementaryType::String) => format!("t_string_{loc}"),
+            TyKind::Elementary(ElementaryType::Bytes) => format!("t_bytes_{loc}"),
+            TyKind::Elementary(ty) => format!("t_{}", ty.to_abi_str()),
+            TyKind::Contract(id) => format!("t_contract({}){}", self.item_name(id), id.get()),
+            TyKind::Enum(id) => format!("t_enum({}){}", self.item_name(id), id.get()),
+            TyKind::Udvt(_, id) => {
+                format!("t_userDefinedValueType({}){}", self.item_name(id), id.get())
+            }
+            TyKind::Struct(id) => format!("t_struct({}){}_storage", self.item_name(id), id.get()),
+            TyKind::Mapping(k, v) => format!(
+                "t_mapping({},{})",
+                self.storage_type_identifier(k, true),
+                self.storage_type_identifier(v, false)
+            ),
+            TyKind::DynArray(elem) => {
+                format!("t_array({})dyn_storage", self.storage_type_identifier(elem, false))
+            }
+            TyKind::Array(elem, len) => {
+                format!("t_array({}){len}_storage", self.storage_type_identifier(elem, false))
+            }
+            TyKind::FnPtr(f) => {
+                let visibility =
+                    if f.visibility == Visibility::External { "external" } else { "internal" };
+                let state_mutability = match f.state_mutability {
+                    StateMutability::Pure => "pure",
+                    StateMutability::View => "view",
+                    StateMutability::NonPayable => "nonpayable",
+                    StateMutability::Payable => "payable",
+                };
+                format!("t_function_{visibility}_{state_mutability}")
+            }
+            TyKind::Ref(ty, _) => self.storage_type_identifier(ty, is_key),
+            _ => format!("t_{ty:?}"),
+        }
+    }
+}
+
+/// Computes the root slot of an ERC-7201 namespace:
+/// `keccak256(abi.encode(uint256(keccak256(id)) - 1)) & ~bytes32(uint256(0xff))`.
+///
+/// Reference: <https://eips.ethereum.org/EIPS/eip-7201#formula>
+pub fn erc7201_slot(id: &str) -> B256 {
+    let location = U256::from_be_bytes(keccak256(id).0).wrapping_sub(U256::from(1));
+    let mut slot = keccak256(location.to_be_bytes::<32>());
+    slot[31] = 0;
+    slot
+}
+
+/// Computes the `(slot, offset)` of each type when laid out consecutively in storage, and the
+/// total number of slots used.
+///
+/// Reference: <https://github.com/ethereum/solidity/blob/9d7cc42bc1c12bb43e9dccf8c6c36833fdfcbbca/libsolidity/ast/Types.cpp#L118>
+fn storage_offsets<'gcx>(
+    gcx: Gcx<'gcx>,
+    tys: impl Iterator<Item = Ty<'gcx>>,
+) -> (Vec<(U256, u8)>, U256) {
+    let mut offsets = Vec::with_capacity(tys.size_hint().0);
+    let mut slot = U256::ZERO;
+    let mut offset = 0u8;
+    for ty in tys {
+        let bytes = gcx.type_storage_bytes(ty);
+        if offset as usize + bytes as usize > 32 {
+            slot = slot.saturating_add(U256::from(1));
+            offset = 0;
+        }
+        offsets.push((slot, offset));
+        let size = gcx.type_storage_size(ty);
+        if size == U256::from(1) && offset as usize + bytes as usize <= 32 {
+            offset += bytes;
+        } else {
+            slot = slot.saturating_add(size);
+            offset = 0;
+        }
+        if offset >= 32 {
+            slot = slot.saturating_add(U256::from(1));
+            offset = 0;
+        }
+    }
+    if offset > 0 {
+        slot = slot.saturating_add(U256::from(1));
+    }
+    (offsets, slot)
+}
@@ -0,0 +1,114 @@
+use super::{resolver::ExprResolver, Gcx};
+use crate::hir::{self, Visit};
+use solar_data_structures::{map::FxIndexSet, Never};
+use std::ops::ControlFlow;
+
+/// The events and errors used by the code of a contract.
+///
+/// Return type of [`Gcx::contract_used_items`].
+#[derive(Clone, Copy, Debug)]
+pub struct UsedItems<'gcx> {
+    /// The events used in `emit` statements.
+    pub events: &'gcx [hir::EventId],
+    /// The errors used in `revert` statements and calls, like in `require(cond, Error())`.
+    pub errors: &'gcx [hir::ErrorId],
+}
+
+pub(super) fn used_items(gcx: Gcx<'_>, id: hir::ContractId) -> UsedItems<'_> {
+    let mut collector = UsedItemsCollector {
+        gcx,
+        events: FxIndexSet::default(),
+        errors: FxIndexSet::default(),
+        functions: FxIndexSet::default(),
+        source: gcx.hir.contract(id).source,
+        contract: None,
+    };
+    for &base in gcx.hir.contract(id).linearized_bases {
+        let c = gcx.hir.contract(base);
+        for var in c.variables() {
+            let _ = collector.visit_nested_var(var);
+        }
+        collector.functions.extend(c.functions());
+    }
+    // Visiting a function may add new functions to the set.
+    let mut i = 0;
+    while let Some(&f) = collector.functions.get_index(i) {
+        let _ = collector.visit_nested_function(f);
+        i += 1;
+    }
+
+    UsedItems {
+        events: gcx.bump().alloc_slice_copy(&collector.events.into_iter().collect::<Vec<_>>()),
+        errors: gcx.bump().alloc_slice_copy(&collector.errors.into_iter().collect::<Vec<_>>()),
+    }
+}
+
+/// Collects the events and errors used in the functions reachable from a contract.
+///
+/// External calls are not followed.
+struct UsedItemsCollector<'gcx> {
+    gcx: Gcx<'gcx>,
+    events: FxIndexSet<hir::EventId>,
+    errors: FxIndexSet<hir::ErrorId>,
+    /// The reachable functions, in visiting order.
+    functions: FxIndexSet<hir::FunctionId>,
+    /// The source and contract of the code being visited, which determine the `using for`
+    /// directives that apply.
+    source: hir::SourceId,
+    contract: Option<hir::ContractId>,
+}
+
+impl<'gcx> Visit<'gcx> for UsedItemsCollector<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_var(&mut self, var: &'gcx hir::Variable<'gcx>) -> ControlFlow<Self::BreakValue> {
+        (self.source, self.contract) = (var.source, var.contract);
+        self.walk_var(var)
+    }
+
+    fn visit_function(&mut self, func: &'gcx hir::Function<'gcx>) -> ControlFlow<Self::BreakValue> {
+        (self.source, self.contract) = (func.source, func.contract);
+        for &modifier in func.modifiers {
+            if let hir::ItemId::Function(modifier) = modifier {
+                self.functions.insert(modifier);
+            }
+        }
+        self.walk_function(func)
+    }
+
+    fn visit_stmt(&mut self, stmt: &'gcx hir::Stmt<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match stmt.kind {
+            hir::StmtKind::Emit(res, _) => {
+                self.events.extend(res.iter().filter_map(|&res| hir::EventId::try_from(res).ok()));
+            }
+            hir::StmtKind::Revert(res, _) => {
+                self.errors.extend(res.iter().filter_map(|&res| hir::ErrorId::try_from(res).ok()));
+            }
+            _ => {}
+        }
+        self.walk_stmt(stmt)
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let hir::ExprKind::Call(callee, ref args) = expr.kind {
+            let resolver = ExprResolver::new(self.gcx, self.source, self.contract);
+            let callee = resolver.callee(callee, args.len()).filter(|callee| !callee.external);
+            for item in callee.map(|callee| callee.items).unwrap_or_default() {
+                match item {
+                    hir::ItemId::Function(f) => {
+                        self.functions.insert(f);
+                    }
+                    hir::ItemId::Error(e) => {
+                        self.errors.insert(e);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.walk_expr(expr)
+    }
+}
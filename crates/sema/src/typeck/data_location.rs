@@ -0,0 +1,236 @@
+//! Data location checks.
+//!
+//! Reference: <https://github.com/ethereum/solidity/blob/9d7cc42bc1c12bb43e9dccf8c6c36833fdfcbbca/libsolidity/analysis/DeclarationTypeChecker.cpp#L350>
+
+use crate::{
+    hir,
+    ty::{Gcx, Ty, TyKind},
+};
+use solar_ast::ast::{DataLocation, ElementaryType, Visibility};
+
+/// Checks the data locations of the parameters, returns and local variables of a function.
+pub(super) fn check_function(gcx: Gcx<'_>, id: hir::FunctionId) {
+    let f = gcx.hir.function(id);
+    if f.is_getter() {
+        return;
+    }
+
+    let is_internal = f.visibility <= Visibility::Internal || f.kind.is_modifier() || f.is_free();
+    let is_library = f.contract.is_some_and(|c| gcx.hir.contract(c).kind.is_library());
+    for (vars, is_return) in [(f.parameters, false), (f.returns, true)] {
+        let cx = if f.kind.is_constructor() {
+            let is_abstract = f.contract.is_some_and(|c| gcx.hir.contract(c).is_abstract());
+            VarContext::Constructor { storage: is_abstract }
+        } else {
+            VarContext::Callable {
+                external: f.visibility == Visibility::External,
+                storage: is_internal || is_library,
+                is_return,
+            }
+        };
+        for &var in vars {
+            check_variable(gcx, var, cx);
+        }
+    }
+
+    if let Some(body) = f.body {
+        check_block(gcx, body);
+    }
+}
+
+/// Checks that `transient` is only used on mutable state variables of value types.
+pub(super) fn check_state_variable(gcx: Gcx<'_>, id: hir::VariableId) {
+    let var = gcx.hir.variable(id);
+    if !var.is_state_variable() || var.data_location != Some(DataLocation::Transient) {
+        return;
+    }
+
+    let ty = gcx.type_of_hir_ty(&var.ty);
+    let msg = if var.mutability.is_some() {
+        "transient cannot be used as data location for constant or immutable variables"
+    } else if !ty.is_value_type() && !ty.has_error() {
+        "transient storage is not yet implemented for reference types"
+    } else {
+        return;
+    };
+    gcx.dcx().err(msg).span(var.span).emit();
+}
+
+/// The context a variable is declared in, which determines its allowed data locations.
+#[derive(Clone, Copy)]
+enum VarContext {
+    /// A constructor parameter.
+    Constructor {
+        /// Whether `storage` is allowed, i.e. the contract is abstract.
+        storage: bool,
+    },
+    /// A function or modifier parameter or return variable.
+    Callable {
+        /// Whether the function is external.
+        external: bool,
+        /// Whether `storage` is allowed, i.e. the function is internal or in a library.
+        storage: bool,
+        /// Whether the variable is a return variable.
+        is_return: bool,
+    },
+    /// A `try` return variable or a `catch` parameter.
+    TryCatch,
+    /// A local variable.
+    Local,
+}
+
+impl VarContext {
+    fn allowed(self) -> &'static [DataLocation] {
+        use DataLocation::*;
+        match self {
+            Self::Constructor { storage: true } => &[Storage, Memory],
+            Self::Constructor { storage: false } => &[Memory],
+            Self::Callable { storage: true, .. } | Self::Local => &[Storage, Memory, Calldata],
+            Self::Callable { storage: false, .. } => &[Memory, Calldata],
+            Self::TryCatch => &[Memory],
+        }
+    }
+
+    fn is_parameter(self) -> bool {
+        !matches!(self, Self::Local)
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Constructor { .. } => "constructor parameter",
+            Self::Callable { external: true, is_return: false, .. } => {
+                "parameter in external function"
+            }
+            Self::Callable { is_return: true, .. } => "return parameter in function",
+            Self::Callable { .. } | Self::TryCatch => "parameter in function",
+            Self::Local => "variable",
+        }
+    }
+}
+
+fn check_block(gcx: Gcx<'_>, block: hir::Block<'_>) {
+    for stmt in block {
+        check_stmt(gcx, stmt);
+    }
+}
+
+fn check_stmt(gcx: Gcx<'_>, stmt: &hir::Stmt<'_>) {
+    match stmt.kind {
+        hir::StmtKind::DeclSingle(var) => check_variable(gcx, var, VarContext::Local),
+        hir::StmtKind::DeclMulti(vars, _) => {
+            for &var in vars.iter().flatten() {
+                check_variable(gcx, var, VarContext::Local);
+            }
+        }
+        hir::StmtKind::Block(block)
+        | hir::StmtKind::UncheckedBlock(block)
+        | hir::StmtKind::Loop(block, _) => check_block(gcx, block),
+        hir::StmtKind::If(_, then, else_) => {
+            check_stmt(gcx, then);
+            if let Some(else_) = else_ {
+                check_stmt(gcx, else_);
+            }
+        }
+        hir::StmtKind::Try(try_) => {
+            for &var in try_.returns {
+                check_variable(gcx, var, VarContext::TryCatch);
+            }
+            check_block(gcx, try_.block);
+            for catch in try_.catch {
+                for &var in catch.args {
+                    check_variable(gcx, var, VarContext::TryCatch);
+                }
+                check_block(gcx, catch.block);
+            }
+        }
+        hir::StmtKind::Emit(..)
+        | hir::StmtKind::Revert(..)
+        | hir::StmtKind::Return(_)
+        | hir::StmtKind::Break
+        | hir::StmtKind::Continue
+        | hir::StmtKind::Expr(_)
+        | hir::StmtKind::Placeholder
+        | hir::StmtKind::Err(_) => {}
+    }
+}
+
+fn check_variable(gcx: Gcx<'_>, id: hir::VariableId, cx: VarContext) {
+    let var = gcx.hir.variable(id);
+    let ty = gcx.type_of_hir_ty(&var.ty);
+    if ty.has_error() {
+        return;
+    }
+
+    let loc = var.data_location;
+    let fmt_loc = |loc: Option<DataLocation>| match loc {
+        Some(loc) => format!("\"{loc}\""),
+        None => "none".to_string(),
+    };
+    if !is_reference_or_mapping(ty) {
+        if loc.is_some() {
+            let msg = format!(
+                "data location can only be specified for array, struct or mapping types, \
+                 but {} was given",
+                fmt_loc(loc)
+            );
+            gcx.dcx().err(msg).span(var.span).emit();
+        }
+        return;
+    }
+
+    let allowed = cx.allowed();
+    let Some(loc) = loc.filter(|loc| allowed.contains(loc)) else {
+        let msg = format!(
+            "data location must be {} for {}, but {} was given",
+            join_human_readable(allowed.iter().map(|&loc| fmt_loc(Some(loc)))),
+            cx.description(),
+            fmt_loc(loc)
+        );
+        gcx.dcx().err(msg).span(var.span).emit();
+        return;
+    };
+
+    if loc == DataLocation::Storage {
+        return;
+    }
+    if let TyKind::Mapping(..) = ty.kind {
+        let msg = if cx.is_parameter() {
+            "mapping types can only have a data location of \"storage\" and thus only be \
+             parameters or return variables for internal or library functions"
+        } else {
+            "mapping types can only have a data location of \"storage\""
+        };
+        gcx.dcx().err(msg).span(var.span).emit();
+    } else if ty.has_mapping() {
+        let msg = format!(
+            "type {} is only valid in storage because it contains a (nested) mapping",
+            gcx.ty_solc_name(ty)
+        );
+        gcx.dcx().err(msg).span(var.span).emit();
+    }
+}
+
+/// Returns `true` if the type is a reference type or a mapping, i.e. it requires a data location.
+fn is_reference_or_mapping(ty: Ty<'_>) -> bool {
+    matches!(
+        ty.kind,
+        TyKind::Elementary(ElementaryType::String | ElementaryType::Bytes)
+            | TyKind::DynArray(_)
+            | TyKind::Array(..)
+            | TyKind::Struct(_)
+            | TyKind::Mapping(..)
+    )
+}
+
+/// Joins the items as `a, b or c`.
+fn join_human_readable(items: impl ExactSizeIterator<Item = String>) -> String {
+    let len = items.len();
+    let mut s = String::new();
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            s.push_str(if i == len - 1 { " or " } else { ", " });
+        }
+        s.push_str(&item);
+    }
+    s
+}
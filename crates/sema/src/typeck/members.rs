@@ -0,0 +1,62 @@
+//! Member access checks.
+
+use crate::{
+    ast_lowering::resolve::similar_declaration_help,
+    hir::{self, Visit},
+    ty::Gcx,
+};
+use solar_data_structures::Never;
+use solar_interface::edit_distance::find_best_match_for_name;
+use std::ops::ControlFlow;
+
+/// Checks that the members accessed in the code of a source exist, suggesting similar names for
+/// the ones that don't.
+///
+/// Only the member accesses whose base has members that can be determined from declarations are
+/// checked.
+pub(super) fn check_source(gcx: Gcx<'_>, id: hir::SourceId) {
+    let mut checker = MemberChecker { gcx, source: id, contract: None };
+    let _ = checker.visit_nested_source(id);
+}
+
+struct MemberChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+    contract: Option<hir::ContractId>,
+}
+
+impl<'gcx> Visit<'gcx> for MemberChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_nested_contract(&mut self, id: hir::ContractId) -> ControlFlow<Self::BreakValue> {
+        let prev = self.contract.replace(id);
+        let r = self.visit_contract(self.gcx.hir.contract(id));
+        self.contract = prev;
+        r
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let hir::ExprKind::Member(base, member) = expr.kind {
+            let gcx = self.gcx;
+            if let Some(members) = gcx.missing_member(base, member, self.source, self.contract) {
+                let base = gcx.sess.source_map().span_to_snippet(base.span).unwrap_or_default();
+                let msg = format!("member `{member}` not found in `{base}`");
+                let mut err = gcx.dcx().err(msg).span(member.span);
+                let names = members.iter().map(|m| m.name).collect::<Vec<_>>();
+                if let Some(similar) = find_best_match_for_name(&names, member.name, None) {
+                    let res = members.iter().find(|m| m.name == similar).and_then(|m| m.res);
+                    err = err.help(match res {
+                        Some(res) => similar_declaration_help(similar, res),
+                        None => format!("a member with a similar name exists: `{similar}`"),
+                    });
+                }
+                err.emit();
+            }
+        }
+        self.walk_expr(expr)
+    }
+}
@@ -1,21 +1,224 @@
 use crate::{
     ast_lowering::resolve::{Declaration, Declarations},
+    eval::{ConstValue, ConstantEvaluator},
     hir::{self, Res},
     ty::{Gcx, Ty},
 };
+use alloy_primitives::{B256, U256};
 use rayon::prelude::*;
+use solar_ast::ast::{ElementaryType, TypeSize};
 use solar_data_structures::{map::FxHashSet, parallel};
 
+mod data_location;
+mod members;
+
 pub(crate) fn check(gcx: Gcx<'_>) {
     parallel!(
         gcx.sess,
         gcx.hir.par_contract_ids().for_each(|id| {
             check_duplicate_definitions(gcx, &gcx.symbol_resolver.contract_scopes[id]);
+            check_storage_layout_specifier(gcx, id);
         }),
         gcx.hir.par_source_ids().for_each(|id| {
             check_duplicate_definitions(gcx, &gcx.symbol_resolver.source_scopes[id]);
+            members::check_source(gcx, id);
+        }),
+        gcx.hir.par_function_ids().for_each(|id| {
+            data_location::check_function(gcx, id);
+        }),
+        gcx.hir.par_strukt_ids().for_each(|id| {
+            check_storage_location(gcx, id);
+        }),
+        gcx.hir.par_variable_ids().for_each(|id| {
+            check_constant_value(gcx, id);
+            data_location::check_state_variable(gcx, id);
         }),
     );
+    check_proxy_selectors(gcx);
+}
+
+/// Checks the proxy and implementation pairs passed with `--proxy` for function selector
+/// collisions.
+fn check_proxy_selectors(gcx: Gcx<'_>) {
+    for pair in &gcx.sess.proxies {
+        let (Some(proxy), Some(implementation)) =
+            (find_contract(gcx, &pair.proxy), find_contract(gcx, &pair.implementation))
+        else {
+            continue;
+        };
+        for collision in gcx.proxy_selector_collisions(proxy, implementation) {
+            let proxy_sig = gcx.item_signature(collision.first.into());
+            let impl_sig = gcx.item_signature(collision.second.into());
+            let msg = if proxy_sig == impl_sig {
+                format!("implementation function `{impl_sig}` is shadowed by the proxy")
+            } else {
+                format!(
+                    "proxy function `{proxy_sig}` and implementation function `{impl_sig}` \
+                     produce the same 4-byte selector `{}`",
+                    collision.selector
+                )
+            };
+            let note = "implementation function declared here";
+            let help =
+                "calls to this selector through the proxy are not forwarded to the implementation";
+            gcx.dcx()
+                .warn(msg)
+                .span(gcx.item_name(collision.first).span)
+                .span_note(gcx.item_name(collision.second).span, note)
+                .help(help)
+                .emit();
+        }
+    }
+}
+
+/// Finds a contract by name or fully qualified name, emitting an error if it is not found or is
+/// ambiguous.
+fn find_contract(gcx: Gcx<'_>, name: &str) -> Option<hir::ContractId> {
+    let candidates = gcx
+        .hir
+        .contract_ids()
+        .filter(|&id| {
+            gcx.hir.contract(id).name.as_str() == name
+                || gcx.contract_fully_qualified_name(id).to_string() == name
+        })
+        .collect::<Vec<_>>();
+    match candidates[..] {
+        [id] => Some(id),
+        [] => {
+            gcx.dcx().err(format!("contract `{name}` not found")).emit();
+            None
+        }
+        _ => {
+            let msg = format!("contract name `{name}` is ambiguous");
+            let help = "use the fully qualified name of the contract: `<path>:<name>`";
+            gcx.dcx().err(msg).help(help).emit();
+            None
+        }
+    }
+}
+
+/// Checks that the value of a constant of an elementary type can be evaluated and fits in the
+/// declared type.
+///
+/// Initializers that the constant evaluator does not support are skipped.
+fn check_constant_value(gcx: Gcx<'_>, id: hir::VariableId) {
+    let var = gcx.hir.variable(id);
+    let (Some(hir::VarMut::Constant), Some(init), hir::TypeKind::Elementary(ty)) =
+        (var.mutability, var.initializer, &var.ty.kind)
+    else {
+        return;
+    };
+    let mut evaluator = ConstantEvaluator::new(gcx);
+    if let Err(err) = evaluator.try_eval_as(init, *ty) {
+        // Errors in referenced constants are reported on their own declaration.
+        if !err.kind.is_unsupported() && (err.span.is_dummy() || init.span.contains(err.span)) {
+            evaluator.emit_eval_error(init, err);
+        }
+    }
+}
+
+/// Checks the storage layout specifier of a contract: `layout at <slot>`.
+///
+/// The specifier is only allowed on the most derived, non-abstract contract, and the base slot must
+/// be a constant that leaves enough room for all the state variables.
+fn check_storage_layout_specifier(gcx: Gcx<'_>, id: hir::ContractId) {
+    let c = gcx.hir.contract(id);
+    if let Some(base_slot) =
+        c.linearized_bases[1..].iter().find_map(|&b| gcx.hir.contract(b).layout)
+    {
+        let msg = "storage layout can only be specified in the most derived contract";
+        let note = "storage layout was already specified here";
+        gcx.dcx().err(msg).span(c.name.span).span_note(base_slot.span, note).emit();
+    }
+
+    let Some(slot) = c.layout else { return };
+    if !c.kind.is_contract() {
+        let msg = if c.kind.is_abstract_contract() {
+            "storage layout cannot be specified for abstract contracts"
+        } else {
+            "storage layout can only be specified for contracts"
+        };
+        gcx.dcx().err(msg).span(slot.span).emit();
+        return;
+    }
+
+    let mut evaluator = ConstantEvaluator::new(gcx);
+    let base_slot = match evaluator.try_eval(slot) {
+        Ok(int) => int.data,
+        Err(err) if err.kind.is_unsupported() => {
+            let msg =
+                "the base slot of the storage layout must be a compile-time constant expression";
+            gcx.dcx().err(msg).span(slot.span).emit();
+            return;
+        }
+        Err(err) => {
+            evaluator.emit_eval_error(slot, err);
+            return;
+        }
+    };
+    let size = gcx.contract_storage_size(id);
+    if !size.is_zero() && base_slot.checked_add(size - U256::from(1)).is_none() {
+        let msg = "contract extends past the end of storage when this base slot value is specified";
+        gcx.dcx().err(msg).span(slot.span).emit();
+    }
+}
+
+/// Checks that the `bytes32` location constants of a struct with an ERC-7201 storage location
+/// match the computed root slot of its namespace.
+///
+/// A constant declared in the same scope as the struct is a location constant if its name, ignoring
+/// case and underscores, is the struct name followed by `location`, like `MyStorageLocation` or
+/// `MY_STORAGE_LOCATION` for `MyStorage`. If the struct is the only namespaced struct in its
+/// scope, any constant whose name ends with `location` is considered.
+///
+/// Initializers that the constant evaluator does not support are skipped.
+fn check_storage_location(gcx: Gcx<'_>, id: hir::StructId) {
+    let Some(expected) = gcx.struct_storage_slot(id) else { return };
+    let strukt = gcx.hir.strukt(id);
+    let location = strukt.storage_location.unwrap();
+    let items = match strukt.contract {
+        Some(c) => gcx.hir.contract(c).items,
+        None => gcx.hir.source(strukt.source).items,
+    };
+
+    let normalize = |s: &str| s.replace('_', "").to_lowercase();
+    let only_namespace = items
+        .iter()
+        .filter_map(hir::ItemId::as_struct)
+        .all(|s| s == id || gcx.struct_storage_slot(s).is_none());
+    let name = normalize(strukt.name.as_str()) + "location";
+    for var in items.iter().filter_map(hir::ItemId::as_variable) {
+        let var = gcx.hir.variable(var);
+        let (Some(hir::VarMut::Constant), Some(init), Some(var_name)) =
+            (var.mutability, var.initializer, var.name)
+        else {
+            continue;
+        };
+        let bytes32 = ElementaryType::FixedBytes(TypeSize::new_fb_bytes(32));
+        if !matches!(var.ty.kind, hir::TypeKind::Elementary(ty) if ty == bytes32) {
+            continue;
+        }
+        let var_name = normalize(var_name.as_str());
+        if !(var_name == name || (only_namespace && var_name.ends_with("location"))) {
+            continue;
+        }
+
+        let Ok(ConstValue::Int(value, _)) = ConstantEvaluator::new(gcx).try_eval_as(init, bytes32)
+        else {
+            continue;
+        };
+        if U256::from_be_slice(&value.to_bytes_be().1) == expected {
+            continue;
+        }
+        let msg = format!(
+            "storage location constant does not match the namespace of struct `{}`",
+            strukt.name
+        );
+        let note = format!("namespace `{}:{}` is declared here", location.formula, location.id);
+        let help =
+            format!("the ERC-7201 root slot of this namespace is `{}`", B256::from(expected));
+        gcx.dcx().err(msg).span(init.span).span_note(location.span, note).help(help).emit();
+    }
 }
 
 /// Checks for definitions that have the same name and parameter types in the given scope.
@@ -0,0 +1,118 @@
+use solar::{
+    ast::{ast, visit::Visit},
+    interface::{
+        source_map::{FileName, Rewriter},
+        Session,
+    },
+    parse::Parser,
+};
+
+/// Migrates `require(cond, "message")` to `if (!(cond)) revert Message();`, declaring the errors
+/// before the contract.
+struct RequireToCustomError<'a, 'sm> {
+    rewriter: &'a mut Rewriter<'sm>,
+    errors: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for RequireToCustomError<'_, '_> {
+    fn visit_item(&mut self, item: &'ast ast::Item<'ast>) {
+        let n_errors = self.errors.len();
+        self.walk_item(item);
+        if matches!(item.kind, ast::ItemKind::Contract(_)) {
+            for error in &self.errors[n_errors..] {
+                self.rewriter.insert_before(item.span, format!("error {error}();\n"));
+            }
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast ast::Stmt<'ast>) {
+        if let ast::StmtKind::Expr(expr) = &stmt.kind {
+            if let ast::ExprKind::Call(callee, ast::CallArgs::Unnamed([cond, msg])) = &expr.kind {
+                if let (ast::ExprKind::Ident(ident), ast::ExprKind::Lit(lit, _)) =
+                    (&callee.kind, &msg.kind)
+                {
+                    if ident.as_str() == "require" {
+                        let ast::LitKind::Str(_, msg) = &lit.kind else { unreachable!() };
+                        let error = String::from_utf8_lossy(msg)
+                            .split(' ')
+                            .map(|word| word[..1].to_uppercase() + &word[1..])
+                            .collect::<String>();
+                        // Edits refer to the original source, so the condition is copied as is.
+                        let cond = self.rewriter.snippet(cond.span).unwrap();
+                        self.rewriter
+                            .replace(stmt.span, format!("if (!({cond})) revert {error}();"));
+                        self.errors.push(error);
+                    }
+                }
+            }
+        }
+        self.walk_stmt(stmt);
+    }
+}
+
+#[test]
+fn main() {
+    let src = "\
+contract C {
+    address owner;
+
+    function f(uint x) public {
+        require(msg.sender == owner, \"not owner\");
+        require(x > 0, \"zero amount\");
+        x += 1;
+    }
+}
+";
+    let sess = Session::builder().with_test_emitter().build();
+    sess.enter(|| {
+        // Parse the source code.
+        let arena = ast::Arena::new();
+        let name = FileName::Custom("test.sol".into());
+        let mut parser = Parser::from_source_code(&sess, &arena, name, src.to_string()).unwrap();
+        let source_unit = parser.parse_file().map_err(|e| e.emit()).unwrap();
+
+        // Record the edits with a visitor, and apply them to the source files.
+        let mut rewriter = Rewriter::new(sess.source_map());
+        let mut codemod = RequireToCustomError { rewriter: &mut rewriter, errors: Vec::new() };
+        codemod.visit_source_unit(&source_unit);
+        let patched = rewriter.apply().unwrap();
+        assert_eq!(patched.len(), 1);
+        assert_eq!(
+            patched[0].src,
+            "\
+error NotOwner();
+error ZeroAmount();
+contract C {
+    address owner;
+
+    function f(uint x) public {
+        if (!(msg.sender == owner)) revert NotOwner();
+        if (!(x > 0)) revert ZeroAmount();
+        x += 1;
+    }
+}
+"
+        );
+        assert_eq!(
+            patched[0].unified_diff(),
+            "\
+--- a/<test.sol>
++++ b/<test.sol>
+@@ -1,9 +1,11 @@
++error NotOwner();
++error ZeroAmount();
+ contract C {
+     address owner;
+ 
+     function f(uint x) public {
+-        require(msg.sender == owner, \"not owner\");
+-        require(x > 0, \"zero amount\");
++        if (!(msg.sender == owner)) revert NotOwner();
++        if (!(x > 0)) revert ZeroAmount();
+         x += 1;
+     }
+ }
+"
+        );
+    });
+}
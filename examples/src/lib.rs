@@ -3,4 +3,5 @@
 #![allow(unreachable_pub)]
 #![cfg(test)]
 
+mod codemod;
 mod parser;
@@ -0,0 +1,5 @@
+event AuxEvent(string s);
+
+function emitAux() {
+    emit AuxEvent("aux");
+}
@@ -0,0 +1,70 @@
+//@compile-flags: --emit=abi --pretty-json
+
+import * as Ns from "./auxiliary/referenced_items_aux.sol";
+
+event FreeEvent(uint256 x);
+error FreeError();
+error UnusedFreeError();
+
+function freeHelper() {
+    revert FreeError();
+}
+
+interface IEvents {
+    event InterfaceEvent(address indexed a);
+    error InterfaceError(uint256 code);
+}
+
+library Lib {
+    event LibEvent();
+    error LibError(bytes32 key);
+
+    function check(bool ok) internal {
+        if (!ok) revert LibError(0);
+        emit LibEvent();
+    }
+
+    function unused() internal {
+        emit IEvents.InterfaceEvent(address(0));
+    }
+}
+
+abstract contract Base {
+    event BaseEvent();
+    error BaseError();
+
+    modifier onlyOk() {
+        require(msg.sender != address(0), IEvents.InterfaceError(1));
+        _;
+    }
+}
+
+contract C is Base {
+    // Same signature as `FreeEvent`; only one of them is included.
+    event FreeEvent(uint256 x);
+
+    function f() external onlyOk {
+        Lib.check(true);
+        freeHelper();
+        emit FreeEvent(1);
+        Ns.emitAux();
+    }
+}
+
+library SafeLib {
+    event SafeLibEvent();
+    error SafeLibFailed(address target);
+
+    function safeDo(address target) internal {
+        if (target == address(0)) revert SafeLibFailed(target);
+        emit SafeLibEvent();
+    }
+}
+
+contract D {
+    using SafeLib for address;
+
+    function f(address target) external {
+        target.safeDo();
+    }
+}
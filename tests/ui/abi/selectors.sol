@@ -0,0 +1,57 @@
+//@compile-flags: --emit=selectors --pretty-json
+
+interface IERC20 {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    error InsufficientBalance(uint256 available, uint256 required);
+
+    function totalSupply() external view returns (uint256);
+    function balanceOf(address account) external view returns (uint256);
+    function transfer(address to, uint256 value) external returns (bool);
+    function allowance(address owner, address spender) external view returns (uint256);
+    function approve(address spender, uint256 value) external returns (bool);
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+}
+
+interface IERC165 {
+    function supportsInterface(bytes4 interfaceId) external view returns (bool);
+}
+
+struct Point {
+    uint256 x;
+    uint256 y;
+}
+
+error Unauthorized(address caller);
+
+contract Token is IERC20 {
+    event Anon(uint256 indexed x) anonymous;
+    event Moved(Point from, Point to);
+
+    function totalSupply() external pure returns (uint256) {
+        return 0;
+    }
+
+    function balanceOf(address) external pure returns (uint256) {
+        return 0;
+    }
+
+    function transfer(address, uint256) external returns (bool) {
+        emit Moved(Point(0, 0), Point(1, 1));
+        revert Unauthorized(msg.sender);
+    }
+
+    function allowance(address, address) external pure returns (uint256) {
+        return 0;
+    }
+
+    function approve(address, uint256) external returns (bool) {
+        emit Anon(1);
+        return true;
+    }
+
+    function transferFrom(address, address, uint256) external pure returns (bool) {
+        return false;
+    }
+}
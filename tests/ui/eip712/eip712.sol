@@ -0,0 +1,46 @@
+//@compile-flags: --emit=eip712 --pretty-json
+
+struct Person {
+    string name;
+    address wallet;
+}
+
+contract Mailbox {
+    enum Priority {
+        Low,
+        High
+    }
+
+    type Amount is uint128;
+
+    // Example from EIP-712.
+    struct Mail {
+        Person from;
+        Person to;
+        string contents;
+    }
+
+    struct Batch {
+        Mail[] mails;
+        Mailbox sender;
+        Priority priority;
+        Amount fee;
+        bytes32[2] salts;
+        Attachment attachment;
+    }
+
+    struct Attachment {
+        bytes data;
+        Person owner;
+    }
+
+    struct Node {
+        uint256 value;
+        Node[] children;
+    }
+
+    // Mappings cannot be encoded.
+    struct Balances {
+        mapping(address => uint256) balances;
+    }
+}
@@ -0,0 +1,64 @@
+//@compile-flags: --emit=inheritance-graph,call-graph --pretty-json
+
+interface IToken {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+
+library Math {
+    function max(uint256 a, uint256 b) internal pure returns (uint256) {
+        return a > b ? a : b;
+    }
+}
+
+function clamp(uint256 x) pure returns (uint256) {
+    return Math.max(x, 1);
+}
+
+using {clamp} for uint256;
+
+abstract contract Owned {
+    address owner;
+
+    constructor(address owner_) {
+        owner = owner_;
+    }
+
+    modifier onlyOwner() {
+        check();
+        _;
+    }
+
+    function check() internal view virtual {
+        require(msg.sender == owner);
+    }
+}
+
+contract Vault is Owned {
+    using Math for uint256;
+
+    IToken token;
+
+    constructor(IToken token_) Owned(msg.sender) {
+        token = token_;
+    }
+
+    function check() internal view override {
+        super.check();
+    }
+
+    function withdraw(address to, uint256 amount) external onlyOwner {
+        token.transfer(to, clamp(amount));
+        IToken(address(token)).transfer(to, Math.max(amount, 2));
+        this.balance();
+    }
+
+    function balance() external view returns (uint256) {}
+
+    function cap(uint256 amount) external pure returns (uint256) {
+        return amount.max(3) + amount.clamp();
+    }
+
+    function clone() external returns (Vault) {
+        return new Vault(token);
+    }
+}
@@ -0,0 +1,25 @@
+//@compile-flags: --emit=inheritance-graph,call-graph --graph-format=dot
+
+interface I {
+    function f() external;
+}
+
+library L {
+    function g() internal pure {}
+}
+
+function h() pure {}
+
+abstract contract A {
+    modifier m() {
+        _;
+    }
+}
+
+contract C is A {
+    function f(I i) external m {
+        i.f();
+        L.g();
+        h();
+    }
+}
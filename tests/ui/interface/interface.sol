@@ -0,0 +1,65 @@
+//@compile-flags: --emit=interface --pretty-json
+// SPDX-License-Identifier: MIT
+
+type Price is uint128;
+
+enum Side {
+    Buy,
+    Sell
+}
+
+struct Order {
+    address maker;
+    Side side;
+    Price price;
+    Fill[] fills;
+}
+
+struct Fill {
+    uint256 amount;
+    bytes32 id;
+}
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+
+abstract contract Base {
+    event Paused(address indexed account);
+
+    function paused() public view virtual returns (bool);
+}
+
+contract Exchange is Base {
+    event OrderPlaced(bytes32 indexed id, Order order) anonymous;
+
+    error InvalidOrder(Order order, string reason);
+
+    IERC20 public token;
+    mapping(bytes32 => Order) public orders;
+    mapping(address => mapping(string => uint256)) public nonces;
+    uint256[] public history;
+
+    constructor(IERC20 token_) {
+        token = token_;
+    }
+
+    function paused() public pure override returns (bool) {
+        return false;
+    }
+
+    function place(Order calldata order) external payable returns (bytes32 id) {
+        if (order.fills.length == 0) revert InvalidOrder(order, "no fills");
+        emit OrderPlaced(id, order);
+    }
+
+    function bestPrice(Side side) public view returns (Price, uint256 count) {}
+
+    function setCallback(function(uint256) external returns (bool) callback) external {}
+
+    function internalHelper() internal {}
+}
+
+library Lib {
+    function f() external {}
+}
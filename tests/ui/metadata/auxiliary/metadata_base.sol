@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+/// @title Token interface
+interface IToken {
+    /// @notice Emitted when tokens are moved.
+    /// @param from The sender.
+    /// @param to The recipient.
+    event Transfer(address indexed from, address indexed to, uint256 value);
+
+    /// @notice Returns the balance of `owner`.
+    /// @dev Reads from storage.
+    /// @param owner The account to query.
+    /// @return balance The balance.
+    function balanceOf(address owner) external view returns (uint256 balance);
+
+    /// @notice Moves tokens.
+    /// @return Whether the transfer succeeded.
+    function transfer(address to, uint256 amount) external returns (bool);
+}
@@ -0,0 +1,45 @@
+//@compile-flags: --emit=metadata --pretty-json --import-map=@aux/=auxiliary/
+// SPDX-License-Identifier: GPL-3.0-or-later OR MIT
+
+import "./auxiliary/metadata_base.sol";
+
+/**
+ * @title A token
+ * @author Solar
+ * @notice A simple token.
+ * @dev Balances are stored
+ * in a mapping.
+ * @custom:security-contact security@example.com
+ */
+contract Token is IToken {
+    /// @notice The token name.
+    /// @dev Set in the constructor.
+    /// @return The name.
+    string public name;
+
+    mapping(address => uint256) internal balances;
+
+    /// @notice The amount is larger than the balance.
+    /// @param available The balance.
+    error InsufficientBalance(uint256 available);
+
+    /// @notice Creates the token.
+    /// @param name_ The token name.
+    constructor(string memory name_) {
+        name = name_;
+    }
+
+    function balanceOf(address owner) external view returns (uint256) {
+        return balances[owner];
+    }
+
+    /// @inheritdoc IToken
+    /// @dev Reverts with `InsufficientBalance`.
+    function transfer(address to, uint256 amount) external returns (bool) {
+        if (balances[msg.sender] < amount) revert InsufficientBalance(balances[msg.sender]);
+        balances[msg.sender] -= amount;
+        balances[to] += amount;
+        emit Transfer(msg.sender, to, amount);
+        return true;
+    }
+}
@@ -0,0 +1,18 @@
+contract A layout at 1 {}
+
+contract B is A layout at 2 ** 8 {}
+
+contract C layout at 0x1234 is A {}
+
+contract D is A, B layout at uint256(keccak256("D")) - 1 {}
+
+contract E layout at 1 layout at 2 {} //~ ERROR: storage layout already specified
+
+// `layout` and `at` are not keywords.
+contract layout {
+    uint256 at;
+
+    function f(uint256 layout) public {
+        at = layout;
+    }
+}
@@ -0,0 +1,3 @@
+import "./auxiliary/udvts.sol"; //~ ERROR: file not found
+import "./auxiliary/missing.sol"; //~ ERROR: file not found
+import "forge-std/Test.sol"; //~ ERROR: file not found
@@ -0,0 +1,21 @@
+import {MyUdvt as Udvt} from "./auxiliary/udvt.sol";
+import {MyUdtv} from "./auxiliary/udvt.sol"; //~ ERROR: declaration `MyUdtv` not found
+import * as Aux from "./auxiliary/udvt.sol";
+
+library Lib {
+    struct Point {
+        uint x;
+    }
+}
+
+contract C {
+    uint balance;
+    Lib.Piont p; //~ ERROR: unresolved symbol
+    Aux.MyUdvtt u; //~ ERROR: unresolved symbol
+
+    function f() public {
+        uint amount = balanse; //~ ERROR: unresolved symbol
+        amount = totalSupply; //~ ERROR: unresolved symbol
+        Udtv.wrap(amount); //~ ERROR: unresolved symbol
+    }
+}
@@ -0,0 +1,22 @@
+contract Invalid {
+    /// @custom:storage-location example.invalid
+    //~^ ERROR: invalid storage location; expected `<formula>:<id>`
+    struct A {
+        uint256 x;
+    }
+
+    /// @custom:storage-location erc7201:example.b
+    /// @custom:storage-location erc7201:example.c
+    //~^ ERROR: storage location already specified
+    struct B {
+        uint256 x;
+    }
+
+    /// @custom:storage-location other:example.d
+    struct D {
+        uint256 x;
+    }
+
+    // Unknown formulas are not checked.
+    bytes32 constant D_LOCATION = 0;
+}
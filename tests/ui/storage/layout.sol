@@ -0,0 +1,37 @@
+//@compile-flags: --emit=storage-layout --pretty-json
+
+contract Base {
+    uint128 a;
+    uint128 b;
+    bool c;
+    address d;
+    uint256 constant CONST = 1;
+    uint256 immutable IMMUTABLE = 2;
+    uint256 transient t;
+}
+
+contract C is Base {
+    struct S {
+        uint8 x;
+        uint256 y;
+        uint8[3] z;
+    }
+
+    enum E {
+        A,
+        B
+    }
+
+    E e;
+    S s;
+    uint64[5] small;
+    uint256[] dyn;
+    string str;
+    mapping(address => mapping(string => S)) nested;
+    bytes32 last;
+}
+
+contract Custom layout at 0x1000 {
+    uint256 x;
+    bool y;
+}
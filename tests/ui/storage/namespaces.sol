@@ -0,0 +1,26 @@
+//@compile-flags: --emit=storage-layout --pretty-json
+
+contract Base {
+    /// @custom:storage-location erc7201:example.main
+    struct MainStorage {
+        uint128 x;
+        uint128 y;
+        mapping(address => uint256) balances;
+    }
+
+    uint256 a;
+}
+
+contract C is Base {
+    /// @custom:storage-location erc7201:example.c
+    struct CStorage {
+        bool flag;
+    }
+
+    // Not namespaced.
+    struct S {
+        uint256 z;
+    }
+
+    address b;
+}
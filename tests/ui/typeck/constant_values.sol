@@ -0,0 +1,17 @@
+uint8 constant OK = 255;
+uint8 constant TOO_BIG = 256; //~ ERROR: evaluation of constant value failed
+int8 constant TOO_SMALL = -129; //~ ERROR: evaluation of constant value failed
+uint constant NEGATIVE = 1 - 2; //~ ERROR: evaluation of constant value failed
+uint constant HALF = 1 / 2; //~ ERROR: evaluation of constant value failed
+uint constant FRACTIONAL_ETHER = 1.5 wei; //~ ERROR: evaluation of constant value failed
+uint constant DIV_ZERO = 1 / 0; //~ ERROR: evaluation of constant value failed
+uint constant OVERFLOW = type(uint256).max + 1; //~ ERROR: evaluation of constant value failed
+uint16 constant OVERFLOW_TYPED = uint16(OK) * 300; //~ ERROR: evaluation of constant value failed
+uint8 constant TRUNCATED = uint8(uint256(300));
+bytes2 constant B2 = "ab";
+bytes2 constant B2_TOO_LONG = "abc"; //~ ERROR: evaluation of constant value failed
+uint constant FROM_CONSTANT = TOO_BIG;
+
+// Unsupported expressions are not checked here.
+bytes32 constant HASH = keccak256("hello");
+uint constant TIMESTAMP = block.timestamp;
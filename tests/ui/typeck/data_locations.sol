@@ -0,0 +1,60 @@
+struct S {
+    uint256 x;
+}
+
+struct WithMapping {
+    mapping(uint256 => uint256) m;
+}
+
+library L {
+    function lib(uint256[] storage a) external {}
+    function libPublic(S storage s) public returns (S storage) {
+        return s;
+    }
+}
+
+contract C {
+    uint256 transient ok;
+    uint256[] transient arr; //~ ERROR: transient storage is not yet implemented for reference types
+    uint256 constant transient CONST = 1; //~ ERROR: transient cannot be used as data location for constant or immutable variables
+
+    mapping(uint256 => uint256) m;
+
+    constructor(uint256[] memory a, S storage s) {} //~ ERROR: data location must be "memory" for constructor parameter, but "storage" was given
+
+    modifier mod(uint256[] storage a) {
+        _;
+    }
+
+    function ext(uint256[] calldata a, bytes memory b) external returns (string memory) {}
+    function extMissing(uint256[] a) external {} //~ ERROR: data location must be "memory" or "calldata" for parameter in external function, but none was given
+    function extStorage(S storage s) external {} //~ ERROR: data location must be "memory" or "calldata" for parameter in external function, but "storage" was given
+    function extReturn() external returns (bytes b) {} //~ ERROR: data location must be "memory" or "calldata" for return parameter in function, but none was given
+    function pub(string calldata s) public {}
+    function pubStorage(string storage s) public {} //~ ERROR: data location must be "memory" or "calldata" for parameter in function, but "storage" was given
+    function int_(S storage s, S memory t, S calldata u) internal returns (S storage) {}
+    function intMissing(S s) internal {} //~ ERROR: data location must be "storage", "memory" or "calldata" for parameter in function, but none was given
+    function valueType(uint256 memory x) public {} //~ ERROR: data location can only be specified for array, struct or mapping types, but "memory" was given
+    function mapping_(mapping(uint256 => uint256) storage x) internal {}
+    function mappingMemory(mapping(uint256 => uint256) memory x) internal {} //~ ERROR: mapping types can only have a data location of "storage" and thus only be parameters or return variables for internal or library functions
+    function nestedMapping(WithMapping memory x) internal {} //~ ERROR: type struct WithMapping is only valid in storage because it contains a (nested) mapping
+
+    function locals() public {
+        uint256[] memory a;
+        uint256[] storage b = arr;
+        bytes c; //~ ERROR: data location must be "storage", "memory" or "calldata" for variable, but none was given
+        uint256 memory d; //~ ERROR: data location can only be specified for array, struct or mapping types, but "memory" was given
+        mapping(uint256 => uint256) memory e; //~ ERROR: mapping types can only have a data location of "storage"
+        for (uint256 i = 0; i < 1; i++) {
+            string f; //~ ERROR: data location must be "storage", "memory" or "calldata" for variable, but none was given
+        }
+        (bytes memory g, string h) = (c, ""); //~ ERROR: data location must be "storage", "memory" or "calldata" for variable, but none was given
+        try this.ext(a, c) returns (string calldata s) { //~ ERROR: data location must be "memory" for parameter in function, but "calldata" was given
+        } catch (bytes memory reason) {
+        }
+    }
+}
+
+abstract contract A {
+    constructor(uint256[] memory a, S storage s) {}
+}
@@ -0,0 +1,38 @@
+//@compile-flags: --emit=hashes --pretty-json
+
+uint constant MAX_LEN = 10;
+uint8 constant SMALL = 200;
+int8 constant NEG = -128;
+uint constant HALF_ETHER = 0.5 ether;
+bytes4 constant B4 = "abcd";
+uint16 constant WIDE = uint16(uint8(255)) + 1;
+
+type U is uint16;
+
+library L {
+    uint constant X = 3;
+}
+
+contract C {
+    function a(uint[(2**8) / 4] memory) public {}
+    function b(uint[MAX_LEN] memory) public {}
+    function c(uint[L.X] memory) public {}
+    function d(uint[type(uint8).max] memory) public {}
+    function e(uint[(7 / 2) * 2] memory) public {}
+    function f(uint[1.5 ether / 1e17] memory) public {}
+    function g(uint[uint8(300 - 200)] memory) public {}
+    function h(uint[uint(int(NEG) + 130)] memory) public {}
+    function i(uint[SMALL + 55] memory) public {}
+    function j(uint[(HALF_ETHER / 1 gwei) / 1e7] memory) public {}
+    function k(uint[uint8(bytes1(B4))] memory) public {}
+    function l(uint[WIDE - 255] memory) public {}
+    function m(uint[MAX_LEN > 5 ? 1 : 2] memory) public {}
+    function n(uint[uint8(uint16(0x1234))] memory) public {}
+    function o(uint[U.unwrap(U.wrap(42))] memory) public {}
+    function p(uint[2.5e1 - (5 / 2) * 2] memory) public {}
+    function q(uint[uint(type(int16).max) + 1 >> 12] memory) public {}
+    function r(uint[~uint8(0xf0)] memory) public {}
+    function s(uint[2 ** 3 ** 2 / 64 - 2 - 1] memory) public {}
+    function t(uint[HALF_ETHER / 1 gwei / 1e7 * 2] memory) public {}
+    function u(uint[6 ^ 3 | 8 & 12] memory) public {}
+}
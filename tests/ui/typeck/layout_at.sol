@@ -0,0 +1,32 @@
+uint256 constant BASE = 0x100;
+
+contract Base layout at BASE {}
+
+contract Derived is Base {} //~ ERROR: storage layout can only be specified in the most derived contract
+
+abstract contract Abstract layout at 1 {} //~ ERROR: storage layout cannot be specified for abstract contracts
+
+interface I layout at 1 {} //~ ERROR: storage layout can only be specified for contracts
+
+library L layout at 1 {} //~ ERROR: storage layout can only be specified for contracts
+
+contract Ok1 layout at BASE * 2 + 1 {
+    uint256 x;
+}
+
+contract Ok2 layout at 2**256 - 1 {
+    uint256 x;
+}
+
+contract Negative layout at 0 - 1 {} //~ ERROR: evaluation of constant value failed
+
+contract TooBig layout at 2**256 {} //~ ERROR: evaluation of constant value failed
+
+contract Fraction layout at 1 / 2 {} //~ ERROR: evaluation of constant value failed
+
+contract NotConstant layout at block.number {} //~ ERROR: the base slot of the storage layout must be a compile-time constant expression
+
+contract PastTheEnd layout at 2**256 - 1 { //~ ERROR: contract extends past the end of storage when this base slot value is specified
+    uint256 x;
+    uint256 y;
+}
@@ -0,0 +1,43 @@
+import * as Aux from "../resolve/auxiliary/udvt.sol";
+
+library Math {
+    function add(uint256 a, uint256 b) internal pure returns (uint256) {
+        return a + b;
+    }
+}
+
+contract Base {
+    function hook() internal virtual {}
+}
+
+contract Other {}
+
+contract C is Base, Other {
+    using Math for uint256;
+
+    struct Position {
+        uint256 amount;
+        uint256[] ids;
+    }
+
+    enum Side { Buy, Sell }
+
+    uint256 public totalSupply;
+    Position position;
+
+    function hook() internal override {
+        super.hok(); //~ ERROR: member `hok` not found
+    }
+
+    function f(uint256 a) public {
+        position.amout = a; //~ ERROR: member `amout` not found
+        position.ids.lenght; //~ ERROR: member `lenght` not found
+        a.add(1);
+        Side.Sel; //~ ERROR: member `Sel` not found
+        this.totalSuply(); //~ ERROR: member `totalSuply` not found
+        msg.sendr; //~ ERROR: member `sendr` not found
+        address(this).balance;
+        Aux.MyUdvtt; //~ ERROR: member `MyUdvtt` not found
+        C.Positon; //~ ERROR: member `Positon` not found
+    }
+}
@@ -0,0 +1,20 @@
+//@compile-flags: --proxy Proxy=Implementation
+
+contract Proxy {
+    address public admin; //~ WARN: implementation function `admin()` is shadowed by the proxy
+
+    function upgradeTo(address) external {}
+
+    function collate_propagate_storage(bytes16) external {}
+    //~^ WARN: proxy function `collate_propagate_storage(bytes16)` and implementation function `burn(uint256)` produce the same 4-byte selector `0x42966c68`
+
+    fallback() external payable {}
+}
+
+contract Implementation {
+    address public admin;
+
+    function burn(uint256) external {}
+
+    function transfer(address, uint256) external {}
+}
@@ -0,0 +1,45 @@
+/// @custom:storage-location erc7201:example.main
+struct FreeStorage {
+    uint256 x;
+}
+
+bytes32 constant FREE_STORAGE_LOCATION = 0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500;
+
+contract Example {
+    /// @custom:storage-location erc7201:example.main
+    struct MainStorage {
+        uint256 x;
+        uint256 y;
+    }
+
+    /// @custom:storage-location erc7201:example.other
+    struct OtherStorage {
+        uint256 z;
+    }
+
+    // keccak256(abi.encode(uint256(keccak256("example.main")) - 1)) & ~bytes32(uint256(0xff))
+    bytes32 private constant MAIN_STORAGE_LOCATION =
+        0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500;
+
+    bytes32 private constant OtherStorageLocation =
+        0x183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500; //~ ERROR: storage location constant does not match the namespace of struct `OtherStorage`
+
+    // Not a location constant of either struct.
+    bytes32 private constant UNRELATED_LOCATION = bytes32(0);
+}
+
+contract Single {
+    /**
+     * @dev Only namespaced struct in this contract.
+     * @custom:storage-location erc7201:example.single
+     */
+    struct Layout {
+        uint256 x;
+    }
+
+    bytes32 internal constant STORAGE_LOCATION = bytes32(uint256(1)); //~ ERROR: storage location constant does not match the namespace of struct `Layout`
+
+    // Unsupported initializers are skipped.
+    bytes32 internal constant OTHER_LOCATION =
+        keccak256(abi.encode(uint256(keccak256("example.single")) - 1)) & ~bytes32(uint256(0xff));
+}